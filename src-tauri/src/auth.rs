@@ -1,8 +1,15 @@
 use reqwest::Client;
+use std::collections::{HashMap, HashSet};
+use tauri::{Emitter, Manager, Window};
 
+use crate::http::{self, SupabaseError};
 
-pub async fn validate_service_key(project_url: &str, service_key: &str) -> Result<String, String> {
-    let client = Client::new();
+pub async fn validate_service_key(
+    client: &Client,
+    limits: &http::RateLimits,
+    project_url: &str,
+    service_key: &str,
+) -> Result<String, String> {
     // We check /storage/v1/bucket because we specifically need Storage Admin rights
     // and it's a good proxy for "Service Role" validity.
     let url = format!("{}/storage/v1/bucket", project_url);
@@ -13,15 +20,512 @@ pub async fn validate_service_key(project_url: &str, service_key: &str) -> Resul
         .header("apikey", service_key)
         .send()
         .await
+        .inspect(|r| limits.observe(r))
         .map_err(|e| format!("Network request failed: {}", e))?;
 
     if res.status().is_success() {
         // We could verify we get a list back, but 200 OK is sufficient proof of auth
         Ok("Key Validated: Storage Admin Access Confirmed".to_string())
     } else {
-        // Try to parse error message
-        let status = res.status();
-        let body = res.text().await.unwrap_or_default();
-        Err(format!("Validation Failed (Status {}): {}", status, body))
+        match SupabaseError::from_response(res).await {
+            // Not a key problem; say so up front rather than "Validation Failed"
+            e if e.is_project_paused() => Err(e.to_string()),
+            e => Err(format!("Validation Failed: {}", e)),
+        }
+    }
+}
+
+/// What a key is allowed to do, discovered up front so the UI can disable
+/// features instead of failing mid-migration with a 403
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ConnectionCapabilities {
+    pub project_ref: Option<String>,
+    pub role: Option<String>,
+    pub expires_at: Option<u64>, // unix seconds
+    pub can_read_storage: bool,
+    pub can_write_storage: bool,
+    pub can_admin_auth: bool,
+    pub management_api_available: bool,
+}
+
+/// Decodes the claims of a Supabase JWT key. The signature is NOT verified;
+/// this only reads what the key claims to be.
+pub fn decode_jwt_claims(key: &str) -> Option<serde_json::Value> {
+    use base64::Engine;
+    let payload = key.split('.').nth(1)?;
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+async fn probe(client: &Client, limits: &http::RateLimits, url: &str, service_key: &str) -> bool {
+    client
+        .get(url)
+        .header("Authorization", format!("Bearer {}", service_key))
+        .header("apikey", service_key)
+        .send()
+        .await
+        .inspect(|r| limits.observe(r))
+        .map(|r| r.status().is_success())
+        .unwrap_or(false)
+}
+
+pub async fn probe_capabilities(
+    client: &Client,
+    limits: &http::RateLimits,
+    project_url: &str,
+    service_key: &str,
+) -> ConnectionCapabilities {
+    let claims = decode_jwt_claims(service_key);
+    let claim = |name: &str| claims.as_ref().and_then(|c| c[name].as_str().map(|s| s.to_string()));
+
+    let project_ref = claim("ref").or_else(|| crate::functions::extract_project_ref(project_url).ok());
+    let role = claim("role");
+    let expires_at = claims.as_ref().and_then(|c| c["exp"].as_u64());
+
+    let can_read_storage = probe(client, limits, &format!("{}/storage/v1/bucket", project_url), service_key).await;
+    let can_admin_auth = probe(
+        client,
+        limits,
+        &format!("{}/auth/v1/admin/users?page=1&per_page=1", project_url),
+        service_key,
+    )
+    .await;
+    let management_api_available = match &project_ref {
+        Some(r) => probe(client, limits, &format!("https://api.supabase.com/v1/projects/{}/functions", r), service_key).await,
+        None => false,
+    };
+
+    // A write probe would leave objects behind; the service role bypasses
+    // storage RLS, so write access is inferred from role + read access
+    let can_write_storage = can_read_storage && role.as_deref() == Some("service_role");
+
+    ConnectionCapabilities {
+        project_ref,
+        role,
+        expires_at,
+        can_read_storage,
+        can_write_storage,
+        can_admin_auth,
+        management_api_available,
+    }
+}
+
+/// One row of the source-vs-destination readiness grid
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CapabilityRow {
+    pub feature: String, // storage_read, storage_write, auth_admin, management, db_connect
+    pub source: bool,
+    pub dest: bool,
+}
+
+/// Lines up both projects' probed capabilities (plus database reachability) feature by feature
+pub fn capability_matrix(
+    source: &ConnectionCapabilities,
+    dest: &ConnectionCapabilities,
+    db_connect: (bool, bool),
+) -> Vec<CapabilityRow> {
+    let row = |feature: &str, source: bool, dest: bool| CapabilityRow {
+        feature: feature.to_string(),
+        source,
+        dest,
+    };
+    vec![
+        row("storage_read", source.can_read_storage, dest.can_read_storage),
+        row("storage_write", source.can_write_storage, dest.can_write_storage),
+        row("auth_admin", source.can_admin_auth, dest.can_admin_auth),
+        row("management", source.management_api_available, dest.management_api_available),
+        row("db_connect", db_connect.0, db_connect.1),
+    ]
+}
+
+const USERS_PER_PAGE: usize = 100;
+
+/// What to do when a source user's email already exists on the destination
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictPolicy {
+    Skip,
+    OverwriteMetadata,
+    Error,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct UserConflict {
+    pub email: String,
+    pub source_id: String,
+    pub reason: String,
+}
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct ImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub conflicts: Vec<UserConflict>,
+}
+
+/// Fetches one page of users through the GoTrue admin API
+async fn list_users_page(
+    client: &Client,
+    limits: &http::RateLimits,
+    project_url: &str,
+    service_key: &str,
+    page: usize,
+) -> Result<Vec<serde_json::Value>, String> {
+    let url = format!(
+        "{}/auth/v1/admin/users?page={}&per_page={}",
+        project_url, page, USERS_PER_PAGE
+    );
+    let res = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", service_key))
+        .header("apikey", service_key)
+        .send()
+        .await
+        .inspect(|r| limits.observe(r))
+        .map_err(|e| format!("Network request failed: {}", e))?;
+
+    if !res.status().is_success() {
+        return Err(format!("Failed to list users: {}", SupabaseError::from_response(res).await));
+    }
+
+    let body: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
+    Ok(body["users"].as_array().cloned().unwrap_or_default())
+}
+
+/// Lists every user in a project, following pages until a short one
+async fn list_all_users(
+    client: &Client,
+    limits: &http::RateLimits,
+    project_url: &str,
+    service_key: &str,
+) -> Result<Vec<serde_json::Value>, String> {
+    let mut users = vec![];
+    for page in 1.. {
+        let batch = list_users_page(client, limits, project_url, service_key, page).await?;
+        let last_page = batch.len() < USERS_PER_PAGE;
+        users.extend(batch);
+        if last_page {
+            break;
+        }
+    }
+    Ok(users)
+}
+
+/// Destination users keyed by email, and by phone for phone-only accounts.
+/// Users with neither are never matched, so they can't collide on "".
+#[derive(Default)]
+struct DestUserIndex {
+    by_email: HashMap<String, String>,
+    by_phone: HashMap<String, String>,
+}
+
+impl DestUserIndex {
+    fn email_of(user: &serde_json::Value) -> Option<String> {
+        user["email"]
+            .as_str()
+            .map(|e| e.trim().to_lowercase())
+            .filter(|e| !e.is_empty())
+    }
+
+    /// GoTrue stores phones as digits; tolerate "+" and separators from older exports
+    fn phone_of(user: &serde_json::Value) -> Option<String> {
+        user["phone"]
+            .as_str()
+            .map(|p| p.chars().filter(|c| c.is_ascii_digit()).collect::<String>())
+            .filter(|p| !p.is_empty())
+    }
+
+    fn insert(&mut self, user: &serde_json::Value, id: &str) {
+        if let Some(email) = Self::email_of(user) {
+            self.by_email.insert(email, id.to_string());
+        }
+        if let Some(phone) = Self::phone_of(user) {
+            self.by_phone.insert(phone, id.to_string());
+        }
+    }
+
+    /// Destination id and what matched ("Email" or "Phone")
+    fn find(&self, user: &serde_json::Value) -> Option<(&String, &'static str)> {
+        match Self::email_of(user) {
+            Some(email) => self.by_email.get(&email).map(|id| (id, "Email")),
+            None => Self::phone_of(user)
+                .and_then(|phone| self.by_phone.get(&phone))
+                .map(|id| (id, "Phone")),
+        }
+    }
+}
+
+/// Copies auth users source -> destination. Safe to re-run: users already
+/// present on the destination (matched by email, or phone for phone-only
+/// users) are handled per `policy`.
+pub async fn import_users(
+    window: &Window,
+    client: &Client,
+    source_url: &str,
+    source_key: &str,
+    dest_url: &str,
+    dest_key: &str,
+    policy: ConflictPolicy,
+) -> Result<ImportSummary, String> {
+    let log = |line: String| window.emit("log", line).unwrap();
+    let limits = http::rate_limits(window.app_handle());
+    copy_users(&log, client, &limits, source_url, source_key, dest_url, dest_key, policy).await
+}
+
+/// `import_users` without the window: progress goes to `log`
+#[allow(clippy::too_many_arguments)]
+async fn copy_users(
+    log: &(dyn Fn(String) + Sync),
+    client: &Client,
+    limits: &http::RateLimits,
+    source_url: &str,
+    source_key: &str,
+    dest_url: &str,
+    dest_key: &str,
+    policy: ConflictPolicy,
+) -> Result<ImportSummary, String> {
+    log("Indexing destination users...".to_string());
+    let existing = list_all_users(client, limits, dest_url, dest_key).await?;
+    let mut dest_users = DestUserIndex::default();
+    let mut dest_ids: HashSet<String> = HashSet::new();
+    for user in &existing {
+        let id = user["id"].as_str().unwrap_or_default().to_string();
+        dest_users.insert(user, &id);
+        dest_ids.insert(id);
+    }
+
+    let mut summary = ImportSummary::default();
+    for page in 1.. {
+        let batch = list_users_page(client, limits, source_url, source_key, page).await?;
+        let last_page = batch.len() < USERS_PER_PAGE;
+
+        for user in &batch {
+            let source_id = user["id"].as_str().unwrap_or_default().to_string();
+            let email = user["email"].as_str().unwrap_or_default().to_string();
+
+            if let Some((dest_id, matched_on)) = dest_users.find(user) {
+                match policy {
+                    ConflictPolicy::Skip => summary.skipped += 1,
+                    ConflictPolicy::OverwriteMetadata => {
+                        update_user_metadata(client, limits, dest_url, dest_key, dest_id, user).await?;
+                        summary.updated += 1;
+                    }
+                    ConflictPolicy::Error => summary.conflicts.push(UserConflict {
+                        email,
+                        source_id,
+                        reason: format!("{} already exists on destination", matched_on),
+                    }),
+                }
+                continue;
+            }
+
+            // Preserve the id unless another destination user already holds it
+            let keep_id = !dest_ids.contains(&source_id);
+            match create_user(client, limits, dest_url, dest_key, user, keep_id).await {
+                Ok(new_id) => {
+                    dest_users.insert(user, &new_id);
+                    dest_ids.insert(new_id);
+                    summary.created += 1;
+                }
+                Err(e) => summary.conflicts.push(UserConflict {
+                    email,
+                    source_id,
+                    reason: e,
+                }),
+            }
+        }
+
+        log(format!(
+            "Auth import page {}: {} created, {} updated, {} skipped, {} conflicts",
+            page,
+            summary.created,
+            summary.updated,
+            summary.skipped,
+            summary.conflicts.len()
+        ));
+
+        if last_page {
+            break;
+        }
+    }
+
+    Ok(summary)
+}
+
+async fn create_user(
+    client: &Client,
+    limits: &http::RateLimits,
+    project_url: &str,
+    service_key: &str,
+    user: &serde_json::Value,
+    keep_id: bool,
+) -> Result<String, String> {
+    let mut body = serde_json::json!({
+        "email": user["email"],
+        "phone": user["phone"],
+        "email_confirm": !user["email_confirmed_at"].is_null(),
+        "phone_confirm": !user["phone_confirmed_at"].is_null(),
+        "user_metadata": user["user_metadata"],
+        "app_metadata": user["app_metadata"],
+    });
+    if keep_id {
+        body["id"] = user["id"].clone();
+    }
+    if let Some(hash) = user["encrypted_password"].as_str().filter(|h| !h.is_empty()) {
+        body["password_hash"] = serde_json::Value::String(hash.to_string());
+    }
+
+    let res = client
+        .post(format!("{}/auth/v1/admin/users", project_url))
+        .header("Authorization", format!("Bearer {}", service_key))
+        .header("apikey", service_key)
+        .json(&body)
+        .send()
+        .await
+        .inspect(|r| limits.observe(r))
+        .map_err(|e| format!("Network request failed: {}", e))?;
+
+    if !res.status().is_success() {
+        return Err(format!("Create failed: {}", SupabaseError::from_response(res).await));
+    }
+
+    let created: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
+    Ok(created["id"].as_str().unwrap_or_default().to_string())
+}
+
+async fn update_user_metadata(
+    client: &Client,
+    limits: &http::RateLimits,
+    project_url: &str,
+    service_key: &str,
+    dest_id: &str,
+    user: &serde_json::Value,
+) -> Result<(), String> {
+    let body = serde_json::json!({
+        "user_metadata": user["user_metadata"],
+        "app_metadata": user["app_metadata"],
+    });
+
+    let res = client
+        .put(format!("{}/auth/v1/admin/users/{}", project_url, dest_id))
+        .header("Authorization", format!("Bearer {}", service_key))
+        .header("apikey", service_key)
+        .json(&body)
+        .send()
+        .await
+        .inspect(|r| limits.observe(r))
+        .map_err(|e| format!("Network request failed: {}", e))?;
+
+    if !res.status().is_success() {
+        return Err(format!("Metadata update failed: {}", SupabaseError::from_response(res).await));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockServer, Reply};
+    use serde_json::json;
+
+    #[test]
+    fn phone_only_users_do_not_collide() {
+        let mut index = DestUserIndex::default();
+        index.insert(&json!({ "id": "a", "email": "", "phone": "15550001111" }), "a");
+        index.insert(&json!({ "id": "b", "email": null, "phone": "15550002222" }), "b");
+
+        let other = json!({ "email": "", "phone": "15550003333" });
+        assert!(index.find(&other).is_none());
+
+        let same = json!({ "email": "", "phone": "+1 555 000 2222" });
+        assert_eq!(index.find(&same), Some((&"b".to_string(), "Phone")));
+    }
+
+    #[test]
+    fn email_match_is_case_insensitive() {
+        let mut index = DestUserIndex::default();
+        index.insert(&json!({ "email": "Ada@Example.com", "phone": "" }), "a");
+
+        let user = json!({ "email": "ada@example.com" });
+        assert_eq!(index.find(&user), Some((&"a".to_string(), "Email")));
+    }
+
+    #[test]
+    fn users_without_email_or_phone_never_match() {
+        let mut index = DestUserIndex::default();
+        index.insert(&json!({ "email": "", "phone": "" }), "a");
+
+        assert!(index.find(&json!({ "email": "", "phone": "" })).is_none());
+    }
+
+    /// Source users: one already on the destination by email, one new, one
+    /// whose id another destination user holds, and one the destination rejects
+    async fn import_with(policy: ConflictPolicy) -> (ImportSummary, MockServer) {
+        let source = MockServer::start(|_| {
+            Reply::json(200, json!({ "users": [
+                { "id": "s-ada", "email": "ADA@example.com", "user_metadata": { "plan": "pro" }, "app_metadata": {} },
+                { "id": "s-new", "email": "new@example.com" },
+                { "id": "taken", "email": "clash@example.com" },
+                { "id": "s-bad", "email": "bad@example.com" },
+            ] }))
+        })
+        .await;
+        let dest = MockServer::start(|request| match request.method.as_str() {
+            "GET" => Reply::json(200, json!({ "users": [
+                { "id": "d-ada", "email": "ada@example.com" },
+                { "id": "taken", "email": "someone@example.com" },
+            ] })),
+            "POST" if request.json()["email"] == "bad@example.com" => {
+                Reply::json(422, json!({ "code": 422, "msg": "Invalid email" }))
+            }
+            "POST" => {
+                let id = request.json()["id"].as_str().unwrap_or("generated").to_string();
+                Reply::json(200, json!({ "id": id }))
+            }
+            _ => Reply::json(200, json!({})),
+        })
+        .await;
+        let limits = http::RateLimits::default();
+        let summary = copy_users(&|_| {}, &Client::new(), &limits, &source.url, "key", &dest.url, "key", policy)
+            .await
+            .unwrap();
+        (summary, dest)
+    }
+
+    fn reasons(summary: &ImportSummary) -> Vec<(&str, &str)> {
+        summary.conflicts.iter().map(|c| (c.email.as_str(), c.reason.as_str())).collect()
+    }
+
+    #[tokio::test]
+    async fn import_handles_each_policy_and_id_clash() {
+        let (skip, dest) = import_with(ConflictPolicy::Skip).await;
+        assert_eq!((skip.created, skip.updated, skip.skipped), (2, 0, 1));
+        let rejected = reasons(&skip);
+        assert_eq!(rejected.len(), 1);
+        assert!(rejected[0].0 == "bad@example.com" && rejected[0].1.contains("Invalid email"), "{:?}", rejected);
+        let created: Vec<_> = dest.requests().into_iter().filter(|r| r.method == "POST").map(|r| r.json()).collect();
+        let new = created.iter().find(|b| b["email"] == "new@example.com").unwrap();
+        assert_eq!(new["id"], "s-new");
+        // The destination already has a user with this id, so it gets a fresh one
+        let clash = created.iter().find(|b| b["email"] == "clash@example.com").unwrap();
+        assert!(clash.get("id").is_none(), "{}", clash);
+        assert_eq!(dest.count("PUT", "/auth/v1/admin/users/"), 0);
+
+        let (overwrite, dest) = import_with(ConflictPolicy::OverwriteMetadata).await;
+        assert_eq!((overwrite.created, overwrite.updated, overwrite.skipped, overwrite.conflicts.len()), (2, 1, 0, 1));
+        let updates: Vec<_> = dest.requests().into_iter().filter(|r| r.method == "PUT").collect();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].path, "/auth/v1/admin/users/d-ada");
+        assert_eq!(updates[0].json()["user_metadata"], json!({ "plan": "pro" }));
+
+        let (error, dest) = import_with(ConflictPolicy::Error).await;
+        assert_eq!((error.created, error.updated, error.skipped), (2, 0, 0));
+        let conflicts = reasons(&error);
+        assert_eq!(conflicts[0], ("ADA@example.com", "Email already exists on destination"));
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(dest.count("POST", "/auth/v1/admin/users"), 3);
     }
 }
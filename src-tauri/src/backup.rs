@@ -0,0 +1,96 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use crate::profiles::Profile;
+use crate::{db, http, paths, report, storage};
+
+/// Archives for one profile live in userdata/backups/<profile_id>/
+pub fn profile_backup_dir(app: &AppHandle, profile_id: &str) -> PathBuf {
+    paths::get_userdata_dir(app).join("backups").join(profile_id)
+}
+
+/// Writes backup-<unix secs>.tar.gz with `database.sql` (when the profile has
+/// a db_url) and every source object under `storage/<bucket>/<key>`
+pub async fn run_profile_backup(app: &AppHandle, profile: &Profile) -> Result<PathBuf, String> {
+    let dir = profile_backup_dir(app, &profile.id);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let archive_path = dir.join(format!("backup-{}.tar.gz", report::now_secs()));
+    // Written under a .part name so an interrupted run never looks like a finished archive
+    let part_path = archive_path.with_extension("gz.part");
+
+    let file = File::create(&part_path).map_err(|e| e.to_string())?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let result = async {
+        if let Some(conn) = &profile.db_url {
+            let _ = app.emit("log", format!("Backup [{}]: dumping database...", profile.name));
+            let dump = db::dump_database(app, conn).await?;
+            append(&mut archive, "database.sql", &dump)?;
+        }
+
+        let _ = app.emit("log", format!("Backup [{}]: copying storage...", profile.name));
+        let mirror = storage::StorageMirror::new(
+            http::client_for_app(app),
+            &profile.source_url, &profile.source_key, "", ""
+        ).with_rate_limits(http::rate_limits(app));
+        let buckets = mirror.list_source_buckets().await?;
+        let manifest = serde_json::json!({
+            "buckets": buckets,
+            "manual_steps": [storage::UNMIRRORED_BUCKET_SETTINGS],
+        });
+        append(&mut archive, "storage/buckets.json", &serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?)?;
+        for bucket in buckets {
+            for object in mirror.list_objects(&bucket.id).await? {
+                let data = mirror.download_object(&bucket.id, &object.name).await?;
+                append(&mut archive, &format!("storage/{}/{}", bucket.id, object.name), &data)?;
+            }
+        }
+
+        let encoder = archive.into_inner().map_err(|e| e.to_string())?;
+        encoder.finish().map_err(|e| e.to_string())?;
+        Ok::<(), String>(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&part_path);
+        return Err(e);
+    }
+    fs::rename(&part_path, &archive_path).map_err(|e| e.to_string())?;
+    Ok(archive_path)
+}
+
+fn append(archive: &mut tar::Builder<GzEncoder<File>>, path: &str, data: &[u8]) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(report::now_secs());
+    header.set_cksum();
+    archive
+        .append_data(&mut header, path, data)
+        .map_err(|e| format!("Cannot add {} to backup: {}", path, e))
+}
+
+/// Deletes all but the newest `keep` archives in `dir`. Returns how many were removed.
+pub fn prune_backups(dir: &Path, keep: usize) -> Result<usize, String> {
+    let mut archives: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy())
+                .is_some_and(|n| n.starts_with("backup-") && n.ends_with(".tar.gz"))
+        })
+        .collect();
+
+    // backup-<secs> names sort chronologically while the timestamps have equal width
+    archives.sort();
+    let excess = archives.len().saturating_sub(keep);
+    for path in &archives[..excess] {
+        fs::remove_file(path).map_err(|e| e.to_string())?;
+    }
+    Ok(excess)
+}
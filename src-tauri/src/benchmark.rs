@@ -0,0 +1,303 @@
+use std::time::Instant;
+use tauri::{Emitter, Window};
+
+use crate::report::now_secs;
+use crate::storage::StorageMirror;
+
+const MB: f64 = 1024.0 * 1024.0;
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct TransferBenchmark {
+    pub sample_bytes: u64,
+    pub latency_ms: u64,
+    pub upload_mb_per_sec: f64,
+    pub download_mb_per_sec: f64,
+    /// ETA basis: seconds to copy 1 GB (download + upload) at measured rates
+    pub seconds_per_gb: f64,
+}
+
+fn mb_per_sec(bytes: u64, started: Instant) -> f64 {
+    let secs = started.elapsed().as_secs_f64().max(0.001);
+    bytes as f64 / MB / secs
+}
+
+/// Round-trips a synthetic object through a temp destination bucket.
+/// The temp bucket and object are removed even when a step fails.
+pub async fn benchmark_transfer(window: &Window, mirror: &StorageMirror, sample_bytes: u64) -> Result<TransferBenchmark, String> {
+    let bucket_id = format!("devpulse-benchmark-{}", now_secs());
+    let object_name = "sample.bin";
+
+    window.emit("log", format!("Benchmark: creating temp bucket {}", bucket_id)).unwrap();
+    let started = Instant::now();
+    mirror.create_dest_bucket(&bucket_id, false).await?;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let result = run(window, mirror, &bucket_id, object_name, sample_bytes, latency_ms).await;
+
+    window.emit("log", "Benchmark: cleaning up...").unwrap();
+    if let Err(e) = mirror.delete_dest_objects(&bucket_id, &[object_name.to_string()]).await {
+        window.emit("log", format!("Benchmark cleanup (object) failed: {}", e)).unwrap();
+    }
+    if let Err(e) = mirror.delete_dest_bucket(&bucket_id).await {
+        window.emit("log", format!("Benchmark cleanup (bucket) failed: {}", e)).unwrap();
+    }
+
+    result
+}
+
+async fn run(
+    window: &Window,
+    mirror: &StorageMirror,
+    bucket_id: &str,
+    object_name: &str,
+    sample_bytes: u64,
+    latency_ms: u64,
+) -> Result<TransferBenchmark, String> {
+    // Pseudo-random payload so compression along the path can't flatter the numbers
+    let payload: Vec<u8> = (0..sample_bytes)
+        .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+        .collect();
+
+    window
+        .emit("log", format!("Benchmark: uploading {:.2} MB...", sample_bytes as f64 / MB))
+        .unwrap();
+    let started = Instant::now();
+    mirror
+        .upload_object(bucket_id, object_name, payload, "application/octet-stream")
+        .await?;
+    let upload_mb_per_sec = mb_per_sec(sample_bytes, started);
+
+    window.emit("log", "Benchmark: downloading...").unwrap();
+    let started = Instant::now();
+    let data = mirror.download_dest_object(bucket_id, object_name).await?;
+    let download_mb_per_sec = mb_per_sec(data.len() as u64, started);
+
+    if data.len() as u64 != sample_bytes {
+        return Err(format!(
+            "Benchmark read-back size mismatch: {} of {} bytes",
+            data.len(),
+            sample_bytes
+        ));
+    }
+
+    let seconds_per_gb = 1024.0 / download_mb_per_sec + 1024.0 / upload_mb_per_sec;
+    window
+        .emit(
+            "log",
+            format!(
+                "Benchmark: up {:.2} MB/s, down {:.2} MB/s, latency {} ms",
+                upload_mb_per_sec, download_mb_per_sec, latency_ms
+            ),
+        )
+        .unwrap();
+
+    Ok(TransferBenchmark {
+        sample_bytes,
+        latency_ms,
+        upload_mb_per_sec,
+        download_mb_per_sec,
+        seconds_per_gb,
+    })
+}
+
+/// Result of `dest_roundtrip_test`
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RoundtripResult {
+    pub ok: bool,
+    pub upload_ms: u64,
+    pub download_ms: u64,
+    pub error: Option<String>,
+}
+
+const ROUNDTRIP_PAYLOAD: &[u8] = b"devpulse destination round-trip check";
+
+/// Writes a known payload to the destination, reads it back and compares the
+/// bytes. Uses a temp bucket, falling back to an existing bucket when bucket
+/// creation isn't allowed. The object (and temp bucket) are removed even on failure.
+pub async fn dest_roundtrip(window: &Window, mirror: &StorageMirror) -> RoundtripResult {
+    let log = |msg: String| window.emit("log", msg).unwrap();
+    let mut result = RoundtripResult {
+        ok: false,
+        upload_ms: 0,
+        download_ms: 0,
+        error: None,
+    };
+
+    let temp_bucket = format!("devpulse-roundtrip-{}", now_secs());
+    log(format!("Round-trip: creating temp bucket {}", temp_bucket));
+    let (bucket_id, created) = match mirror.create_dest_bucket(&temp_bucket, false).await {
+        Ok(()) => (temp_bucket, true),
+        Err(e) => {
+            log(format!("Round-trip: cannot create temp bucket ({}), looking for an existing one", e));
+            match mirror.list_dest_buckets().await.map(|b| b.into_iter().next()) {
+                Ok(Some(bucket)) => (bucket.id, false),
+                Ok(None) => {
+                    result.error = Some(format!("No bucket available for the round-trip: {}", e));
+                    return result;
+                }
+                Err(e) => {
+                    result.error = Some(e);
+                    return result;
+                }
+            }
+        }
+    };
+    let object_name = format!(".devpulse-roundtrip-{}", now_secs());
+
+    log(format!("Round-trip: uploading {} bytes to {}/{}", ROUNDTRIP_PAYLOAD.len(), bucket_id, object_name));
+    let started = Instant::now();
+    let outcome = match mirror
+        .upload_object(&bucket_id, &object_name, ROUNDTRIP_PAYLOAD.to_vec(), "application/octet-stream")
+        .await
+    {
+        Ok(()) => {
+            result.upload_ms = started.elapsed().as_millis() as u64;
+            log("Round-trip: downloading...".to_string());
+            let started = Instant::now();
+            match mirror.download_dest_object(&bucket_id, &object_name).await {
+                Ok(data) => {
+                    result.download_ms = started.elapsed().as_millis() as u64;
+                    if data == ROUNDTRIP_PAYLOAD {
+                        log("Round-trip: read-back matches".to_string());
+                        Ok(())
+                    } else {
+                        Err(format!(
+                            "Read-back mismatch: got {} bytes, expected {}",
+                            data.len(),
+                            ROUNDTRIP_PAYLOAD.len()
+                        ))
+                    }
+                }
+                Err(e) => Err(format!("Download failed: {}", e)),
+            }
+        }
+        Err(e) => Err(format!("Upload failed: {}", e)),
+    };
+
+    log("Round-trip: cleaning up...".to_string());
+    if let Err(e) = mirror.delete_dest_objects(&bucket_id, &[object_name]).await {
+        log(format!("Round-trip cleanup (object) failed: {}", e));
+    }
+    if created {
+        if let Err(e) = mirror.delete_dest_bucket(&bucket_id).await {
+            log(format!("Round-trip cleanup (bucket) failed: {}", e));
+        }
+    }
+
+    match outcome {
+        Ok(()) => {
+            result.ok = true;
+            log(format!("Round-trip OK: upload {} ms, download {} ms", result.upload_ms, result.download_ms));
+        }
+        Err(e) => {
+            log(format!("Round-trip FAILED: {}", e));
+            result.error = Some(e);
+        }
+    }
+    result
+}
+
+/// One phase of a smoke test
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SmokePhase {
+    pub phase: String, // SETUP, MIGRATE, VERIFY, CLEANUP
+    pub ok: bool,
+    pub ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SmokeTestResult {
+    pub passed: bool,
+    pub phases: Vec<SmokePhase>,
+}
+
+/// At the bucket root, so MIGRATE finds it whatever the listing depth
+const SMOKE_OBJECT: &str = "smoke-fixture.txt";
+const SMOKE_PAYLOAD: &[u8] = b"devpulse end-to-end smoke test fixture";
+
+/// Runs the whole pipeline on a one-object fixture: a temp source bucket is
+/// seeded, mirrored and copied to the destination, read back and compared,
+/// then every temp bucket and object is removed from both projects.
+/// `source` and `dest` write to one project each; `mirror` copies between them.
+pub async fn smoke_test(window: &Window, source: &StorageMirror, dest: &StorageMirror, mirror: &StorageMirror) -> SmokeTestResult {
+    let log = |msg: String| window.emit("log", msg).unwrap();
+    let bucket_id = format!("devpulse-smoke-{}", now_secs());
+    let mut phases = vec![];
+    let mut phase = |name: &str, started: Instant, outcome: Result<(), String>| {
+        let ok = outcome.is_ok();
+        log(format!("Smoke test: {} {}", name, if ok { "OK" } else { "FAILED" }));
+        phases.push(SmokePhase {
+            phase: name.to_string(),
+            ok,
+            ms: started.elapsed().as_millis() as u64,
+            error: outcome.err(),
+        });
+        ok
+    };
+
+    log(format!("Smoke test: seeding temp bucket {} on the source", bucket_id));
+    let started = Instant::now();
+    let (mut source_created, mut dest_created) = (false, false);
+    let setup = async {
+        source.create_dest_bucket(&bucket_id, false).await?;
+        source_created = true;
+        source.upload_object(&bucket_id, SMOKE_OBJECT, SMOKE_PAYLOAD.to_vec(), "text/plain").await
+    }
+    .await;
+    let mut passed = phase("SETUP", started, setup);
+
+    if passed {
+        let started = Instant::now();
+        let migrate = async {
+            let bucket = mirror
+                .list_source_buckets()
+                .await?
+                .into_iter()
+                .find(|b| b.id == bucket_id)
+                .ok_or("Temp bucket not visible on the source")?;
+            dest_created = mirror.mirror_bucket(&bucket, false).await?;
+            let objects = mirror.list_objects(&bucket_id).await?;
+            if !objects.iter().any(|o| o.name == SMOKE_OBJECT) {
+                return Err(format!("Fixture {} not listed on the source", SMOKE_OBJECT));
+            }
+            for object in &objects {
+                mirror.transfer_object(&bucket_id, object).await?;
+            }
+            Ok(())
+        }
+        .await;
+        passed = phase("MIGRATE", started, migrate);
+    }
+
+    if passed {
+        let started = Instant::now();
+        let verify = match mirror.download_dest_object(&bucket_id, SMOKE_OBJECT).await {
+            Ok(data) if data == SMOKE_PAYLOAD => Ok(()),
+            Ok(data) => Err(format!("Destination copy has {} bytes, expected {}", data.len(), SMOKE_PAYLOAD.len())),
+            Err(e) => Err(e),
+        };
+        passed = phase("VERIFY", started, verify);
+    }
+
+    // Always runs, whatever failed above
+    let started = Instant::now();
+    let mut errors = vec![];
+    for (side, project, created) in [("source", source, source_created), ("destination", dest, dest_created)] {
+        if !created {
+            continue;
+        }
+        let cleanup = async {
+            project.delete_dest_objects(&bucket_id, &[SMOKE_OBJECT.to_string()]).await?;
+            project.delete_dest_bucket(&bucket_id).await
+        };
+        if let Err(e) = cleanup.await {
+            errors.push(format!("{}: {}", side, e));
+        }
+    }
+    let cleanup = if errors.is_empty() { Ok(()) } else { Err(errors.join("; ")) };
+    passed = phase("CLEANUP", started, cleanup) && passed;
+
+    log(format!("Smoke test {}", if passed { "PASSED" } else { "FAILED" }));
+    SmokeTestResult { passed, phases }
+}
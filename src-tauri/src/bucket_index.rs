@@ -0,0 +1,247 @@
+use futures::StreamExt;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager, Window};
+
+use crate::{deps, functions, http, paths, report, storage};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct IndexEntry {
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Content hashes of one bucket in one project
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct BucketIndex {
+    pub project: String,
+    pub bucket_id: String,
+    pub indexed_at: u64, // unix seconds
+    pub objects: BTreeMap<String, IndexEntry>,
+}
+
+/// Keys sharing identical content
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct DuplicateGroup {
+    pub sha256: String,
+    pub size: u64,
+    pub keys: Vec<String>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct IndexResult {
+    pub index: BucketIndex,
+    pub duplicates: Vec<DuplicateGroup>,
+    pub failed: Vec<report::FailedObject>,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct IndexProgress {
+    bucket_id: String,
+    done: usize,
+    total: usize,
+}
+
+/// All indexes, keyed by "<project>/<bucket>" (userdata/backups/bucket_index.json)
+fn index_path(app: &AppHandle) -> PathBuf {
+    paths::get_userdata_dir(app).join("backups").join("bucket_index.json")
+}
+
+fn index_key(project_url: &str, bucket_id: &str) -> String {
+    format!("{}/{}", functions::normalize_project(project_url), bucket_id)
+}
+
+fn load_all(app: &AppHandle) -> Result<BTreeMap<String, BucketIndex>, String> {
+    let path = index_path(app);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| format!("Corrupt bucket_index.json: {}", e))
+}
+
+fn save_all(app: &AppHandle, all: &BTreeMap<String, BucketIndex>) -> Result<(), String> {
+    let path = index_path(app);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(all).map_err(|e| e.to_string())?;
+    fs::write(&path, data).map_err(|e| e.to_string())
+}
+
+/// Removes every persisted index of a project; returns how many were dropped
+fn drop_project(all: &mut BTreeMap<String, BucketIndex>, project_url: &str) -> usize {
+    let prefix = format!("{}/", functions::normalize_project(project_url));
+    let before = all.len();
+    all.retain(|key, _| !key.starts_with(&prefix));
+    before - all.len()
+}
+
+/// Forgets a project's indexes once its objects change outside a migration
+/// (e.g. a wipe), so content-hash skips can't trust them
+pub fn invalidate_project(app: &AppHandle, project_url: &str) -> Result<usize, String> {
+    let mut all = load_all(app)?;
+    let dropped = drop_project(&mut all, project_url);
+    if dropped > 0 {
+        save_all(app, &all)?;
+    }
+    Ok(dropped)
+}
+
+/// Last persisted index for a project's bucket, if any
+pub fn load_index(app: &AppHandle, project_url: &str, bucket_id: &str) -> Option<BucketIndex> {
+    load_all(app).ok()?.remove(&index_key(project_url, bucket_id))
+}
+
+/// Hashes every object in the bucket with bounded concurrency, persists the
+/// index and reports keys with duplicate content. Emits `index_progress`.
+pub async fn index_bucket(window: &Window, url: &str, key: &str, bucket_id: &str) -> Result<IndexResult, String> {
+    let app = window.app_handle();
+    let config = deps::load_config(app);
+    let mirror = storage::StorageMirror::new(http::client_for_app(app), url, key, "", "")
+        .with_performance(config.performance)
+        .with_rate_limits(http::rate_limits(app));
+
+    let objects = mirror.list_objects(bucket_id).await?;
+    let total = objects.len();
+    window.emit("log", format!("Indexing {} objects in {}...", total, bucket_id)).unwrap();
+
+    let concurrency = mirror.performance().transfer_concurrency;
+    let mut hashed = futures::stream::iter(0..total)
+        .map(|i| {
+            let (mirror, name) = (&mirror, &objects[i].name);
+            async move { (name, mirror.hash_object(bucket_id, name).await) }
+        })
+        .buffer_unordered(concurrency);
+
+    let mut entries = BTreeMap::new();
+    let mut failed = vec![];
+    let mut done = 0;
+    while let Some((name, outcome)) = hashed.next().await {
+        match outcome {
+            Ok((size, sha256)) => {
+                entries.insert(name.clone(), IndexEntry { size, sha256 });
+            }
+            Err(e) => {
+                window.emit("log", format!("  Could not hash {}: {}", name, e)).unwrap();
+                failed.push(report::FailedObject {
+                    bucket: bucket_id.to_string(),
+                    key: name.clone(),
+                    error: Some(e),
+                });
+            }
+        }
+        done += 1;
+        window
+            .emit("index_progress", IndexProgress { bucket_id: bucket_id.to_string(), done, total })
+            .unwrap();
+    }
+
+    let index = BucketIndex {
+        project: functions::normalize_project(url),
+        bucket_id: bucket_id.to_string(),
+        indexed_at: report::now_secs(),
+        objects: entries,
+    };
+
+    let mut all = load_all(app)?;
+    all.insert(index_key(url, bucket_id), index.clone());
+    save_all(app, &all)?;
+
+    Ok(IndexResult {
+        duplicates: find_duplicates(&index),
+        index,
+        failed,
+    })
+}
+
+fn find_duplicates(index: &BucketIndex) -> Vec<DuplicateGroup> {
+    let mut by_hash: BTreeMap<&str, DuplicateGroup> = BTreeMap::new();
+    for (key, entry) in &index.objects {
+        by_hash
+            .entry(&entry.sha256)
+            .or_insert_with(|| DuplicateGroup {
+                sha256: entry.sha256.clone(),
+                size: entry.size,
+                keys: vec![],
+            })
+            .keys
+            .push(key.clone());
+    }
+    by_hash.into_values().filter(|g| g.keys.len() > 1).collect()
+}
+
+/// True when both indexes record identical content for the object, its listed
+/// size still matches (a cheap guard against a stale source index) and the
+/// destination listing still holds it at that size (the persisted destination
+/// index goes stale after wipes or manual deletes)
+pub fn is_unchanged(
+    source: Option<&BucketIndex>,
+    dest: Option<&BucketIndex>,
+    object: &storage::StorageObject,
+    dest_size: Option<u64>,
+) -> bool {
+    match (source, dest) {
+        (Some(s), Some(d)) => s.objects.get(&object.name).is_some_and(|e| {
+            e.size == object.size() && dest_size == Some(e.size) && d.objects.get(&object.name) == Some(e)
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(project: &str, bucket_id: &str, objects: &[(&str, u64, &str)]) -> BucketIndex {
+        BucketIndex {
+            project: project.to_string(),
+            bucket_id: bucket_id.to_string(),
+            indexed_at: 0,
+            objects: objects
+                .iter()
+                .map(|(name, size, sha)| (name.to_string(), IndexEntry { size: *size, sha256: sha.to_string() }))
+                .collect(),
+        }
+    }
+
+    fn object(name: &str, size: u64) -> storage::StorageObject {
+        serde_json::from_value(serde_json::json!({ "name": name, "id": "x", "metadata": { "size": size } })).unwrap()
+    }
+
+    #[test]
+    fn matching_hashes_skip_only_when_object_is_still_on_destination() {
+        let source = index("src", "docs", &[("a.txt", 3, "abc")]);
+        let dest = index("dst", "docs", &[("a.txt", 3, "abc")]);
+        let obj = object("a.txt", 3);
+
+        assert!(is_unchanged(Some(&source), Some(&dest), &obj, Some(3)));
+        // Deleted (or wiped) since the destination was indexed
+        assert!(!is_unchanged(Some(&source), Some(&dest), &obj, None));
+        // Replaced by something else since
+        assert!(!is_unchanged(Some(&source), Some(&dest), &obj, Some(7)));
+    }
+
+    #[test]
+    fn changed_source_is_not_skipped() {
+        let source = index("src", "docs", &[("a.txt", 3, "abc")]);
+        let dest = index("dst", "docs", &[("a.txt", 3, "old")]);
+
+        assert!(!is_unchanged(Some(&source), Some(&dest), &object("a.txt", 3), Some(3)));
+        assert!(!is_unchanged(Some(&source), None, &object("a.txt", 3), Some(3)));
+    }
+
+    #[test]
+    fn drop_project_removes_only_that_projects_indexes() {
+        let dest_url = "https://dstref.supabase.co";
+        let source_url = "https://srcref.supabase.co";
+        let mut all = BTreeMap::new();
+        all.insert(index_key(dest_url, "docs"), index("dst", "docs", &[]));
+        all.insert(index_key(dest_url, "media"), index("dst", "media", &[]));
+        all.insert(index_key(source_url, "docs"), index("src", "docs", &[]));
+
+        assert_eq!(drop_project(&mut all, dest_url), 2);
+        assert_eq!(all.keys().collect::<Vec<_>>(), vec![&index_key(source_url, "docs")]);
+    }
+}
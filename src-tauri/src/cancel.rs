@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, Window};
+
+use crate::{checkpoint, telemetry};
+
+/// Error text long-running operations return when the user cancels them
+pub const CANCELLED: &str = "Cancelled by user";
+
+/// Why a long-running operation ended
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", content = "message", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StopReason {
+    UserCancelled,
+    Error(String),
+    Completed,
+}
+
+impl StopReason {
+    pub fn from_result<T>(result: &Result<T, String>) -> Self {
+        match result {
+            Ok(_) => StopReason::Completed,
+            Err(e) if e == CANCELLED => StopReason::UserCancelled,
+            Err(e) => StopReason::Error(e.clone()),
+        }
+    }
+
+    /// Text for logs and the UI: "Cancelled by user" vs "Failed: ..."
+    pub fn label(&self) -> String {
+        match self {
+            StopReason::UserCancelled => CANCELLED.to_string(),
+            StopReason::Error(e) => format!("Failed: {}", e),
+            StopReason::Completed => "Completed".to_string(),
+        }
+    }
+}
+
+/// Payload of the `operation_stopped` event
+#[derive(serde::Serialize, Clone, Debug)]
+struct OperationStopped {
+    operation: String,
+    reason: StopReason,
+}
+
+/// Cooperative cancel flag, checked by the operation between units of work
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// `Err(CANCELLED)` once cancelled, for use with `?`
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// How often a paused operation re-checks whether it may continue
+const PAUSE_POLL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Pause flag, separate from cancel: a paused operation starts no new units
+/// of work but lets the ones in flight finish, and keeps its progress
+#[derive(Clone, Default)]
+pub struct PauseToken(Arc<AtomicBool>);
+
+impl PauseToken {
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Sets the flag and returns its previous value
+    pub fn set(&self, paused: bool) -> bool {
+        self.0.swap(paused, Ordering::Relaxed)
+    }
+
+    /// Waits while paused; returns early once `cancel` is set
+    pub async fn wait_while_paused(&self, cancel: &CancelToken) {
+        while self.is_paused() && !cancel.is_cancelled() {
+            tokio::time::sleep(PAUSE_POLL).await;
+        }
+    }
+}
+
+/// Tokens of running operations keyed by operation name (managed state)
+#[derive(Default)]
+pub struct CancelRegistry(Mutex<HashMap<String, CancelToken>>);
+
+/// Pause tokens of running pausable operations (managed state)
+#[derive(Default)]
+pub struct PauseRegistry(Mutex<HashMap<String, PauseToken>>);
+
+/// Payload of the `operation_paused` event
+#[derive(serde::Serialize, Clone, Debug)]
+struct OperationPaused {
+    operation: String,
+    paused: bool,
+}
+
+/// Registers a fresh token for `operation` ("migration", "install", "backup", "dir_scan")
+pub fn begin(app: &AppHandle, operation: &str) -> CancelToken {
+    let token = CancelToken::default();
+    app.state::<CancelRegistry>()
+        .0
+        .lock()
+        .unwrap()
+        .insert(operation.to_string(), token.clone());
+    token
+}
+
+/// Registers a pause token for `operation` (only "migration" is pausable)
+pub fn begin_pausable(app: &AppHandle, operation: &str) -> PauseToken {
+    let token = PauseToken::default();
+    app.state::<PauseRegistry>()
+        .0
+        .lock()
+        .unwrap()
+        .insert(operation.to_string(), token.clone());
+    token
+}
+
+/// Pauses or resumes a running operation. Returns false when it isn't
+/// running or can't be paused. A migration's checkpoint is updated right away. Emits `operation_paused` and a
+/// `<OPERATION>_PAUSED` / `<OPERATION>_RESUMED` telemetry event.
+pub fn set_paused(window: &Window, operation: &str, paused: bool) -> bool {
+    let Some(token) = window.app_handle().state::<PauseRegistry>().0.lock().unwrap().get(operation).cloned() else {
+        return false;
+    };
+    if token.set(paused) == paused {
+        return true;
+    }
+    // The storage checkpoint reflects the pause now, not at the next finished transfer
+    if operation == "migration" {
+        if let Err(e) = checkpoint::mark_paused(window.app_handle(), paused) {
+            window.emit("log", format!("Could not update the checkpoint: {}", e)).unwrap();
+        }
+    }
+    let state = if paused { "PAUSED" } else { "RESUMED" };
+    window.emit("log", format!(
+        "{} {}{}",
+        operation,
+        state.to_lowercase(),
+        if paused { " (transfers in flight will finish; no new ones start)" } else { "" }
+    )).unwrap();
+    telemetry::track_event(window, telemetry::TelemetryEvent::new(
+        &format!("{}_{}", operation.to_uppercase(), state),
+        serde_json::json!({ "operation": operation }),
+    ));
+    window
+        .emit("operation_paused", OperationPaused { operation: operation.to_string(), paused })
+        .unwrap();
+    true
+}
+
+/// Requests cancellation. Returns false when the operation isn't running.
+pub fn cancel(app: &AppHandle, operation: &str) -> bool {
+    match app.state::<CancelRegistry>().0.lock().unwrap().get(operation) {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Unregisters the operation and reports how it stopped
+pub fn finish<T>(window: &Window, operation: &str, result: &Result<T, String>) -> StopReason {
+    window
+        .app_handle()
+        .state::<CancelRegistry>()
+        .0
+        .lock()
+        .unwrap()
+        .remove(operation);
+    window.app_handle().state::<PauseRegistry>().0.lock().unwrap().remove(operation);
+
+    let reason = StopReason::from_result(result);
+    if reason != StopReason::Completed {
+        window.emit("log", format!("{} stopped: {}", operation, reason.label())).unwrap();
+        telemetry::track_event(window, telemetry::TelemetryEvent::new(
+            "OPERATION_STOPPED",
+            serde_json::json!({ "operation": operation, "reason": reason }),
+        ));
+    }
+    window
+        .emit("operation_stopped", OperationStopped { operation: operation.to_string(), reason: reason.clone() })
+        .unwrap();
+    reason
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stop_reason_tells_user_cancel_from_failure() {
+        assert_eq!(StopReason::from_result(&Ok::<_, String>(())), StopReason::Completed);
+        assert_eq!(StopReason::from_result::<()>(&Err(CANCELLED.to_string())), StopReason::UserCancelled);
+        assert_eq!(
+            StopReason::from_result::<()>(&Err("pg_dump exited with 1".to_string())),
+            StopReason::Error("pg_dump exited with 1".to_string())
+        );
+    }
+
+    #[test]
+    fn stop_reason_labels_and_payload() {
+        assert_eq!(StopReason::UserCancelled.label(), "Cancelled by user");
+        assert_eq!(StopReason::Error("disk full".to_string()).label(), "Failed: disk full");
+        assert_eq!(
+            serde_json::to_value(StopReason::Error("disk full".to_string())).unwrap(),
+            serde_json::json!({ "kind": "ERROR", "message": "disk full" })
+        );
+        assert_eq!(serde_json::to_value(StopReason::UserCancelled).unwrap(), serde_json::json!({ "kind": "USER_CANCELLED" }));
+    }
+
+    #[test]
+    fn cancelled_token_stops_the_next_unit_of_work() {
+        let token = CancelToken::default();
+        assert!(token.check().is_ok());
+        token.clone().cancel();
+        assert_eq!(StopReason::from_result(&token.check()), StopReason::UserCancelled);
+    }
+
+    #[tokio::test]
+    async fn cancel_releases_a_paused_operation() {
+        let (pause, cancel) = (PauseToken::default(), CancelToken::default());
+        pause.0.store(true, Ordering::Relaxed);
+        cancel.cancel();
+        tokio::time::timeout(PAUSE_POLL * 4, pause.wait_while_paused(&cancel)).await.unwrap();
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::paths;
+
+/// Durable progress shared across runs (userdata/checkpoint.json)
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Checkpoint {
+    /// "schema.table" -> last synced high-water value (as text)
+    pub db_watermarks: HashMap<String, String>,
+    /// bucket id -> storage progress of the latest migration run
+    pub storage_buckets: BTreeMap<String, BucketProgress>,
+}
+
+/// How far a storage migration got through one bucket
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct BucketProgress {
+    pub id: String,
+    pub name: String,
+    pub objects_total: usize,
+    pub objects_done: usize, // synced or skipped
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub status: String, // PENDING, IN_PROGRESS, PAUSED, DONE, PARTIAL, FAILED
+}
+
+/// Per-bucket state for the resume view; empty when no checkpoint exists
+pub fn migration_state(app: &AppHandle) -> Result<Vec<BucketProgress>, String> {
+    Ok(load(app)?.storage_buckets.into_values().collect())
+}
+
+/// Load-modify-save of one bucket's progress. Failures only cost resume accuracy,
+/// so they are returned for logging rather than aborting the migration.
+pub fn update_bucket(app: &AppHandle, bucket_id: &str, update: impl FnOnce(&mut BucketProgress)) -> Result<(), String> {
+    let mut cp = load(app)?;
+    let progress = cp.storage_buckets.entry(bucket_id.to_string()).or_default();
+    progress.id = bucket_id.to_string();
+    update(progress);
+    save(app, &cp)
+}
+
+/// Flips the running bucket(s) to PAUSED, or back to IN_PROGRESS on resume,
+/// as soon as the user pauses rather than when the next transfer finishes
+pub fn mark_paused(app: &AppHandle, paused: bool) -> Result<(), String> {
+    let (from, to) = if paused { ("IN_PROGRESS", "PAUSED") } else { ("PAUSED", "IN_PROGRESS") };
+    let mut cp = load(app)?;
+    let mut changed = false;
+    for progress in cp.storage_buckets.values_mut().filter(|p| p.status == from) {
+        progress.status = to.to_string();
+        changed = true;
+    }
+    if changed {
+        save(app, &cp)?;
+    }
+    Ok(())
+}
+
+fn checkpoint_path(app: &AppHandle) -> PathBuf {
+    paths::get_userdata_dir(app).join("checkpoint.json")
+}
+
+/// Loads the checkpoint; a missing file is an empty checkpoint
+pub fn load(app: &AppHandle) -> Result<Checkpoint, String> {
+    let path = checkpoint_path(app);
+    if !path.exists() {
+        return Ok(Checkpoint::default());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| format!("Corrupt checkpoint: {}", e))
+}
+
+pub fn save(app: &AppHandle, checkpoint: &Checkpoint) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(checkpoint).map_err(|e| e.to_string())?;
+    fs::write(checkpoint_path(app), data).map_err(|e| e.to_string())
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Emitter, Window};
+
+use crate::db::{quote_literal, Psql};
+use crate::grants::MANAGED_SCHEMAS;
+
+/// A source comment that was missing or different on the destination
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CommentStatus {
+    pub target: String, // e.g. "COLUMN public.users.email"
+    pub status: String, // RESTORED, FAILED
+    pub error: Option<String>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CommentReport {
+    pub source_comments: usize,
+    pub already_present: usize,
+    pub restored: usize,
+    pub failed: usize,
+    pub comments: Vec<CommentStatus>,
+}
+
+/// `COMMENT ON` target -> hex-encoded description (comments may span lines,
+/// which the line-based psql output can't carry) for schemas, tables, views,
+/// columns and functions outside the platform schemas
+fn comments_sql() -> String {
+    format!(
+        "SELECT format('SCHEMA %I', n.nspname), encode(convert_to(d.description, 'UTF8'), 'hex') \
+         FROM pg_description d JOIN pg_namespace n ON n.oid = d.objoid AND d.classoid = 'pg_namespace'::regclass \
+         WHERE n.nspname NOT IN ({0}) \
+         UNION ALL \
+         SELECT format('%s %I.%I', CASE c.relkind WHEN 'v' THEN 'VIEW' WHEN 'm' THEN 'MATERIALIZED VIEW' \
+           WHEN 'f' THEN 'FOREIGN TABLE' ELSE 'TABLE' END, n.nspname, c.relname), \
+           encode(convert_to(d.description, 'UTF8'), 'hex') \
+         FROM pg_description d JOIN pg_class c ON c.oid = d.objoid AND d.classoid = 'pg_class'::regclass AND d.objsubid = 0 \
+         JOIN pg_namespace n ON n.oid = c.relnamespace \
+         WHERE c.relkind IN ('r', 'p', 'v', 'm', 'f') AND n.nspname NOT IN ({0}) \
+         UNION ALL \
+         SELECT format('COLUMN %I.%I.%I', n.nspname, c.relname, a.attname), encode(convert_to(d.description, 'UTF8'), 'hex') \
+         FROM pg_description d JOIN pg_class c ON c.oid = d.objoid AND d.classoid = 'pg_class'::regclass AND d.objsubid > 0 \
+         JOIN pg_namespace n ON n.oid = c.relnamespace \
+         JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = d.objsubid \
+         WHERE n.nspname NOT IN ({0}) \
+         UNION ALL \
+         SELECT format('%s %s', CASE p.prokind WHEN 'p' THEN 'PROCEDURE' ELSE 'FUNCTION' END, p.oid::regprocedure), \
+           encode(convert_to(d.description, 'UTF8'), 'hex') \
+         FROM pg_description d JOIN pg_proc p ON p.oid = d.objoid AND d.classoid = 'pg_proc'::regclass \
+         JOIN pg_namespace n ON n.oid = p.pronamespace \
+         WHERE n.nspname NOT IN ({0}) AND p.prokind IN ('f', 'p')",
+        MANAGED_SCHEMAS
+    )
+}
+
+async fn load_comments(psql: &Psql, conn: &str) -> Result<BTreeMap<String, String>, String> {
+    Ok(psql
+        .query(conn, &comments_sql())
+        .await?
+        .into_iter()
+        .filter(|r| r.len() == 2)
+        .filter_map(|r| {
+            let text = String::from_utf8(hex::decode(&r[1]).ok()?).ok()?;
+            Some((r[0].clone(), text))
+        })
+        .collect())
+}
+
+/// Compares comment coverage between source and destination and re-applies
+/// every source comment the destination lacks or has different. pg_dump
+/// keeps `COMMENT ON` statements (the app never passes --no-comments), so
+/// gaps here come from restores made with other tools or flags.
+pub async fn migrate_comments(window: &Window, app: &AppHandle, source_db: &str, dest_db: &str) -> Result<CommentReport, String> {
+    let psql = Psql::locate(app)?;
+    let source = load_comments(&psql, source_db).await?;
+    let dest = load_comments(&psql, dest_db).await?;
+
+    let mut report = CommentReport {
+        source_comments: source.len(),
+        already_present: 0,
+        restored: 0,
+        failed: 0,
+        comments: vec![],
+    };
+    for (target, text) in &source {
+        if dest.get(target) == Some(text) {
+            report.already_present += 1;
+            continue;
+        }
+        let statement = format!("COMMENT ON {} IS {}", target, quote_literal(text));
+        let mut status = CommentStatus { target: target.clone(), status: "RESTORED".to_string(), error: None };
+        match psql.execute(dest_db, &[statement]).await {
+            Ok(_) => report.restored += 1,
+            Err(e) => {
+                report.failed += 1;
+                status.status = "FAILED".to_string();
+                status.error = Some(e);
+            }
+        }
+        report.comments.push(status);
+    }
+
+    window.emit("log", format!(
+        "Comments: {} on source, {} already on destination, {} restored, {} failed",
+        report.source_comments,
+        report.already_present,
+        report.restored,
+        report.failed
+    )).unwrap();
+    Ok(report)
+}
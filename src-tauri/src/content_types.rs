@@ -0,0 +1,157 @@
+use tauri::{Emitter, Window};
+
+use crate::storage::{self, StorageMirror};
+
+/// Bytes read from objects whose type has to be sniffed
+const SNIFF_BYTES: u64 = 512;
+
+const OCTET_STREAM: &str = "application/octet-stream";
+
+/// A destination object served with a different type than its name/content imply
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct ContentTypeFinding {
+    pub key: String,
+    pub stored: String, // empty when the object has no recorded type
+    pub expected: String,
+    pub detected_by: String, // EXTENSION, MAGIC
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ContentTypeFix {
+    pub key: String,
+    pub content_type: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// "text/plain; charset=utf-8" -> "text/plain"
+fn base_type(mime: &str) -> String {
+    mime.split(';').next().unwrap_or_default().trim().to_lowercase()
+}
+
+/// Flags destination objects whose stored content-type disagrees with their
+/// extension. Objects without a known extension are sniffed (first bytes)
+/// only when stored as octet-stream, the usual result of a lossy migration.
+pub async fn audit_content_types(window: &Window, mirror: &StorageMirror, bucket_id: &str) -> Result<Vec<ContentTypeFinding>, String> {
+    audit_objects(&|line| window.emit("log", line).unwrap(), mirror, bucket_id).await
+}
+
+/// `audit_content_types` with progress going to `log`. S3 listings carry no
+/// content-type, so those objects are asked for the type they are served with.
+async fn audit_objects(log: &(dyn Fn(String) + Sync), mirror: &StorageMirror, bucket_id: &str) -> Result<Vec<ContentTypeFinding>, String> {
+    log(format!("Content types: listing {}...", bucket_id));
+    let objects = mirror.list_dest_objects(bucket_id).await?;
+
+    let mut findings = vec![];
+    for object in objects.iter().filter(|o| o.metadata.is_some()) {
+        let listed = object.metadata.as_ref().and_then(|m| m["mimetype"].as_str());
+        let stored = match listed {
+            Some(mime) => mime.to_string(),
+            None => match mirror.dest_content_type(bucket_id, &object.name).await {
+                Ok(mime) => mime.unwrap_or_default(),
+                Err(e) => {
+                    log(format!("  Could not read {}: {}", object.name, e));
+                    continue;
+                }
+            },
+        };
+        let (expected, detected_by) = match storage::content_type_from_extension(&object.name) {
+            Some(mime) => (mime, "EXTENSION"),
+            None if stored.is_empty() || base_type(&stored) == OCTET_STREAM => {
+                match mirror.read_dest_head(bucket_id, &object.name, SNIFF_BYTES).await {
+                    Ok(head) => match storage::sniff_content_type(&head) {
+                        Some(mime) => (mime, "MAGIC"),
+                        None => continue,
+                    },
+                    Err(e) => {
+                        log(format!("  Could not read {}: {}", object.name, e));
+                        continue;
+                    }
+                }
+            }
+            None => continue,
+        };
+        if base_type(&stored) != expected {
+            findings.push(ContentTypeFinding {
+                key: object.name.clone(),
+                stored,
+                expected: expected.to_string(),
+                detected_by: detected_by.to_string(),
+            });
+        }
+    }
+
+    log(format!(
+        "Content types: {} of {} objects in {} have a mismatched type",
+        findings.len(),
+        objects.len(),
+        bucket_id
+    ));
+    Ok(findings)
+}
+
+/// Re-uploads each flagged object in place with its expected content-type
+/// (the Storage API has no metadata-only update)
+pub async fn fix_content_types(
+    window: &Window,
+    mirror: &StorageMirror,
+    bucket_id: &str,
+    findings: &[ContentTypeFinding],
+) -> Vec<ContentTypeFix> {
+    let mut fixes = vec![];
+    for finding in findings {
+        let outcome = async {
+            let data = mirror.download_dest_object(bucket_id, &finding.key).await?;
+            mirror.upsert_object(bucket_id, &finding.key, data, &finding.expected).await
+        }
+        .await;
+        if let Err(e) = &outcome {
+            window.emit("log", format!("  Could not fix {}: {}", finding.key, e)).unwrap();
+        }
+        fixes.push(ContentTypeFix {
+            key: finding.key.clone(),
+            content_type: finding.expected.clone(),
+            ok: outcome.is_ok(),
+            error: outcome.err(),
+        });
+    }
+    let fixed = fixes.iter().filter(|f| f.ok).count();
+    window.emit("log", format!("Content types: fixed {} of {} objects in {}", fixed, fixes.len(), bucket_id)).unwrap();
+    fixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::{S3Config, S3Credentials};
+    use crate::test_support::{MockServer, Reply};
+
+    #[tokio::test]
+    async fn s3_listing_without_types_is_audited_by_served_type() {
+        let served = [("a.png", "image/png"), ("b.pdf", "application/octet-stream"), ("c.txt", "text/plain; charset=utf-8")];
+        let dest = MockServer::start(move |request| {
+            if request.path.contains("list-type=2") {
+                let contents: String = served
+                    .iter()
+                    .map(|(key, _)| format!("<Contents><Key>{}</Key><Size>4</Size></Contents>", key))
+                    .collect();
+                return Reply::bytes(200, format!("<ListBucketResult><IsTruncated>false</IsTruncated>{}</ListBucketResult>", contents).into_bytes());
+            }
+            let key = request.path.rsplit('/').next().unwrap_or_default();
+            let (_, mime) = served.iter().find(|(k, _)| *k == key).unwrap();
+            Reply::bytes(206, b"d".to_vec()).with_header("Content-Type", mime)
+        })
+        .await;
+        // Only destination credentials: a destination-only mirror must still use S3
+        let credentials = S3Credentials { access_key_id: "id".to_string(), secret_access_key: "secret".to_string() };
+        let config = S3Config { enabled: true, dest: Some(credentials), ..Default::default() };
+        let mirror = StorageMirror::for_destination(reqwest::Client::new(), &dest.url, "key").with_s3(&config);
+
+        let findings = audit_objects(&|_| {}, &mirror, "docs").await.unwrap();
+        let flagged: Vec<_> = findings.iter().map(|f| (f.key.as_str(), f.stored.as_str(), f.expected.as_str())).collect();
+        assert_eq!(flagged, [("b.pdf", "application/octet-stream", "application/pdf")]);
+        let requests = dest.requests();
+        assert!(requests.iter().all(|r| r.path.starts_with("/storage/v1/s3/")), "{:?}", requests.iter().map(|r| &r.path).collect::<Vec<_>>());
+        assert!(requests.iter().all(|r| r.header("Authorization").is_some_and(|a| a.contains("Credential=id/"))));
+    }
+}
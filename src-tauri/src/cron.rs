@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter, Window};
+
+use crate::db::{quote_literal, Psql};
+
+/// How one source `cron.job` entry fared on the destination
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CronJobStatus {
+    pub name: Option<String>,
+    pub schedule: String,
+    pub command: String,
+    pub active: bool,
+    pub status: String, // PRESENT, CREATED, FAILED
+    pub missing_objects: Vec<String>, // schema.name references not found on the destination
+    pub error: Option<String>,
+}
+
+/// Commands are hex-encoded since they often span lines
+const JOBS_SQL: &str = "SELECT coalesce(jobname, ''), schedule, encode(convert_to(command, 'UTF8'), 'hex'), active \
+     FROM cron.job ORDER BY jobid";
+
+async fn has_pg_cron(psql: &Psql, conn: &str) -> Result<bool, String> {
+    let rows = psql.query(conn, "SELECT 1 FROM pg_extension WHERE extname = 'pg_cron'").await?;
+    Ok(!rows.is_empty())
+}
+
+/// (name, schedule, command, active) for every job in `cron.job`
+async fn load_jobs(psql: &Psql, conn: &str) -> Result<Vec<(String, String, String, bool)>, String> {
+    Ok(psql
+        .query(conn, JOBS_SQL)
+        .await?
+        .into_iter()
+        .filter(|r| r.len() == 4)
+        .filter_map(|r| {
+            let command = String::from_utf8(hex::decode(&r[2]).ok()?).ok()?;
+            Some((r[0].clone(), r[1].clone(), command, r[3] == "t"))
+        })
+        .collect())
+}
+
+/// schema.name references in a job command (e.g. `public.cleanup()`),
+/// skipping the cron/net/pg_catalog helpers every project has
+fn qualified_references(command: &str) -> Vec<(String, String)> {
+    let re = regex::Regex::new(r#"(?i)\b([a-z_][a-z0-9_]*)\.([a-z_][a-z0-9_]*)\b"#).unwrap();
+    let mut seen = HashSet::new();
+    re.captures_iter(command)
+        .map(|c| (c[1].to_string(), c[2].to_string()))
+        .filter(|(schema, _)| !matches!(schema.as_str(), "cron" | "net" | "pg_catalog" | "extensions"))
+        .filter(|r| seen.insert(r.clone()))
+        .collect()
+}
+
+/// Whether `schema.name` is a relation or function in `conn`
+async fn object_exists(psql: &Psql, conn: &str, schema: &str, name: &str) -> Result<bool, String> {
+    let sql = format!(
+        "SELECT EXISTS (SELECT 1 FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+         WHERE n.nspname = {0} AND c.relname = {1}) \
+         OR EXISTS (SELECT 1 FROM pg_proc p JOIN pg_namespace n ON n.oid = p.pronamespace \
+         WHERE n.nspname = {0} AND p.proname = {1})",
+        quote_literal(schema),
+        quote_literal(name)
+    );
+    let rows = psql.query(conn, &sql).await?;
+    Ok(rows.first().and_then(|r| r.first()).map(String::as_str) == Some("t"))
+}
+
+/// References that exist on the source but not on the destination. Checking
+/// the source first keeps table aliases (`t.id`) out of the report.
+async fn missing_references(
+    psql: &Psql,
+    source_db: &str,
+    dest_db: &str,
+    refs: &[(String, String)],
+) -> Result<Vec<String>, String> {
+    let mut missing = vec![];
+    for (schema, name) in refs {
+        if object_exists(psql, source_db, schema, name).await? && !object_exists(psql, dest_db, schema, name).await? {
+            missing.push(format!("{}.{}", schema, name));
+        }
+    }
+    Ok(missing)
+}
+
+/// Replays the source's pg_cron jobs on the destination via `cron.schedule`.
+/// Jobs already there (same name, or same schedule and command when unnamed)
+/// are left alone, so re-running doesn't duplicate them. Jobs run in the
+/// destination's cron database, and disabled jobs stay disabled.
+pub async fn migrate_cron_jobs(
+    window: &Window,
+    app: &AppHandle,
+    source_db: &str,
+    dest_db: &str,
+) -> Result<Vec<CronJobStatus>, String> {
+    let psql = Psql::locate(app)?;
+    if !has_pg_cron(&psql, source_db).await? {
+        window.emit("log", "Cron jobs: pg_cron is not enabled on the source; nothing to migrate.").unwrap();
+        return Ok(vec![]);
+    }
+    if !has_pg_cron(&psql, dest_db).await? {
+        return Err("pg_cron is not enabled on the destination. Enable it (Database > Extensions) and try again.".to_string());
+    }
+
+    let source = load_jobs(&psql, source_db).await?;
+    let existing = load_jobs(&psql, dest_db).await?;
+    let existing_names: HashSet<&str> =
+        existing.iter().map(|j| j.0.as_str()).filter(|n| !n.is_empty()).collect();
+    let existing_unnamed: HashSet<(&str, &str)> = existing
+        .iter()
+        .filter(|j| j.0.is_empty())
+        .map(|j| (j.1.as_str(), j.2.as_str()))
+        .collect();
+
+    let mut results = vec![];
+    for (name, schedule, command, active) in source {
+        let mut status = CronJobStatus {
+            name: (!name.is_empty()).then(|| name.clone()),
+            schedule: schedule.clone(),
+            command: command.clone(),
+            active,
+            status: "PRESENT".to_string(),
+            missing_objects: missing_references(&psql, source_db, dest_db, &qualified_references(&command)).await?,
+            error: None,
+        };
+        let present = if name.is_empty() {
+            existing_unnamed.contains(&(schedule.as_str(), command.as_str()))
+        } else {
+            existing_names.contains(name.as_str())
+        };
+
+        if !present {
+            let mut statements = vec![if name.is_empty() {
+                format!("SELECT cron.schedule({}, {})", quote_literal(&schedule), quote_literal(&command))
+            } else {
+                format!(
+                    "SELECT cron.schedule({}, {}, {})",
+                    quote_literal(&name),
+                    quote_literal(&schedule),
+                    quote_literal(&command)
+                )
+            }];
+            if !active {
+                statements.push(format!(
+                    "SELECT cron.alter_job(max(jobid), active := false) FROM cron.job WHERE command = {}",
+                    quote_literal(&command)
+                ));
+            }
+            match psql.execute(dest_db, &statements).await {
+                Ok(_) => {
+                    window.emit("log", format!(
+                        "  Scheduled cron job {} ({})",
+                        status.name.as_deref().unwrap_or("(unnamed)"),
+                        schedule
+                    )).unwrap();
+                    status.status = "CREATED".to_string();
+                }
+                Err(e) => {
+                    status.status = "FAILED".to_string();
+                    status.error = Some(e);
+                }
+            }
+        }
+        if !status.missing_objects.is_empty() {
+            window.emit("log", format!(
+                "  WARNING: cron job {} references objects missing on the destination: {}",
+                status.name.as_deref().unwrap_or("(unnamed)"),
+                status.missing_objects.join(", ")
+            )).unwrap();
+        }
+        results.push(status);
+    }
+
+    let count = |s: &str| results.iter().filter(|j| j.status == s).count();
+    window.emit("log", format!(
+        "Cron jobs: {} created, {} already present, {} failed, {} with missing references",
+        count("CREATED"),
+        count("PRESENT"),
+        count("FAILED"),
+        results.iter().filter(|j| !j.missing_objects.is_empty()).count()
+    )).unwrap();
+    Ok(results)
+}
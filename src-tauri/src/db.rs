@@ -0,0 +1,470 @@
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Window};
+use tokio::process::Command;
+
+use crate::deps::{self, PulseManager};
+
+/// Driver pack that provides the Postgres client binaries
+pub const PG_PACKAGE: &str = "postgres-15";
+
+/// Command for a driver-pack binary with its shared libraries on the loader path
+fn pg_command(bin: &Path) -> Command {
+    let mut cmd = Command::new(bin);
+    cmd.envs(deps::child_process_env(bin));
+    cmd
+}
+
+/// Field separator for unaligned psql output (ASCII unit separator)
+const FIELD_SEP: &str = "\u{1f}";
+
+/// Thin wrapper over the Pulse Pack's `psql` binary
+pub struct Psql {
+    bin: PathBuf,
+}
+
+impl Psql {
+    pub fn locate(app: &AppHandle) -> Result<Self, String> {
+        let mgr = PulseManager::new(app);
+        let bin = mgr
+            .resolve(PG_PACKAGE, &format!("psql{}", std::env::consts::EXE_SUFFIX))
+            .map_err(|e| format!("{}. Install the Pulse Pack first.", e))?;
+        Ok(Self { bin })
+    }
+
+    async fn run(&self, conn: &str, commands: &[String], single_transaction: bool) -> Result<String, String> {
+        let mut cmd = pg_command(&self.bin);
+        cmd.arg("-X")
+            .arg("-q")
+            .arg("-A")
+            .arg("-t")
+            .arg("-F")
+            .arg(FIELD_SEP)
+            .arg("-v")
+            .arg("ON_ERROR_STOP=1")
+            .arg("-d")
+            .arg(conn);
+        if single_transaction {
+            cmd.arg("--single-transaction");
+        }
+        for c in commands {
+            cmd.arg("-c").arg(c);
+        }
+
+        let output = cmd
+            .output()
+            .await
+            .map_err(|e| format!("Failed to start psql: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "psql failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Runs a query and returns its rows as text fields
+    pub async fn query(&self, conn: &str, sql: &str) -> Result<Vec<Vec<String>>, String> {
+        let out = self.run(conn, &[sql.to_string()], false).await?;
+        Ok(out
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| l.split(FIELD_SEP).map(|f| f.to_string()).collect())
+            .collect())
+    }
+
+    /// Runs statements in order within one session and one transaction
+    pub async fn execute(&self, conn: &str, statements: &[String]) -> Result<String, String> {
+        self.run(conn, statements, true).await
+    }
+}
+
+/// Whether `conn` accepts a connection and a trivial query
+pub async fn can_connect(app: &AppHandle, conn: &str) -> bool {
+    match Psql::locate(app) {
+        Ok(psql) => psql.query(conn, "SELECT 1").await.is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Quotes an identifier: my"table -> "my""table"
+pub fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Quotes a string literal: it's -> 'it''s'
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// psql-side path literal for \copy (forward slashes work on every platform)
+pub fn copy_path(path: &std::path::Path) -> String {
+    quote_literal(&path.to_string_lossy().replace('\\', "/"))
+}
+
+fn driver_bin(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    PulseManager::new(app)
+        .resolve(PG_PACKAGE, &format!("{}{}", name, std::env::consts::EXE_SUFFIX))
+        .map_err(|e| format!("{}. Install the Pulse Pack first.", e))
+}
+
+/// How often tailed process output is flushed to the UI
+const TAIL_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+/// Lines emitted per flush; the rest of a burst is summarized in one line
+const TAIL_MAX_LINES: usize = 20;
+/// stderr lines kept for the error message when the process fails
+const TAIL_KEEP_LINES: usize = 20;
+
+fn flush_tail(app: &AppHandle, label: &str, pending: &mut Vec<String>) {
+    let skipped = pending.len().saturating_sub(TAIL_MAX_LINES);
+    for line in pending.drain(..).take(TAIL_MAX_LINES) {
+        let _ = app.emit("log", format!("  [{}] {}", label, line));
+    }
+    if skipped > 0 {
+        let _ = app.emit("log", format!("  [{}] ... {} more lines", label, skipped));
+    }
+}
+
+/// Reads one line without its line ending, replacing invalid UTF-8 instead of
+/// failing on it (pg_dump echoes identifiers in the server's encoding). None at
+/// the end of input. Safe in `select!`: a partly read line stays in `buf`.
+async fn read_lossy_line<R: tokio::io::AsyncBufRead + Unpin>(reader: &mut R, buf: &mut Vec<u8>) -> Option<String> {
+    use tokio::io::AsyncBufReadExt;
+
+    let read = reader.read_until(b'\n', buf).await;
+    if buf.is_empty() || read.is_err() {
+        return None;
+    }
+    let line = String::from_utf8_lossy(buf).trim_end_matches(['\n', '\r']).to_string();
+    buf.clear();
+    Some(line)
+}
+
+/// Emits a child's stderr as `log` lines while it runs, coalesced so a chatty
+/// process can't flood the UI. Every line is also handed to `on_line`.
+/// Returns the last lines for error reporting.
+async fn tail_stderr(
+    app: AppHandle,
+    label: String,
+    stderr: tokio::process::ChildStderr,
+    mut on_line: impl FnMut(&str) + Send + 'static,
+) -> Vec<String> {
+    let mut reader = tokio::io::BufReader::new(stderr);
+    let mut buf = vec![];
+    let mut pending = vec![];
+    let mut kept = std::collections::VecDeque::with_capacity(TAIL_KEEP_LINES);
+    let mut tick = tokio::time::interval(TAIL_FLUSH_INTERVAL);
+    loop {
+        tokio::select! {
+            line = read_lossy_line(&mut reader, &mut buf) => match line {
+                Some(line) => {
+                    on_line(&line);
+                    if kept.len() == TAIL_KEEP_LINES {
+                        kept.pop_front();
+                    }
+                    kept.push_back(line.clone());
+                    pending.push(line);
+                }
+                _ => break,
+            },
+            _ = tick.tick() => flush_tail(&app, &label, &mut pending),
+        }
+    }
+    flush_tail(&app, &label, &mut pending);
+    kept.into()
+}
+
+/// Runs a driver binary, streaming its stderr (pg_dump/pg_restore
+/// `--verbose` progress) to the UI live. Returns stdout.
+async fn run_streaming(app: &AppHandle, cmd: Command, label: &str) -> Result<Vec<u8>, String> {
+    run_streaming_with(app, cmd, label, |_| {}).await
+}
+
+/// `run_streaming`, also passing each stderr line to `on_line`
+async fn run_streaming_with(
+    app: &AppHandle,
+    mut cmd: Command,
+    label: &str,
+    on_line: impl FnMut(&str) + Send + 'static,
+) -> Result<Vec<u8>, String> {
+    use tokio::io::AsyncReadExt;
+
+    let mut child = cmd
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", label, e))?;
+    let stderr = child.stderr.take().ok_or("stderr not captured")?;
+    let tail = tokio::spawn(tail_stderr(app.clone(), label.to_string(), stderr, on_line));
+
+    let mut stdout = vec![];
+    if let Some(mut out) = child.stdout.take() {
+        out.read_to_end(&mut stdout).await.map_err(|e| e.to_string())?;
+    }
+    let status = child.wait().await.map_err(|e| e.to_string())?;
+    let last_lines = tail.await.unwrap_or_default();
+    if !status.success() {
+        return Err(format!("{} failed: {}", label, last_lines.join("\n").trim()));
+    }
+    Ok(stdout)
+}
+
+/// Plain-SQL dump of a database via the Pulse Pack's `pg_dump`.
+/// Per-table progress (`--verbose`) is streamed to the log as it happens.
+/// `COMMENT ON` statements are kept (never pass `--no-comments` here).
+pub async fn dump_database(app: &AppHandle, conn: &str) -> Result<Vec<u8>, String> {
+    let bin = driver_bin(app, "pg_dump")?;
+
+    let mut cmd = pg_command(&bin);
+    cmd.arg("--format=plain")
+        .arg("--no-owner")
+        .arg("--no-privileges")
+        .arg("--verbose")
+        .arg("-d")
+        .arg(conn);
+    run_streaming(app, cmd, "pg_dump").await
+}
+
+/// Schema-only dump (`pg_dump --schema-only`) as text, progress streamed to the log
+pub async fn dump_schema(app: &AppHandle, conn: &str) -> Result<String, String> {
+    let mut cmd = pg_command(&driver_bin(app, "pg_dump")?);
+    cmd.arg("--schema-only")
+        .arg("--no-owner")
+        .arg("--no-privileges")
+        .arg("--verbose")
+        .arg("-d")
+        .arg(conn);
+    let out = run_streaming(app, cmd, "pg_dump").await?;
+    Ok(String::from_utf8_lossy(&out).to_string())
+}
+
+/// Archive formats `pg_restore` reads; only custom and directory support `-j`
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DumpFormat {
+    Custom,
+    Directory,
+    Tar,
+    Plain,
+}
+
+/// Sniffs the dump format: custom archives start with "PGDMP", directory
+/// dumps hold a toc.dat, tar archives carry "ustar" at offset 257
+fn dump_format(path: &Path) -> Result<DumpFormat, String> {
+    use std::io::Read;
+
+    if path.is_dir() {
+        return if path.join("toc.dat").is_file() {
+            Ok(DumpFormat::Directory)
+        } else {
+            Err(format!("{} is not a pg_dump directory archive (no toc.dat).", path.display()))
+        };
+    }
+    let mut head = vec![0u8; 512];
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Cannot open dump {}: {}", path.display(), e))?;
+    let read = file.read(&mut head).map_err(|e| e.to_string())?;
+    head.truncate(read);
+    Ok(if head.starts_with(b"PGDMP") {
+        DumpFormat::Custom
+    } else if head.len() >= 262 && &head[257..262] == b"ustar" {
+        DumpFormat::Tar
+    } else {
+        DumpFormat::Plain
+    })
+}
+
+/// Payload of the `restore_progress` event, one per finished table
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RestoreProgress {
+    pub table: String,
+    pub tables_done: usize,
+}
+
+/// Table name from pg_restore's verbose output. Parallel workers log
+/// "finished item 1234 TABLE DATA users" on completion; a serial restore only
+/// logs "processing data for table \"public.users\"" as each table starts.
+fn restored_table(line: &str, parallel: bool) -> Option<String> {
+    if parallel {
+        line.split("finished item ").nth(1)?.split(" TABLE DATA ").nth(1).map(|t| t.trim().to_string())
+    } else {
+        line.split("processing data for table ").nth(1).map(|t| t.trim().trim_matches('"').to_string())
+    }
+}
+
+/// Restores a custom/directory dump with `pg_restore -j <jobs>`. Jobs are
+/// capped at the CPU count; object ordering and dependencies are left to
+/// pg_restore's TOC. Parallel restore needs a seekable custom or directory
+/// archive, so other formats run with one job (plain SQL is refused).
+pub async fn restore_database(app: &AppHandle, conn: &str, dump_path: &str, jobs: usize) -> Result<usize, String> {
+    let path = Path::new(dump_path);
+    let format = dump_format(path)?;
+    if format == DumpFormat::Plain {
+        return Err(format!(
+            "{} looks like a plain SQL dump; pg_restore needs a custom (-Fc) or directory (-Fd) archive. Run plain dumps through psql instead.",
+            dump_path
+        ));
+    }
+    let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let mut jobs = jobs.clamp(1, cpus);
+    if format == DumpFormat::Tar && jobs > 1 {
+        let _ = app.emit("log", "  Tar archives can't be restored in parallel; use a custom (-Fc) or directory (-Fd) dump for -j. Restoring with 1 job.");
+        jobs = 1;
+    }
+    let _ = app.emit("log", format!("Restoring {} with {} job(s)...", dump_path, jobs));
+
+    let mut cmd = pg_command(&driver_bin(app, "pg_restore")?);
+    cmd.arg("--no-owner")
+        .arg("--no-privileges")
+        .arg("--verbose")
+        .arg(format!("--jobs={}", jobs))
+        .arg("-d")
+        .arg(conn)
+        .arg(path);
+
+    // Serially, a table is done when the next one starts (or the restore ends)
+    let parallel = jobs > 1;
+    let progress = std::sync::Arc::new(std::sync::Mutex::new((0usize, None::<String>)));
+    let emit_done = |app: &AppHandle, state: &mut (usize, Option<String>), table: String| {
+        state.0 += 1;
+        let _ = app.emit("restore_progress", RestoreProgress { table, tables_done: state.0 });
+    };
+    let (handle, state) = (app.clone(), progress.clone());
+    run_streaming_with(app, cmd, "pg_restore", move |line| {
+        if let Some(table) = restored_table(line, parallel) {
+            let mut state = state.lock().unwrap();
+            let finished = if parallel { Some(table) } else { state.1.replace(table) };
+            if let Some(finished) = finished {
+                emit_done(&handle, &mut state, finished);
+            }
+        }
+    })
+    .await?;
+
+    let mut state = progress.lock().unwrap();
+    if let Some(last) = state.1.take() {
+        emit_done(app, &mut state, last);
+    }
+    let tables = state.0;
+    let _ = app.emit("log", format!("Restore complete: {} table(s) loaded.", tables));
+    Ok(tables)
+}
+
+/// Server vs local pg_dump major versions
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct VersionCompat {
+    pub server_version: String,
+    pub pg_dump_version: String,
+    pub compatible: bool,
+}
+
+/// Major version from "pg_dump (PostgreSQL) 15.4" or "15.4 (Ubuntu ...)"
+fn major_version(text: &str) -> Option<u32> {
+    text.split_whitespace()
+        .find(|w| w.starts_with(|c: char| c.is_ascii_digit()))
+        .and_then(|w| w.split('.').next())
+        .and_then(|m| m.parse().ok())
+}
+
+/// pg_dump refuses to dump servers newer than itself. Compares the local
+/// pg_dump against the source server and warns before a dump can abort.
+pub async fn check_version_compat(window: &Window, app: &AppHandle, source_db: &str) -> Result<VersionCompat, String> {
+    let output = pg_command(&driver_bin(app, "pg_dump")?)
+        .arg("--version")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to start pg_dump: {}", e))?;
+    let pg_dump_version = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .trim_start_matches("pg_dump (PostgreSQL)")
+        .trim()
+        .to_string();
+
+    let server_version = Psql::locate(app)?
+        .query(source_db, "SHOW server_version")
+        .await?
+        .into_iter()
+        .next()
+        .and_then(|r| r.into_iter().next())
+        .ok_or("Could not read the server version")?;
+
+    let (server_major, client_major) = (major_version(&server_version), major_version(&pg_dump_version));
+    let compatible = match (server_major, client_major) {
+        (Some(server), Some(client)) => client >= server,
+        _ => false,
+    };
+    if !compatible {
+        window.emit("log", format!(
+            "WARNING: pg_dump {} is older than the source server (Postgres {}). Dumps will abort with a version mismatch. Switch to a release channel whose Pulse Pack ships pg_dump {} or newer.",
+            pg_dump_version,
+            server_version,
+            server_major.map(|m| m.to_string()).unwrap_or_else(|| server_version.clone())
+        )).unwrap();
+    }
+    Ok(VersionCompat {
+        server_version,
+        pg_dump_version,
+        compatible,
+    })
+}
+
+/// One driver binary started with the child-process environment
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct BinaryCheck {
+    pub binary: String,
+    pub path: Option<String>,
+    pub version: Option<String>,
+    pub error: Option<String>,
+    pub missing_runtime: Vec<String>, // Windows: MSVC runtime DLLs not found
+}
+
+/// Starts each Postgres client binary once (`--version`) so a missing shared
+/// library (e.g. libpq) shows up now rather than mid-migration
+pub async fn warm_up_drivers(app: &AppHandle) -> Vec<BinaryCheck> {
+    let mgr = PulseManager::new(app);
+    let mut checks = vec![];
+    for name in ["pg_dump", "pg_restore", "psql"] {
+        let mut check = BinaryCheck {
+            binary: name.to_string(),
+            path: None,
+            version: None,
+            error: None,
+            missing_runtime: vec![],
+        };
+        match mgr.resolve(PG_PACKAGE, &format!("{}{}", name, std::env::consts::EXE_SUFFIX)) {
+            Ok(bin) => {
+                check.path = Some(bin.to_string_lossy().to_string());
+                check.missing_runtime = deps::missing_msvc_runtime(&bin);
+                if !check.missing_runtime.is_empty() {
+                    check.error = Some(deps::msvc_runtime_message(&check.missing_runtime));
+                    checks.push(check);
+                    continue;
+                }
+                match pg_command(&bin).arg("--version").output().await {
+                    Ok(out) if out.status.success() => {
+                        check.version = Some(String::from_utf8_lossy(&out.stdout).trim().to_string());
+                    }
+                    Ok(out) => check.error = Some(String::from_utf8_lossy(&out.stderr).trim().to_string()),
+                    Err(e) => check.error = Some(format!("Failed to start: {}", e)),
+                }
+            }
+            Err(e) => check.error = Some(e),
+        }
+        checks.push(check);
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stderr_lines_survive_invalid_utf8() {
+        let mut reader: &[u8] = b"pg_dump: dumping table \"caf\xe9\"\r\nstill reading\nno newline at end";
+        let mut buf = vec![];
+        let mut lines = vec![];
+        while let Some(line) = read_lossy_line(&mut reader, &mut buf).await {
+            lines.push(line);
+        }
+        assert_eq!(lines, ["pg_dump: dumping table \"caf\u{fffd}\"", "still reading", "no newline at end"]);
+    }
+}
@@ -0,0 +1,167 @@
+use tauri::{AppHandle, Emitter, Window};
+
+use crate::checkpoint;
+use crate::db::{copy_path, quote_ident, quote_literal, Psql};
+
+/// A table kept in sync by high-water mark
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct SyncTable {
+    #[serde(default = "default_schema")]
+    pub schema: String,
+    pub table: String,
+    pub watermark_column: String, // monotonic: updated_at, serial id, ...
+    pub key_columns: Vec<String>, // conflict target for the upsert
+}
+
+fn default_schema() -> String {
+    "public".to_string()
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct TableSyncResult {
+    pub table: String,
+    pub rows_synced: u64,
+    pub watermark: Option<String>,
+}
+
+impl SyncTable {
+    fn qualified(&self) -> String {
+        format!("{}.{}", quote_ident(&self.schema), quote_ident(&self.table))
+    }
+
+    fn checkpoint_key(&self) -> String {
+        format!("{}.{}", self.schema, self.table)
+    }
+}
+
+/// Copies rows newer than each table's stored watermark from source to
+/// destination and upserts them. Watermarks advance only after the
+/// destination transaction commits, so a failed run is safe to repeat.
+pub async fn sync_incremental(
+    window: &Window,
+    app: &AppHandle,
+    source_db: &str,
+    dest_db: &str,
+    tables: &[SyncTable],
+) -> Result<Vec<TableSyncResult>, String> {
+    let psql = Psql::locate(app)?;
+    let mut cp = checkpoint::load(app)?;
+    let mut results = vec![];
+
+    for table in tables {
+        let key = table.checkpoint_key();
+        let col = quote_ident(&table.watermark_column);
+        let low = cp.db_watermarks.get(&key).cloned();
+
+        // Fix the upper bound first so rows written mid-sync wait for the next run
+        let high = psql
+            .query(source_db, &format!("SELECT max({})::text FROM {}", col, table.qualified()))
+            .await?
+            .first()
+            .and_then(|r| r.first().cloned())
+            .filter(|v| !v.is_empty());
+
+        let Some(high) = high else {
+            window.emit("log", format!("  {}: empty, nothing to sync", key)).unwrap();
+            results.push(TableSyncResult { table: key, rows_synced: 0, watermark: low });
+            continue;
+        };
+        if low.as_deref() == Some(high.as_str()) {
+            window.emit("log", format!("  {}: up to date", key)).unwrap();
+            results.push(TableSyncResult { table: key, rows_synced: 0, watermark: low });
+            continue;
+        }
+
+        let mut filter = format!("{} <= {}", col, quote_literal(&high));
+        if let Some(low) = &low {
+            filter = format!("{} > {} AND {}", col, quote_literal(low), filter);
+        }
+
+        let csv = std::env::temp_dir().join(format!("devpulse_sync_{}_{}.csv", table.schema, table.table));
+        psql.execute(
+            source_db,
+            &[format!(
+                "\\copy (SELECT * FROM {} WHERE {}) TO {} WITH (FORMAT csv)",
+                table.qualified(),
+                filter,
+                copy_path(&csv)
+            )],
+        )
+        .await?;
+
+        let result = upsert_from_csv(&psql, dest_db, table, &csv).await;
+        let _ = std::fs::remove_file(&csv);
+        let rows = result?;
+
+        cp.db_watermarks.insert(key.clone(), high.clone());
+        checkpoint::save(app, &cp)?;
+
+        window
+            .emit("log", format!("  {}: {} rows synced (watermark {})", key, rows, high))
+            .unwrap();
+        window
+            .emit("db_sync_table", serde_json::json!({ "table": key, "rows": rows }))
+            .unwrap();
+        results.push(TableSyncResult { table: key, rows_synced: rows, watermark: Some(high) });
+    }
+
+    Ok(results)
+}
+
+/// Loads the CSV into a temp table and upserts it in one transaction.
+/// Returns the number of rows loaded.
+async fn upsert_from_csv(psql: &Psql, dest_db: &str, table: &SyncTable, csv: &std::path::Path) -> Result<u64, String> {
+    let columns: Vec<String> = psql
+        .query(
+            dest_db,
+            &format!(
+                "SELECT column_name FROM information_schema.columns WHERE table_schema = {} AND table_name = {} ORDER BY ordinal_position",
+                quote_literal(&table.schema),
+                quote_literal(&table.table)
+            ),
+        )
+        .await?
+        .into_iter()
+        .filter_map(|r| r.into_iter().next())
+        .collect();
+    if columns.is_empty() {
+        return Err(format!("Table {} not found on destination", table.checkpoint_key()));
+    }
+
+    let conflict = table.key_columns.iter().map(|c| quote_ident(c)).collect::<Vec<_>>().join(", ");
+    let updates: Vec<String> = columns
+        .iter()
+        .filter(|c| !table.key_columns.contains(c))
+        .map(|c| format!("{0} = EXCLUDED.{0}", quote_ident(c)))
+        .collect();
+    let on_conflict = if updates.is_empty() {
+        format!("ON CONFLICT ({}) DO NOTHING", conflict)
+    } else {
+        format!("ON CONFLICT ({}) DO UPDATE SET {}", conflict, updates.join(", "))
+    };
+
+    let out = psql
+        .execute(
+            dest_db,
+            &[
+                format!(
+                    "CREATE TEMP TABLE _devpulse_sync (LIKE {} INCLUDING DEFAULTS) ON COMMIT DROP",
+                    table.qualified()
+                ),
+                format!("\\copy _devpulse_sync FROM {} WITH (FORMAT csv)", copy_path(csv)),
+                format!(
+                    "INSERT INTO {} SELECT * FROM _devpulse_sync {}",
+                    table.qualified(),
+                    on_conflict
+                ),
+                "SELECT count(*) FROM _devpulse_sync".to_string(),
+            ],
+        )
+        .await?;
+
+    Ok(out
+        .lines()
+        .rev()
+        .find_map(|l| l.trim().parse::<u64>().ok())
+        .unwrap_or(0))
+}
@@ -1,8 +1,10 @@
 use std::fs;
-use std::io::Cursor;
-use std::path::PathBuf;
+use std::io::{Cursor, Read, Write};
+use std::path::{Component, Path, PathBuf};
 use tauri::{AppHandle, Emitter, Manager, Window};
 
+use crate::cancel::CancelToken;
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct GitHubAsset {
     pub name: String,
@@ -40,10 +42,53 @@ pub struct PulseManifest {
     pub channels: Option<std::collections::HashMap<String, PulseChannel>>,
     // "Unwrap Experience" - Media/Toasts
     pub pulse_rollout: Option<PulseRollout>,
-    pub packages: std::collections::HashMap<String, PulsePackageSpec>,
+    pub packages: std::collections::HashMap<String, PulsePackageEntry>,
     pub message_of_the_day: Option<String>,
 }
 
+/// Result of `preview_manifest`: schema errors, or the parsed manifest
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ManifestPreview {
+    pub valid: bool,
+    pub errors: Vec<String>, // "path: problem"
+    pub manifest: Option<PulseManifest>,
+}
+
+/// A `packages` entry: either a logical package id mapping OS keys to specs
+/// ({ "postgres-15": { "win32-x64": {...} } }) or, in older flat manifests,
+/// an OS key mapping straight to a spec ({ "win32-x64": {...} })
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum PulsePackageEntry {
+    Spec(PulsePackageSpec),
+    Variants(std::collections::HashMap<String, PulsePackageSpec>),
+}
+
+impl PulseManifest {
+    /// Logical package ids; empty for a flat (single-package) manifest
+    pub fn package_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .packages
+            .iter()
+            .filter(|(_, e)| matches!(e, PulsePackageEntry::Variants(_)))
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// package_id -> OS variant; flat manifests fall back to the OS key alone
+    pub fn package_for(&self, package_id: &str, os_key: &str) -> Option<&PulsePackageSpec> {
+        match self.packages.get(package_id) {
+            Some(PulsePackageEntry::Variants(variants)) => variants.get(os_key),
+            _ => match self.packages.get(os_key) {
+                Some(PulsePackageEntry::Spec(spec)) => Some(spec),
+                _ => None,
+            },
+        }
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct PulsePackageSpec {
     pub url: String,
@@ -52,10 +97,98 @@ pub struct PulsePackageSpec {
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct PulseConfig {
     pub channel: String, // "stable", "insider"
     pub supabase_url: String,
     pub supabase_key: String, // Public Anon Key
+    pub skip_bad_archive_entries: bool, // false = abort on first unreadable entry
+    pub doh_enabled: bool, // resolve hostnames via DNS-over-HTTPS
+    pub doh_endpoint: String,
+    pub performance: PerformanceConfig,
+    pub keep_download_cache: bool, // keep verified archives in bin/cache after install
+    pub hooks: crate::hooks::HooksConfig,
+    pub ca_cert_path: Option<String>, // extra PEM root (self-hosted / TLS-intercepting proxies)
+    pub accept_invalid_certs: bool,   // DANGEROUS: disables TLS verification entirely
+    pub storage_quota_bytes: Option<u64>, // destination plan quota, for preflight warnings
+    pub monitor_enabled: bool, // start the localhost log monitor with the app
+    pub monitor_port: u16,
+    pub backup_retention: usize, // scheduled archives kept per profile
+    pub s3: crate::s3::S3Config,
+    pub trusted_hosts: Vec<String>, // extra download hosts (mirrors) beyond the built-in allowlist
+    pub egress_warning_bytes: u64, // migrations reading more than this need confirmation; 0 = never
+    pub manifest_public_key: String, // pinned ed25519 key (hex/base64) for depot manifests
+    pub require_signed_manifest: bool,
+    pub abort_on_version_mismatch: bool, // false = warn when manifest, package and binary versions disagree
+    pub collision_strategy: crate::storage::CollisionStrategy, // existing destination objects: overwrite, skip, rename
+    pub health_probe_interval_secs: u64, // endpoint pings during a migration; 0 = off
+    pub health_max_outage_secs: u64, // paused this long on an outage before the migration fails
+    pub reconcile_after_migration: bool, // compare counts per bucket and re-transfer missing keys at the end
+}
+
+/// Transfer tuning knobs. Values are clamped to safe ranges on load.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct PerformanceConfig {
+    pub transfer_concurrency: usize, // objects in flight per bucket
+    pub upload_chunk_bytes: u64,     // upload bodies are streamed in chunks of this size
+    pub list_page_size: usize,       // objects per list request
+    pub max_bytes_per_sec: u64,      // 0 = unlimited
+    pub request_timeout_secs: u64,   // connect + read-idle timeout
+    pub restore_jobs: usize,         // pg_restore -j; capped at the CPU count when run
+    pub signed_uploads: bool,        // PUT to signed upload URLs instead of POSTing with the key
+    pub streaming_transfers: bool,   // pipe downloads into uploads instead of buffering each object
+}
+
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            transfer_concurrency: 4,
+            upload_chunk_bytes: 8 * 1024 * 1024,
+            list_page_size: 100,
+            max_bytes_per_sec: 0,
+            request_timeout_secs: 60,
+            restore_jobs: 4,
+            signed_uploads: false,
+            streaming_transfers: true,
+        }
+    }
+}
+
+impl PerformanceConfig {
+    pub fn clamped(self) -> Self {
+        Self {
+            transfer_concurrency: self.transfer_concurrency.clamp(1, 32),
+            upload_chunk_bytes: self.upload_chunk_bytes.clamp(256 * 1024, 64 * 1024 * 1024),
+            list_page_size: self.list_page_size.clamp(10, 1000),
+            max_bytes_per_sec: self.max_bytes_per_sec,
+            request_timeout_secs: self.request_timeout_secs.clamp(5, 600),
+            restore_jobs: self.restore_jobs.clamp(1, 32),
+            signed_uploads: self.signed_uploads,
+            streaming_transfers: self.streaming_transfers,
+        }
+    }
+}
+
+/// Last successfully resolved channel release, persisted as release.json
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct ReleaseState {
+    pub channel: String,
+    pub version: String,
+    pub manifest_url: String,
+    pub rollout_message: Option<String>,
+    #[serde(default)]
+    pub release_notes: Option<String>, // raw markdown/text
+    pub resolved_at: u64, // unix seconds
+}
+
+/// Notes of a pulse_releases row; the column is `release_notes` or `changelog`
+fn release_notes(release: &serde_json::Value) -> Option<String> {
+    ["release_notes", "changelog"]
+        .iter()
+        .filter_map(|c| release[*c].as_str())
+        .find(|n| !n.trim().is_empty())
+        .map(|n| n.to_string())
 }
 
 #[derive(serde::Serialize, Clone, Debug)]
@@ -72,48 +205,452 @@ impl Default for PulseConfig {
             // Pre-configured for DevPulse - User can override in config.json
             supabase_url: "https://dcmgooupmorhqjbdaxtm.supabase.co".to_string(),
             supabase_key: "".to_string(), // TODO: Must be provided by user or build arg
+            skip_bad_archive_entries: true,
+            doh_enabled: false,
+            doh_endpoint: "https://1.1.1.1/dns-query".to_string(),
+            performance: PerformanceConfig::default(),
+            keep_download_cache: false,
+            hooks: crate::hooks::HooksConfig::default(),
+            ca_cert_path: None,
+            accept_invalid_certs: false,
+            storage_quota_bytes: None,
+            monitor_enabled: false,
+            monitor_port: 47821,
+            backup_retention: 7,
+            s3: crate::s3::S3Config::default(),
+            trusted_hosts: vec![],
+            egress_warning_bytes: 10_000_000_000,
+            manifest_public_key: "".to_string(),
+            require_signed_manifest: false,
+            abort_on_version_mismatch: false,
+            collision_strategy: crate::storage::CollisionStrategy::Overwrite,
+            health_probe_interval_secs: 30,
+            health_max_outage_secs: 600,
+            reconcile_after_migration: false,
+        }
+    }
+}
+
+/// Environment for running a driver-pack binary: its `bin` and sibling
+/// `lib` directories prepended to the variable the OS loader searches for
+/// shared libraries (PATH on Windows, LD_LIBRARY_PATH on Linux,
+/// DYLD_LIBRARY_PATH on macOS), and to PATH so helper binaries resolve too
+pub fn child_process_env(binary: &Path) -> Vec<(&'static str, std::ffi::OsString)> {
+    child_process_env_for(binary, std::env::consts::OS, |var| std::env::var_os(var))
+}
+
+/// `child_process_env` for the loader conventions of `os`, reading the
+/// current values through `current`
+fn child_process_env_for(
+    binary: &Path,
+    os: &str,
+    current: impl Fn(&str) -> Option<std::ffi::OsString>,
+) -> Vec<(&'static str, std::ffi::OsString)> {
+    let Some(bin_dir) = binary.parent() else {
+        return vec![];
+    };
+    let mut dirs = vec![bin_dir.to_path_buf()];
+    if let Some(lib_dir) = bin_dir.parent().map(|p| p.join("lib")).filter(|p| p.is_dir()) {
+        dirs.push(lib_dir);
+    }
+
+    let prepend = |var: &'static str| {
+        let existing = current(var).unwrap_or_default();
+        // An unset variable splits into one empty entry, which loaders read as the working directory
+        let inherited = std::env::split_paths(&existing).filter(|p| !p.as_os_str().is_empty());
+        let paths = dirs.iter().cloned().chain(inherited);
+        std::env::join_paths(paths).ok().map(|joined| (var, joined))
+    };
+    let loader_var = match os {
+        "windows" => None,
+        "macos" => Some("DYLD_LIBRARY_PATH"),
+        _ => Some("LD_LIBRARY_PATH"),
+    };
+    std::iter::once("PATH")
+        .chain(loader_var)
+        .filter_map(prepend)
+        .collect()
+}
+
+/// MSVC runtime DLLs the Windows Postgres binaries link against
+const MSVC_RUNTIME_DLLS: [&str; 3] = ["vcruntime140.dll", "vcruntime140_1.dll", "msvcp140.dll"];
+
+/// Runtime DLLs neither bundled next to `binary` nor installed in System32.
+/// Always empty off Windows. A missing runtime makes pg_dump.exe die with
+/// 0xc000007b before printing anything.
+pub fn missing_msvc_runtime(binary: &Path) -> Vec<String> {
+    if std::env::consts::OS != "windows" {
+        return vec![];
+    }
+    let mut dirs: Vec<PathBuf> = binary.parent().map(Path::to_path_buf).into_iter().collect();
+    if let Some(root) = std::env::var_os("SystemRoot") {
+        dirs.push(PathBuf::from(root).join("System32"));
+    }
+    MSVC_RUNTIME_DLLS
+        .iter()
+        .filter(|dll| !dirs.iter().any(|d| d.join(dll).is_file()))
+        .map(|dll| dll.to_string())
+        .collect()
+}
+
+/// User-facing explanation for `missing_msvc_runtime`
+pub fn msvc_runtime_message(missing: &[String]) -> String {
+    format!(
+        "Microsoft Visual C++ Redistributable (x64) required: {} not found. Install it from https://aka.ms/vs/17/release/vc_redist.x64.exe and retry.",
+        missing.join(", ")
+    )
+}
+
+/// What `install_latest` installed and the versions each source claims
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct InstallOutcome {
+    pub files: Vec<String>,
+    pub manifest_version: Option<String>, // channel version declared by the manifest
+    pub package_version: Option<String>,  // version in the package file name
+    pub binary_version: Option<String>,   // `pg_dump --version` after extraction
+    pub consistent: bool,
+}
+
+/// First dotted version in free text: "pg_dump (PostgreSQL) 15.4" -> "15.4"
+fn version_in_text(text: &str) -> Option<String> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map(|w| w.trim_matches('.'))
+        .find(|w| w.contains('.') && w.split('.').all(|p| !p.is_empty()))
+        .map(str::to_string)
+}
+
+/// Version in a package file name: ".../postgres-15.4-linux-x64.tar.gz" -> "15.4"
+fn version_in_url(url: &str) -> Option<String> {
+    version_in_text(url.rsplit('/').next().unwrap_or(url))
+}
+
+/// "15.4" agrees with "15.4.1" and "15"; compared on the shared components
+fn versions_agree(a: &str, b: &str) -> bool {
+    a.split('.').zip(b.split('.')).all(|(x, y)| x == y)
+}
+
+/// Per-package install record, persisted as bin/<package>/installed.json
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct InstalledRecord {
+    pub package_id: String,
+    pub archive_checksum: Option<String>,
+    pub installed_at: u64,                                   // unix seconds
+    pub binaries: std::collections::BTreeMap<String, String>, // path relative to the package -> sha256
+    #[serde(default)]
+    pub version: Option<String>, // what pg_dump --version reported at record time
+}
+
+/// Directories under the drivers root that are not packages
+const NON_PACKAGE_DIRS: [&str; 1] = ["cache"];
+
+/// One package's line in a registry reconciliation
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RegistryChange {
+    pub package_id: String,
+    pub action: String, // ADDED, REMOVED, UNCHANGED
+    pub version: Option<String>,
+    pub detail: String,
+}
+
+/// Result of re-hashing an installed package against its install record
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct InstallVerification {
+    pub ok: bool,
+    pub missing: Vec<String>,
+    pub mismatched: Vec<String>,
+    pub suggestion: Option<String>,
+}
+
+/// Depot manifest used when the release service can't be reached
+const DEFAULT_MANIFEST_URL: &str =
+    "https://raw.githubusercontent.com/devpulse-tools/dptools-deps/main/deps/apps/ezdb/manifest.json";
+
+/// A release channel the user can switch to
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ChannelInfo {
+    pub slug: String,
+    pub version: String,
+    pub required: bool,
+}
+
+/// Binaries a Pulse Pack is useless without; skipping one of these is fatal
+const ESSENTIAL_BINARIES: [&str; 3] = ["pg_dump", "pg_restore", "psql"];
+
+fn is_essential_entry(entry_name: &str) -> bool {
+    let file_name = entry_name.rsplit(['/', '\\']).next().unwrap_or(entry_name);
+    let stem = file_name.strip_suffix(".exe").unwrap_or(file_name);
+    ESSENTIAL_BINARIES.contains(&stem)
+}
+
+/// Runtime config file: AppData/DevPulse/config.json
+pub fn config_path(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap()
+        .join("DevPulse")
+        .join("config.json")
+}
+
+/// Loads config.json, writing the defaults on first run. Environment
+/// overrides are applied on top and never written back.
+pub fn load_config(app: &AppHandle) -> PulseConfig {
+    with_env_overrides(app, load_file_config(app))
+}
+
+/// config.json alone, without environment overrides: the base for anything
+/// that saves the config back
+fn load_file_config(app: &AppHandle) -> PulseConfig {
+    let config_path = config_path(app);
+    if config_path.exists() {
+        return read_config_or_backup(app, &config_path);
+    }
+    let def = PulseConfig::default();
+    if let Some(parent) = config_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(&config_path, serde_json::to_string_pretty(&def).unwrap());
+    def
+}
+
+/// Changes config.json as stored. Starts from the file, not the runtime
+/// config, so DEVPULSE_* overrides (secrets included) are never persisted.
+pub fn update_config(app: &AppHandle, change: impl FnOnce(&mut PulseConfig)) -> Result<PulseConfig, String> {
+    let mut config = load_file_config(app);
+    change(&mut config);
+    save_config(app, &config)?;
+    Ok(config)
+}
+
+/// Prefix of environment overrides for top-level scalar settings, e.g. DEVPULSE_CHANNEL=insider
+const ENV_PREFIX: &str = "DEVPULSE_";
+
+/// Settings masked in the effective config (dotted paths)
+const SECRET_SETTINGS: [&str; 3] = ["supabase_key", "s3.source.secret_access_key", "s3.dest.secret_access_key"];
+
+/// The config in effect and where each setting came from (default, file or env)
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct EffectiveConfig {
+    pub config: serde_json::Value, // secrets masked
+    pub sources: std::collections::BTreeMap<String, String>, // dotted path -> source
+}
+
+/// DEVPULSE_<FIELD> values for the scalar fields of `defaults`. Strings are
+/// taken verbatim; other fields parse as JSON (true, 8080) or fall back to text.
+fn env_overrides(defaults: &serde_json::Map<String, serde_json::Value>) -> Vec<(String, serde_json::Value)> {
+    defaults
+        .iter()
+        .filter(|(_, v)| !v.is_object() && !v.is_array())
+        .filter_map(|(field, default)| {
+            let raw = std::env::var(format!("{}{}", ENV_PREFIX, field.to_uppercase())).ok()?;
+            let value = if default.is_string() {
+                serde_json::Value::String(raw)
+            } else {
+                serde_json::from_str(&raw).unwrap_or(serde_json::Value::String(raw))
+            };
+            Some((field.clone(), value))
+        })
+        .collect()
+}
+
+fn with_env_overrides(app: &AppHandle, config: PulseConfig) -> PulseConfig {
+    let Ok(serde_json::Value::Object(mut fields)) = serde_json::to_value(&config) else {
+        return config;
+    };
+    let overrides = env_overrides(&fields);
+    if overrides.is_empty() {
+        return config;
+    }
+    fields.extend(overrides);
+    match serde_json::from_value::<PulseConfig>(serde_json::Value::Object(fields)) {
+        Ok(mut merged) => {
+            merged.performance = merged.performance.clamped();
+            merged
+        }
+        Err(e) => {
+            let _ = app.emit("log", format!("WARNING: Ignoring {}* environment overrides: {}", ENV_PREFIX, e));
+            config
+        }
+    }
+}
+
+/// Resolves defaults, then config.json, then environment overrides, field by
+/// field, recording which layer supplied each value
+pub fn effective_config(app: &AppHandle) -> Result<EffectiveConfig, String> {
+    let mut merged = serde_json::to_value(PulseConfig::default()).map_err(|e| e.to_string())?;
+    let fields = merged.as_object_mut().ok_or("Config is not an object")?;
+    let defaults = fields.clone();
+
+    let mut sources = std::collections::BTreeMap::new();
+    for (field, value) in &defaults {
+        match value.as_object() {
+            Some(nested) => sources.extend(nested.keys().map(|k| (format!("{}.{}", field, k), "default".to_string()))),
+            None => {
+                sources.insert(field.clone(), "default".to_string());
+            }
+        }
+    }
+
+    let file: Option<serde_json::Value> = fs::read_to_string(config_path(app))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok());
+    if let Some(serde_json::Value::Object(file)) = file {
+        for (field, value) in file.into_iter().filter(|(f, _)| defaults.contains_key(f)) {
+            match (fields.get_mut(&field).and_then(|v| v.as_object_mut()), value) {
+                (Some(nested), serde_json::Value::Object(file_nested)) => {
+                    for (key, v) in file_nested {
+                        sources.insert(format!("{}.{}", field, key), "file".to_string());
+                        nested.insert(key, v);
+                    }
+                }
+                (_, value) => {
+                    sources.insert(field.clone(), "file".to_string());
+                    fields.insert(field, value);
+                }
+            }
+        }
+    }
+
+    for (field, value) in env_overrides(&defaults) {
+        sources.insert(field.clone(), "env".to_string());
+        fields.insert(field, value);
+    }
+
+    let mut config: PulseConfig = serde_json::from_value(merged)
+        .map_err(|e| format!("Settings don't form a valid config ({}); the app is using defaults.", e))?;
+    config.performance = config.performance.clamped();
+
+    let mut config = serde_json::to_value(config).map_err(|e| e.to_string())?;
+    for path in SECRET_SETTINGS {
+        let mut parts: Vec<&str> = path.split('.').collect();
+        let leaf = parts.pop().unwrap_or_default();
+        let parent = parts.iter().try_fold(&mut config, |v, p| v.get_mut(*p));
+        if let Some(serde_json::Value::String(secret)) = parent.and_then(|p| p.get_mut(leaf)) {
+            if !secret.is_empty() {
+                *secret = "********".to_string();
+            }
+        }
+    }
+    Ok(EffectiveConfig { config, sources })
+}
+
+/// Parses a config file. A corrupt file is copied to `<name>.bak` and a
+/// warning logged before falling back to defaults, so no values are lost.
+pub fn read_config_or_backup(app: &AppHandle, path: &Path) -> PulseConfig {
+    let (config, warning) = parse_config_or_backup(path);
+    if let Some(warning) = warning {
+        let _ = app.emit("log", warning);
+    }
+    config
+}
+
+/// The parsing half of `read_config_or_backup`: the config plus the warning to log
+fn parse_config_or_backup(path: &Path) -> (PulseConfig, Option<String>) {
+    let data = fs::read_to_string(path).unwrap_or_default();
+    match serde_json::from_str::<PulseConfig>(&data) {
+        Ok(mut config) => {
+            config.performance = config.performance.clamped();
+            (config, None)
         }
+        Err(e) => {
+            let backup = path.with_extension("json.bak");
+            let saved = fs::copy(path, &backup)
+                .map(|_| format!("Your file was saved to {}.", backup.display()))
+                .unwrap_or_else(|copy_err| format!("Backup failed: {}.", copy_err));
+            let warning = format!("WARNING: {} is corrupt ({}). Using defaults. {}", path.display(), e, saved);
+            (PulseConfig::default(), Some(warning))
+        }
+    }
+}
+
+/// Writes the runtime config.json
+pub fn save_config(app: &AppHandle, config: &PulseConfig) -> Result<(), String> {
+    let path = config_path(app);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
+    let data = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    fs::write(&path, data).map_err(|e| e.to_string())
 }
 
 pub struct PulseManager {
     base_path: PathBuf,
+    release_path: PathBuf,
     client: reqwest::Client,
     config: PulseConfig,
+    cancel: CancelToken,
 }
 
 impl PulseManager {
     pub fn new(app: &AppHandle) -> Self {
         let app_data = app.path().app_data_dir().unwrap();
         let pulse_root = app_data.join("DevPulse").join("bin");
-        let config_path = app_data.join("DevPulse").join("config.json");
+        let release_path = app_data.join("DevPulse").join("release.json");
 
         if !pulse_root.exists() {
             let _ = fs::create_dir_all(&pulse_root);
         }
 
         // Load Config or Create Default
-        let config = if config_path.exists() {
-            let data = fs::read_to_string(&config_path).unwrap_or_default();
-            serde_json::from_str(&data).unwrap_or_default()
-        } else {
-            let def = PulseConfig::default();
-            let _ = fs::write(&config_path, serde_json::to_string_pretty(&def).unwrap());
-            def
-        };
-
-        let client = reqwest::Client::builder()
-            .user_agent("DevPulse-Migrator/1.0")
-            .build()
-            .unwrap();
+        let config = load_config(app);
+        let client = crate::http::client_for_app(app);
 
         Self {
             base_path: pulse_root,
+            release_path,
             client,
             config,
+            cancel: CancelToken::default(),
         }
     }
 
+    /// Lets downloads stop between chunks once `token` is cancelled
+    pub fn with_cancel(mut self, token: CancelToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    pub fn config(&self) -> &PulseConfig {
+        &self.config
+    }
+
+    /// Reads the persisted release state, if a resolve has ever succeeded
+    pub fn release_state(&self) -> Option<ReleaseState> {
+        let data = fs::read_to_string(&self.release_path).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    /// Re-resolves the active release for the configured channel
+    pub async fn refresh_release(&self, window: &Window) -> Result<ReleaseState, String> {
+        self.resolve_active_release(window).await?;
+        self.release_state()
+            .ok_or_else(|| "Release resolved but could not be persisted".to_string())
+    }
+
+    /// Install root of the driver packs (bin/)
+    pub fn bin_path(&self) -> &PathBuf {
+        &self.base_path
+    }
+
+    /// Persisted release state file (release.json)
+    pub fn release_path(&self) -> &PathBuf {
+        &self.release_path
+    }
+
+    /// Package ids with an install directory under bin/ (the download cache excluded)
+    pub fn installed_packages(&self) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(&self.base_path) else {
+            return vec![];
+        };
+        let mut ids: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|id| id != "cache")
+            .collect();
+        ids.sort();
+        ids
+    }
+
     pub fn resolve(&self, package_id: &str, binary_name: &str) -> Result<PathBuf, String> {
         let pkg_root = self.base_path.join(package_id);
 
@@ -136,12 +673,168 @@ impl PulseManager {
         ))
     }
 
+    fn installed_record_path(&self, package_id: &str) -> PathBuf {
+        self.base_path.join(package_id).join("installed.json")
+    }
+
+    /// Hashes the essential binaries after an install so later drift can be detected
+    fn record_install(&self, package_id: &str, archive_checksum: Option<&str>) -> Result<(), String> {
+        let pkg_root = self.base_path.join(package_id);
+        let mut binaries = std::collections::BTreeMap::new();
+        for name in ESSENTIAL_BINARIES {
+            let Ok(path) = self.resolve(package_id, &format!("{}{}", name, std::env::consts::EXE_SUFFIX)) else {
+                continue;
+            };
+            let data = fs::read(&path).map_err(|e| e.to_string())?;
+            let rel = path.strip_prefix(&pkg_root).unwrap_or(&path);
+            binaries.insert(rel.to_string_lossy().replace('\\', "/"), sha256_hex(&data));
+        }
+        let record = InstalledRecord {
+            package_id: package_id.to_string(),
+            archive_checksum: archive_checksum.map(|c| c.to_lowercase()),
+            installed_at: crate::report::now_secs(),
+            binaries,
+            version: self.binary_version(package_id),
+        };
+        let json = serde_json::to_string_pretty(&record).map_err(|e| e.to_string())?;
+        fs::write(self.installed_record_path(package_id), json).map_err(|e| e.to_string())
+    }
+
+    /// Re-hashes the recorded binaries and checks every essential binary still resolves
+    pub fn verify_installed(&self, package_id: &str) -> InstallVerification {
+        let pkg_root = self.base_path.join(package_id);
+        let mut missing = vec![];
+        let mut mismatched = vec![];
+
+        let record = self.read_installed_record(package_id);
+        match &record {
+            Some(record) => {
+                for (rel, expected) in &record.binaries {
+                    match fs::read(pkg_root.join(rel)) {
+                        Ok(data) if sha256_hex(&data).eq_ignore_ascii_case(expected) => {}
+                        Ok(_) => mismatched.push(rel.clone()),
+                        Err(_) => missing.push(rel.clone()),
+                    }
+                }
+            }
+            None => missing.push("installed.json".to_string()),
+        }
+        for name in ESSENTIAL_BINARIES {
+            let binary = format!("{}{}", name, std::env::consts::EXE_SUFFIX);
+            if self.resolve(package_id, &binary).is_err() && !missing.iter().any(|m| m.ends_with(&binary)) {
+                missing.push(binary);
+            }
+        }
+
+        let ok = missing.is_empty() && mismatched.is_empty();
+        InstallVerification {
+            ok,
+            suggestion: (!ok).then(|| match record {
+                Some(_) => format!("Package {} has drifted since install. Reinstall the drivers to repair it.", package_id),
+                None => format!("Package {} has no install record. Reinstall the drivers to enable verification.", package_id),
+            }),
+            missing,
+            mismatched,
+        }
+    }
+
+    /// Confirms the package's pg_dump was built for this OS and CPU by reading
+    /// its executable header, whatever its file extension says
+    pub fn check_binary_platform(&self, package_id: &str) -> Result<(), String> {
+        let binary = [format!("pg_dump{}", std::env::consts::EXE_SUFFIX), "pg_dump.exe".to_string(), "pg_dump".to_string()]
+            .iter()
+            .find_map(|name| self.resolve(package_id, name).ok())
+            .ok_or_else(|| format!("Binary pg_dump not found in package {}", package_id))?;
+        // Headers sit at the start of the file; no need to read the whole binary
+        let mut data = vec![];
+        fs::File::open(&binary)
+            .and_then(|f| f.take(4096).read_to_end(&mut data))
+            .map_err(|e| e.to_string())?;
+        let (os, arch) = binary_platform(&data)
+            .ok_or_else(|| format!("{} is not a recognised executable.", binary.display()))?;
+
+        let (host_os, host_arch) = (std::env::consts::OS, std::env::consts::ARCH);
+        let os_matches = os == host_os || (os == "linux" && host_os != "windows" && host_os != "macos");
+        if !os_matches || arch.is_some_and(|a| a != host_arch) {
+            return Err(format!(
+                "Installed package is for {}/{}, but this is {}/{}. The manifest served the wrong platform build.",
+                os,
+                arch.unwrap_or("any"),
+                host_os,
+                host_arch
+            ));
+        }
+        Ok(())
+    }
+
+    fn read_installed_record(&self, package_id: &str) -> Option<InstalledRecord> {
+        fs::read_to_string(self.installed_record_path(package_id))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+    }
+
+    /// Brings the per-package install records in line with the disk: packages
+    /// whose binaries are present but unrecorded get a record (version from
+    /// `pg_dump --version`), records whose binaries are gone are removed
+    pub fn reconcile_registry(&self) -> Result<Vec<RegistryChange>, String> {
+        let entries = match fs::read_dir(&self.base_path) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(format!("Cannot read {}: {}", self.base_path.display(), e)),
+        };
+        let mut package_ids: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|name| !name.starts_with('.') && !NON_PACKAGE_DIRS.contains(&name.as_str()))
+            .collect();
+        package_ids.sort();
+
+        let pg_dump = format!("pg_dump{}", std::env::consts::EXE_SUFFIX);
+        let mut changes = vec![];
+        for package_id in package_ids {
+            let present = self.resolve(&package_id, &pg_dump).is_ok();
+            let record = self.read_installed_record(&package_id);
+            let record_path = self.installed_record_path(&package_id);
+            let change = |action: &str, version: Option<String>, detail: &str| RegistryChange {
+                package_id: package_id.clone(),
+                action: action.to_string(),
+                version,
+                detail: detail.to_string(),
+            };
+            match (present, record) {
+                (true, None) => {
+                    self.record_install(&package_id, None)?;
+                    let version = self.read_installed_record(&package_id).and_then(|r| r.version);
+                    changes.push(change("ADDED", version, "Binaries present without an install record; recorded them."));
+                }
+                (false, Some(record)) => {
+                    fs::remove_file(&record_path).map_err(|e| e.to_string())?;
+                    // Leave the directory if anything else is still in it
+                    let _ = fs::remove_dir(self.base_path.join(&package_id));
+                    changes.push(change("REMOVED", record.version, "Recorded binaries are gone; removed the install record."));
+                }
+                (true, Some(record)) => changes.push(change("UNCHANGED", record.version, "Installed and recorded.")),
+                (false, None) if record_path.exists() => {
+                    fs::remove_file(&record_path).map_err(|e| e.to_string())?;
+                    changes.push(change("REMOVED", None, "Unreadable install record without binaries; removed it."));
+                }
+                (false, None) => {}
+            }
+        }
+        Ok(changes)
+    }
+
     pub fn check_package(&self, package_id: &str) -> PulsePackage {
         // We assume if we can resolve pg_dump, the package is healthy enough
-        match self.resolve(package_id, "pg_dump.exe") {
+        let pg_dump = format!("pg_dump{}", std::env::consts::EXE_SUFFIX);
+        match self.resolve(package_id, &pg_dump) {
             Ok(_) => PulsePackage {
                 id: package_id.to_string(),
-                version: "detected".to_string(), // In future: read local manifest
+                version: self
+                    .read_installed_record(package_id)
+                    .and_then(|r| r.version)
+                    .unwrap_or_else(|| "detected".to_string()),
                 status: "INSTALLED".to_string(),
             },
             Err(_) => PulsePackage {
@@ -160,7 +853,7 @@ impl PulseManager {
         package_id: &str,
         repo_owner: &str,
         repo_name: &str,
-    ) -> Result<(), String> {
+    ) -> Result<Vec<String>, String> {
         let url = format!(
             "https://api.github.com/repos/{}/{}/releases/latest",
             repo_owner, repo_name
@@ -202,29 +895,21 @@ impl PulseManager {
             )
             .unwrap();
 
-        self.download_and_extract(window, package_id, &asset.browser_download_url)
+        // GitHub assets carry no checksum; the cache is only reused when verifiable
+        self.download_and_extract(window, package_id, &asset.browser_download_url, None)
             .await
     }
 
-    /// STEP 1: RESOLVE - Ask Supabase "Brain" for the correct Manifest
-    async fn resolve_active_release(&self, window: &Window) -> Result<String, String> {
-        window
-            .emit(
-                "log",
-                format!(
-                    "Pulse Protocol: Syncing with Channel '{}'...",
-                    self.config.channel
-                ),
-            )
-            .unwrap();
-
-        // 1. Construct Query: Select * from pulse_releases where channel_slug = $1 and is_active = true limit 1
+    /// Active pulse_releases row for a channel (manifest_url, version, rollout_message,
+    /// release_notes/changelog where the table has them)
+    async fn query_active_release(&self, channel: &str) -> Result<serde_json::Value, String> {
+        // Select * from pulse_releases where channel_slug = $1 and is_active = true limit 1.
+        // `*` rather than a column list: naming a notes column the table lacks is a 400.
         let query_url = format!(
-            "{}/rest/v1/pulse_releases?channel_slug=eq.{}&is_active=eq.true&select=manifest_url,version,rollout_message&limit=1",
-            self.config.supabase_url, self.config.channel
+            "{}/rest/v1/pulse_releases?channel_slug=eq.{}&is_active=eq.true&select=*&limit=1",
+            self.config.supabase_url, channel
         );
 
-        // 2. Execute Query
         let resp = self
             .client
             .get(&query_url)
@@ -238,17 +923,58 @@ impl PulseManager {
             .map_err(|e| format!("Network Error: {}", e))?;
 
         if !resp.status().is_success() {
+            let error = crate::http::SupabaseError::from_response(resp).await;
+            if error.is_project_paused() {
+                return Err(format!("Pulse Brain Unreachable: {}", error));
+            }
             return Err(format!(
                 "Pulse Brain Unreachable ({}). Is Anon Key valid?",
-                resp.status()
+                error.status
             ));
         }
 
-        // 3. Parse Response
         let releases: Vec<serde_json::Value> = resp.json().await.map_err(|e| e.to_string())?;
-        let active = releases
-            .first()
-            .ok_or("No active release found for this channel.")?;
+        releases
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No active release found for this channel.".to_string())
+    }
+
+    /// Moves to `channel` only after confirming it has an active release.
+    /// Persists the config and returns the channel's version.
+    pub async fn switch_channel(&self, app: &AppHandle, channel: &str) -> Result<String, String> {
+        let release = self
+            .query_active_release(channel)
+            .await
+            .map_err(|e| format!("Cannot switch to '{}': {}", channel, e))?;
+        release["manifest_url"]
+            .as_str()
+            .ok_or_else(|| format!("Cannot switch to '{}': release has no manifest", channel))?;
+
+        update_config(app, |config| config.channel = channel.to_string())?;
+        Ok(release["version"].as_str().unwrap_or("unknown").to_string())
+    }
+
+    /// Release notes of a channel's active release; None when it has none
+    pub async fn release_notes(&self, channel: &str) -> Result<Option<String>, String> {
+        let release = self.query_active_release(channel).await?;
+        Ok(release_notes(&release))
+    }
+
+    /// STEP 1: RESOLVE - Ask Supabase "Brain" for the correct Manifest
+    /// Returns the manifest URL and the row's manifest signature, if it carries one.
+    async fn resolve_active_release(&self, window: &Window) -> Result<(String, Option<String>), String> {
+        window
+            .emit(
+                "log",
+                format!(
+                    "Pulse Protocol: Syncing with Channel '{}'...",
+                    self.config.channel
+                ),
+            )
+            .unwrap();
+
+        let active = self.query_active_release(&self.config.channel).await?;
 
         let manifest_url = active["manifest_url"]
             .as_str()
@@ -263,45 +989,376 @@ impl PulseManager {
             )
             .unwrap();
 
-        Ok(manifest_url)
+        // 4. Persist so the UI can show channel state without re-querying
+        let state = ReleaseState {
+            channel: self.config.channel.clone(),
+            version: version.to_string(),
+            manifest_url: manifest_url.clone(),
+            rollout_message: active["rollout_message"].as_str().map(|m| m.to_string()),
+            release_notes: release_notes(&active),
+            resolved_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        };
+        if let Ok(data) = serde_json::to_string_pretty(&state) {
+            if let Err(e) = fs::write(&self.release_path, data) {
+                window
+                    .emit("log", format!("Could not persist release state: {}", e))
+                    .unwrap();
+            }
+        }
+
+        let signature = active["manifest_signature"].as_str().map(|s| s.to_string());
+        Ok((manifest_url, signature))
+    }
+
+    /// Channels with an active release, from the Pulse Brain. Falls back to
+    /// the manifest's `channels` map when the brain is unreachable.
+    pub async fn list_channels(&self) -> Result<Vec<ChannelInfo>, String> {
+        let query_url = format!(
+            "{}/rest/v1/pulse_releases?is_active=eq.true&select=channel_slug,version&order=channel_slug",
+            self.config.supabase_url
+        );
+        let brain = async {
+            let resp = self
+                .client
+                .get(&query_url)
+                .header("apikey", &self.config.supabase_key)
+                .header("Authorization", format!("Bearer {}", self.config.supabase_key))
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            if !resp.status().is_success() {
+                return Err(crate::http::SupabaseError::from_response(resp).await.to_string());
+            }
+            resp.json::<Vec<serde_json::Value>>().await.map_err(|e| e.to_string())
+        };
+
+        match brain.await {
+            Ok(rows) if !rows.is_empty() => {
+                let mut channels: Vec<ChannelInfo> = vec![];
+                for row in rows {
+                    let Some(slug) = row["channel_slug"].as_str() else { continue };
+                    // One active release per channel is expected; keep the first
+                    if channels.iter().any(|c| c.slug == slug) {
+                        continue;
+                    }
+                    channels.push(ChannelInfo {
+                        slug: slug.to_string(),
+                        version: row["version"].as_str().unwrap_or("unknown").to_string(),
+                        required: false,
+                    });
+                }
+                Ok(channels)
+            }
+            _ => {
+                let manifest_url = self
+                    .release_state()
+                    .map(|s| s.manifest_url)
+                    .unwrap_or_else(|| DEFAULT_MANIFEST_URL.to_string());
+                let (manifest, _) = self.fetch_manifest(&manifest_url, None).await?;
+                let mut channels: Vec<ChannelInfo> = manifest
+                    .channels
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(slug, c)| ChannelInfo {
+                        slug,
+                        version: c.version,
+                        required: c.required,
+                    })
+                    .collect();
+                channels.sort_by(|a, b| a.slug.cmp(&b.slug));
+                Ok(channels)
+            }
+        }
     }
 
     /// ORBITAL DEPOT LOGIC: Fetch the "Menu" (Manifest)
-    /// Now accepts a specific URL (resolved from Supabase)
-    pub async fn fetch_manifest(&self, url: &str) -> Result<PulseManifest, String> {
+    /// Now accepts a specific URL (resolved from Supabase). `signature` comes
+    /// from the release row; without one the `<url>.sig` sidecar is tried.
+    /// Returns the manifest with its schema violations: a manifest that
+    /// parses still loads, as it did before the schema existed.
+    pub async fn fetch_manifest(&self, url: &str, signature: Option<&str>) -> Result<(PulseManifest, Vec<String>), String> {
         let resp = self
             .client
             .get(url)
             .send()
             .await
             .map_err(|e| e.to_string())?;
+        crate::http::check_download_host(resp.url(), &self.config)?;
 
         if !resp.status().is_success() {
             return Err(format!("Manifest Unreachable ({})", resp.status()));
         }
 
-        resp.json().await.map_err(|e| e.to_string())
+        let body = resp.bytes().await.map_err(|e| e.to_string())?;
+        self.verify_manifest(url, &body, signature).await?;
+        let raw: serde_json::Value = serde_json::from_slice(&body).map_err(|e| format!("Manifest is not valid JSON: {}", e))?;
+        let errors = crate::manifest_schema::validate_manifest(&raw);
+        match serde_json::from_value(raw) {
+            Ok(manifest) => Ok((manifest, errors)),
+            Err(e) if errors.is_empty() => Err(e.to_string()),
+            Err(_) => Err(format!("Manifest failed validation: {}", errors.join("; "))),
+        }
     }
 
-    /// Installs the package defined in the manifest for the current OS
-    pub async fn install_latest(&self, window: &Window, package_id: &str) -> Result<(), String> {
-        // STEP 1: Resolve (Supabase)
-        let manifest_url = match self.resolve_active_release(window).await {
-            Ok(url) => url,
+    /// Fetches a manifest and reports every schema violation instead of
+    /// stopping at the first, for depot maintainers checking a new release
+    pub async fn preview_manifest(&self, url: &str) -> Result<ManifestPreview, String> {
+        let resp = self.client.get(url).send().await.map_err(|e| e.to_string())?;
+        crate::http::check_download_host(resp.url(), &self.config)?;
+        if !resp.status().is_success() {
+            return Err(format!("Manifest Unreachable ({})", resp.status()));
+        }
+
+        let body = resp.bytes().await.map_err(|e| e.to_string())?;
+        let raw: serde_json::Value = match serde_json::from_slice(&body) {
+            Ok(raw) => raw,
             Err(e) => {
-                window
-                    .emit("log", format!("Pulse Protocol Sync Failed: {}", e))
-                    .unwrap();
-                window
-                    .emit("log", "Falling back to hardcoded Depot default...")
-                    .unwrap();
-                "https://raw.githubusercontent.com/devpulse-tools/dptools-deps/main/deps/apps/ezdb/manifest.json".to_string()
+                return Ok(ManifestPreview {
+                    valid: false,
+                    errors: vec![format!("(root): not valid JSON: {}", e)],
+                    manifest: None,
+                })
             }
         };
-
-        // STEP 2: Hydrate (GitHub Manifest)
+        let mut errors = crate::manifest_schema::validate_manifest(&raw);
+        let manifest = if errors.is_empty() {
+            match serde_json::from_value::<PulseManifest>(raw) {
+                Ok(m) => Some(m),
+                Err(e) => {
+                    errors.push(format!("(root): {}", e));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        Ok(ManifestPreview {
+            valid: errors.is_empty(),
+            errors,
+            manifest,
+        })
+    }
+
+    /// Checks the manifest's ed25519 signature against the pinned key. A bad
+    /// signature is always rejected; a missing one only when signing is enforced.
+    async fn verify_manifest(&self, url: &str, body: &[u8], signature: Option<&str>) -> Result<(), String> {
+        let key = self.config.manifest_public_key.trim();
+        if key.is_empty() {
+            if self.config.require_signed_manifest {
+                return Err("Manifest signing is enforced but no manifest_public_key is pinned.".to_string());
+            }
+            return Ok(());
+        }
+
+        let signature = match signature {
+            Some(sig) => Some(sig.to_string()),
+            None => self.fetch_manifest_sidecar(url).await?,
+        };
+        let Some(signature) = signature else {
+            if self.config.require_signed_manifest {
+                return Err("Manifest is unsigned and signing is enforced. Refusing to trust it.".to_string());
+            }
+            return Ok(());
+        };
+
+        let key = decode_key_material(key).ok_or("manifest_public_key is not valid hex or base64")?;
+        let signature = decode_key_material(signature.trim()).ok_or("Manifest signature is not valid hex or base64")?;
+        ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, key)
+            .verify(body, &signature)
+            .map_err(|_| "Manifest signature does not match the pinned key. Refusing to trust it.".to_string())
+    }
+
+    /// `<manifest url>.sig`; None when the depot doesn't publish one
+    async fn fetch_manifest_sidecar(&self, url: &str) -> Result<Option<String>, String> {
+        let resp = self
+            .client
+            .get(format!("{}.sig", url))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        crate::http::check_download_host(resp.url(), &self.config)?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(format!("Manifest signature unreachable ({})", resp.status()));
+        }
+        resp.text().await.map(Some).map_err(|e| e.to_string())
+    }
+
+    /// Installs the package defined in the manifest for the current OS
+    /// Returns the archive entries that were skipped during extraction
+    pub async fn install_latest(&self, window: &Window, package_id: &str) -> Result<InstallOutcome, String> {
+        let manifest = self.resolve_manifest(window).await?;
+        let pkg_spec = self.select_package(window, &manifest, package_id)?;
+
+        let manifest_version = manifest
+            .channels
+            .as_ref()
+            .and_then(|c| c.get(&self.config.channel).or_else(|| c.get("stable")))
+            .map(|c| c.version.clone());
+        let package_version = version_in_url(&pkg_spec.url);
+        if let (Some(declared), Some(packaged)) = (&manifest_version, &package_version) {
+            if !versions_agree(declared, packaged) {
+                self.version_mismatch(window, &format!(
+                    "manifest declares v{} but the package is {}",
+                    declared, packaged
+                ))?;
+            }
+        }
+
+        let files = self
+            .download_and_extract(window, package_id, &pkg_spec.url, Some(&pkg_spec.checksum))
+            .await?;
+
+        let binary_version = self.binary_version(package_id);
+        let expected = manifest_version.as_ref().or(package_version.as_ref());
+        if let (Some(expected), Some(actual)) = (expected, &binary_version) {
+            if !versions_agree(expected, actual) {
+                let outcome = self.version_mismatch(window, &format!(
+                    "expected v{} but the installed pg_dump reports {}",
+                    expected, actual
+                ));
+                if outcome.is_err() {
+                    // Leave it unverified so resume/verify treat it as not installed
+                    let _ = fs::remove_file(self.installed_record_path(package_id));
+                }
+                outcome?;
+            }
+        }
+
+        Ok(InstallOutcome {
+            consistent: [&manifest_version, &package_version, &binary_version]
+                .iter()
+                .filter_map(|v| v.as_deref())
+                .all(|v| expected.is_none_or(|e| versions_agree(e, v))),
+            files,
+            manifest_version,
+            package_version,
+            binary_version,
+        })
+    }
+
+    /// Warns about a manifest/package/binary version disagreement, or fails
+    /// when `abort_on_version_mismatch` is set
+    fn version_mismatch(&self, window: &Window, detail: &str) -> Result<(), String> {
+        let msg = format!("Version mismatch: {}. The depot manifest may be malformed.", detail);
+        if self.config.abort_on_version_mismatch {
+            return Err(msg);
+        }
+        window.emit("log", format!("WARNING: {}", msg)).unwrap();
+        Ok(())
+    }
+
+    /// Version the installed pg_dump reports, e.g. "15.4"
+    fn binary_version(&self, package_id: &str) -> Option<String> {
+        let bin = self.resolve(package_id, &format!("pg_dump{}", std::env::consts::EXE_SUFFIX)).ok()?;
+        let output = std::process::Command::new(&bin)
+            .envs(child_process_env(&bin))
+            .arg("--version")
+            .output()
+            .ok()?;
+        version_in_text(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Retries an install from its furthest good state: an installed pack that
+    /// still verifies, a cached archive that still matches the checksum
+    /// (re-extract), a partial download (resume), or nothing (fresh download).
+    /// Returns the step it resumed from.
+    pub async fn resume_install(&self, window: &Window, package_id: &str) -> Result<String, String> {
+        if self.installed_record_path(package_id).exists() && self.verify_installed(package_id).ok {
+            window.emit("log", format!("{} is installed and verifies. Nothing to resume.", package_id)).unwrap();
+            return Ok("INSTALLED".to_string());
+        }
+
+        let pkg_spec = self.resolve_package_spec(window, package_id).await?;
+        let cache_path = self.cache_path(package_id, &pkg_spec.url);
+        let step = resume_step(&cache_path, &pkg_spec.checksum);
+        window.emit("log", format!("Resuming install of {} from {}", package_id, step)).unwrap();
+
+        self.download_and_extract(window, package_id, &pkg_spec.url, Some(&pkg_spec.checksum))
+            .await?;
+        Ok(step.to_string())
+    }
+
+    /// Installs every logical package in the manifest (just the Postgres pack
+    /// for flat manifests). Returns (package_id, error) for each one that failed.
+    pub async fn install_all(&self, window: &Window) -> Result<Vec<(String, String)>, String> {
+        let manifest = self.resolve_manifest(window).await?;
+        let mut ids = manifest.package_ids();
+        if ids.is_empty() {
+            ids.push(crate::db::PG_PACKAGE.to_string());
+        }
+
+        let mut failed = vec![];
+        for package_id in ids {
+            window.emit("log", format!("Installing {}...", package_id)).unwrap();
+            let result = match self.select_package(window, &manifest, &package_id) {
+                Ok(spec) => self
+                    .download_and_extract(window, &package_id, &spec.url, Some(&spec.checksum))
+                    .await
+                    .map(|_| ()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                window.emit("log", format!("Install of {} failed: {}", package_id, e)).unwrap();
+                failed.push((package_id, e));
+            }
+        }
+        Ok(failed)
+    }
+
+    /// Resolves the manifest and picks the package for the current OS
+    async fn resolve_package_spec(&self, window: &Window, package_id: &str) -> Result<PulsePackageSpec, String> {
+        let manifest = self.resolve_manifest(window).await?;
+        self.select_package(window, &manifest, package_id)
+    }
+
+    /// STEP 3: package_id -> variant for this OS
+    fn select_package(&self, window: &Window, manifest: &PulseManifest, package_id: &str) -> Result<PulsePackageSpec, String> {
+        // OS Detection: win32-x64 ships zip, linux/darwin ship tar.gz
+        let target_os = platform_key();
+        let pkg_spec = manifest
+            .package_for(package_id, &target_os)
+            .ok_or_else(|| format!("No {} package found for {} in manifest", package_id, target_os))?;
+
+        window
+            .emit(
+                "log",
+                format!("Acquiring Ordnance: {:.2} MB", pkg_spec.size_mb),
+            )
+            .unwrap();
+
+        Ok(pkg_spec.clone())
+    }
+
+    /// Resolves the active release and fetches its manifest
+    async fn resolve_manifest(&self, window: &Window) -> Result<PulseManifest, String> {
+        // STEP 1: Resolve (Supabase)
+        let (manifest_url, signature) = match self.resolve_active_release(window).await {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                window
+                    .emit("log", format!("Pulse Protocol Sync Failed: {}", e))
+                    .unwrap();
+                window
+                    .emit("log", "Falling back to hardcoded Depot default...")
+                    .unwrap();
+                (DEFAULT_MANIFEST_URL.to_string(), None)
+            }
+        };
+
+        // STEP 2: Hydrate (GitHub Manifest)
         window.emit("log", "Acquiring Manifest...").unwrap();
-        let manifest = self.fetch_manifest(&manifest_url).await?;
+        let (manifest, schema_errors) = self.fetch_manifest(&manifest_url, signature.as_deref()).await?;
+        if !schema_errors.is_empty() {
+            window
+                .emit("log", format!("WARNING: Manifest does not match the schema: {}", schema_errors.join("; ")))
+                .unwrap();
+        }
 
         // Intelligent Version Resolution
         let version = if let Some(channels) = &manifest.channels {
@@ -335,23 +1392,7 @@ impl PulseManager {
             // TODO: Emit "pulse_rollout" event to frontend
         }
 
-        // STEP 3: Download Binary (GitHub Assets)
-        // OS Detection (Hardcoded to win32-x64 for this Windows-only tool)
-        let target_os = "win32-x64";
-        let pkg_spec = manifest
-            .packages
-            .get(target_os)
-            .ok_or("No package found for this OS in manifest")?;
-
-        window
-            .emit(
-                "log",
-                format!("Acquiring Ordnance: {:.2} MB", pkg_spec.size_mb),
-            )
-            .unwrap();
-
-        self.download_and_extract(window, package_id, &pkg_spec.url)
-            .await
+        Ok(manifest)
     }
 
     async fn download_and_extract(
@@ -359,40 +1400,794 @@ impl PulseManager {
         window: &Window,
         package_id: &str,
         url: &str,
-    ) -> Result<(), String> {
+        checksum: Option<&str>,
+    ) -> Result<Vec<String>, String> {
+        let window = window.clone();
+        self.fetch_and_extract(move |line: String| window.emit("log", line).unwrap(), package_id, url, checksum)
+            .await
+    }
+
+    /// `download_and_extract` with progress going to `log`. The verified
+    /// archive stays in the cache until extraction succeeds, so a failed
+    /// extraction is retried from it without downloading again.
+    async fn fetch_and_extract<L>(
+        &self,
+        log: L,
+        package_id: &str,
+        url: &str,
+        checksum: Option<&str>,
+    ) -> Result<Vec<String>, String>
+    where
+        L: Fn(String) + Clone + Send + Sync + 'static,
+    {
         let target_dir = self.base_path.join(package_id);
+        let cache_path = self.cache_path(package_id, url);
 
-        window.emit("log", "Initiating Transfer...").unwrap();
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-        let content = response.bytes().await.map_err(|e| e.to_string())?;
+        // Reuse a previously downloaded archive if it still verifies
+        let cached = match (checksum, fs::read(&cache_path)) {
+            (Some(expected), Ok(data)) if sha256_hex(&data).eq_ignore_ascii_case(expected) => {
+                log("Verified cached download found. Skipping transfer.".to_string());
+                Some(data)
+            }
+            _ => None,
+        };
 
-        window.emit("log", "Extracting Payload...").unwrap();
+        let content = match cached {
+            Some(data) => data,
+            None => self.download_to_cache(&log, url, &cache_path, checksum).await?,
+        };
+
+        log("Extracting Payload...".to_string());
+        let skip_bad = self.config.skip_bad_archive_entries;
+        let (task_log, task_dir) = (log.clone(), target_dir.clone());
+        let skipped = crate::tasks::run_blocking(move || {
+            if is_gzip(&content) {
+                Self::extract_tar_gz(&task_log, skip_bad, &content, &task_dir)
+            } else {
+                Self::extract_zip(&task_log, skip_bad, content, &task_dir)
+            }
+        })
+        .await?;
+
+        if let Some(essential) = skipped.iter().find(|n| is_essential_entry(n)) {
+            return Err(format!(
+                "Essential binary {} could not be extracted. Pulse Pack is unusable.",
+                essential
+            ));
+        }
+
+        self.check_binary_platform(package_id)?;
+
+        if !skipped.is_empty() {
+            log(format!("Extraction finished with {} skipped entries.", skipped.len()));
+        }
+
+        if let Err(e) = self.record_install(package_id, checksum) {
+            log(format!("Could not record install checksums: {}", e));
+        }
+
+        if let Ok(pg_dump) = self.resolve(package_id, &format!("pg_dump{}", std::env::consts::EXE_SUFFIX)) {
+            let missing = missing_msvc_runtime(&pg_dump);
+            if !missing.is_empty() {
+                log(format!("WARNING: {}", msvc_runtime_message(&missing)));
+            }
+        }
+
+        // Extraction succeeded; the cached archive is only kept on request
+        if !self.config.keep_download_cache {
+            let _ = fs::remove_file(&cache_path);
+        }
+
+        log("Pulse Pack Installed.".to_string());
+        Ok(skipped)
+    }
+
+    /// base_path/cache/<package_id>-<archive file name>
+    fn cache_path(&self, package_id: &str, url: &str) -> PathBuf {
+        let file_name = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|u| u.rsplit('/').next())
+            .filter(|n| !n.is_empty())
+            .unwrap_or("package.bin");
+        self.base_path
+            .join("cache")
+            .join(format!("{}-{}", package_id, file_name))
+    }
+
+    /// Downloads to the cache file and verifies it before anything is extracted
+    async fn download_to_cache(
+        &self,
+        log: &(dyn Fn(String) + Sync),
+        url: &str,
+        cache_path: &Path,
+        checksum: Option<&str>,
+    ) -> Result<Vec<u8>, String> {
+        log("Initiating Transfer...".to_string());
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+
+        // An interrupted download leaves <cache>.part behind; ask for the rest of it
+        let part = part_path(cache_path);
+        let offset = fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+        let mut response = request_from(&self.client, url, offset).await?;
+        // Release assets redirect to a CDN; only accept where the chain ended up if it's trusted
+        crate::http::check_download_host(response.url(), &self.config)?;
+        if response.url().as_str() != url {
+            log(format!("Download resolved to {}", response.url()));
+        }
+
+        if !response.status().is_success() {
+            return Err(format!("Download failed ({})", response.status()));
+        }
+
+        if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+            log(format!("Resuming download at {} bytes", offset));
+        }
+        write_part(&mut response, &part, &self.cancel).await?;
+
+        let content = fs::read(&part).map_err(|e| e.to_string())?;
+        if let Some(expected) = checksum {
+            let actual = sha256_hex(&content);
+            if !actual.eq_ignore_ascii_case(expected) {
+                // A corrupt partial must not be resumed again
+                let _ = fs::remove_file(&part);
+                return Err(format!(
+                    "Checksum mismatch: expected {}, got {}",
+                    expected, actual
+                ));
+            }
+            log("Checksum verified.".to_string());
+        }
+
+        fs::rename(&part, cache_path).map_err(|e| format!("Failed to write download cache: {}", e))?;
+        Ok(content)
+    }
+
+    /// Records a failed entry, or aborts when skipping is disabled
+    fn skip_entry(
+        log: &dyn Fn(String),
+        skip_bad: bool,
+        skipped: &mut Vec<String>,
+        name: String,
+        err: String,
+    ) -> Result<(), String> {
+        if !skip_bad {
+            return Err(format!("Failed to extract {}: {}", name, err));
+        }
+        log(format!("Skipped entry {}: {}", name, err));
+        skipped.push(name);
+        Ok(())
+    }
+
+    fn extract_zip(log: &dyn Fn(String), skip_bad: bool, content: Vec<u8>, target_dir: &Path) -> Result<Vec<String>, String> {
         let reader = Cursor::new(content);
         let mut archive = zip::ZipArchive::new(reader).map_err(|e| e.to_string())?;
 
+        let mut skipped = vec![];
         for i in 0..archive.len() {
-            let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
-            let outpath = target_dir.join(file.mangled_name());
+            if let Err(e) = Self::extract_zip_entry(&mut archive, i, target_dir) {
+                let name = archive
+                    .by_index_raw(i)
+                    .map(|f| f.name().to_string())
+                    .unwrap_or_else(|_| format!("entry #{}", i));
+                Self::skip_entry(log, skip_bad, &mut skipped, name, e)?;
+            }
+        }
+        Ok(skipped)
+    }
 
-            if file.name().ends_with('/') {
-                fs::create_dir_all(&outpath).map_err(|e| e.to_string())?;
-            } else {
-                if let Some(p) = outpath.parent() {
-                    if !p.exists() {
-                        fs::create_dir_all(p).map_err(|e| e.to_string())?;
-                    }
+    fn extract_zip_entry<R: std::io::Read + std::io::Seek>(
+        archive: &mut zip::ZipArchive<R>,
+        index: usize,
+        target_dir: &Path,
+    ) -> Result<(), String> {
+        let mut file = archive.by_index(index).map_err(|e| e.to_string())?;
+        let outpath = target_dir.join(file.mangled_name());
+
+        if file.name().ends_with('/') {
+            fs::create_dir_all(&outpath).map_err(|e| e.to_string())?;
+        } else {
+            if let Some(p) = outpath.parent() {
+                if !p.exists() {
+                    fs::create_dir_all(p).map_err(|e| e.to_string())?;
                 }
-                let mut outfile = fs::File::create(&outpath).map_err(|e| e.to_string())?;
-                std::io::copy(&mut file, &mut outfile).map_err(|e| e.to_string())?;
             }
+            let mut outfile = fs::File::create(&outpath).map_err(|e| e.to_string())?;
+            std::io::copy(&mut file, &mut outfile).map_err(|e| e.to_string())?;
+            if let Some(mode) = file.unix_mode() {
+                apply_mode(&outpath, mode)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// macOS/Linux packs ship as tar.gz so symlinks and modes survive
+    fn extract_tar_gz(log: &dyn Fn(String), skip_bad: bool, content: &[u8], target_dir: &Path) -> Result<Vec<String>, String> {
+        let decoder = flate2::read::GzDecoder::new(content);
+        let mut archive = tar::Archive::new(decoder);
+        // Entries are checked against the canonical package dir, so it must exist first
+        fs::create_dir_all(target_dir).map_err(|e| e.to_string())?;
+
+        let mut skipped = vec![];
+        for (i, entry) in archive.entries().map_err(|e| e.to_string())?.enumerate() {
+            let mut entry = match entry {
+                Ok(e) => e,
+                Err(e) => {
+                    Self::skip_entry(log, skip_bad, &mut skipped, format!("entry #{}", i), e.to_string())?;
+                    continue;
+                }
+            };
+            let name = entry
+                .path()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|_| format!("entry #{}", i));
+
+            if let Err(e) = Self::extract_tar_entry(&mut entry, target_dir) {
+                Self::skip_entry(log, skip_bad, &mut skipped, name, e)?;
+            }
+        }
+        Ok(skipped)
+    }
+
+    fn extract_tar_entry<R: std::io::Read>(entry: &mut tar::Entry<R>, target_dir: &Path) -> Result<(), String> {
+        let rel_path = entry.path().map_err(|e| e.to_string())?.to_path_buf();
+        let outpath = safe_join(target_dir, &rel_path)
+            .ok_or_else(|| format!("Path escapes target directory: {}", rel_path.display()))?;
+
+        if let Some(p) = outpath.parent() {
+            ensure_within(target_dir, p)?;
+            fs::create_dir_all(p).map_err(|e| e.to_string())?;
         }
 
-        window.emit("log", "Pulse Pack Installed.").unwrap();
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() {
+            fs::create_dir_all(&outpath).map_err(|e| e.to_string())?;
+        } else if entry_type.is_symlink() {
+            let link_target = entry
+                .link_name()
+                .map_err(|e| e.to_string())?
+                .ok_or("Symlink without target")?
+                .to_path_buf();
+            // The link must resolve inside the package from its own directory,
+            // following links earlier entries created
+            let link_dir = outpath.parent().unwrap_or(target_dir);
+            let root = target_dir.canonicalize().map_err(|e| e.to_string())?;
+            if !resolve_link_target(link_dir, &link_target)?.starts_with(&root) {
+                return Err(format!("Symlink target escapes package: {}", link_target.display()));
+            }
+            create_symlink(&link_target, &outpath)?;
+        } else if entry_type.is_hard_link() {
+            let link_target = entry
+                .link_name()
+                .map_err(|e| e.to_string())?
+                .ok_or("Hard link without target")?
+                .to_path_buf();
+            // Hard link targets are relative to the archive root
+            let source = safe_join(target_dir, &link_target)
+                .ok_or_else(|| format!("Hard link target escapes package: {}", link_target.display()))?;
+            ensure_within(target_dir, &source)?;
+            let _ = fs::remove_file(&outpath);
+            fs::hard_link(&source, &outpath).map_err(|e| e.to_string())?;
+        } else if entry_type.is_file() {
+            // Never write through a symlink an earlier entry left at this path
+            if fs::symlink_metadata(&outpath).is_ok_and(|m| m.file_type().is_symlink()) {
+                fs::remove_file(&outpath).map_err(|e| e.to_string())?;
+            }
+            let mut outfile = fs::File::create(&outpath).map_err(|e| e.to_string())?;
+            std::io::copy(entry, &mut outfile).map_err(|e| e.to_string())?;
+            let mode = entry.header().mode().map_err(|e| e.to_string())?;
+            apply_mode(&outpath, mode)?;
+        } else {
+            return Err(format!("Unsupported entry type: {:?}", entry_type));
+        }
         Ok(())
     }
 }
+
+/// Where `resume_install` picks up: a cached archive that still matches the
+/// checksum is re-extracted; anything less (a `.part`, or a cache file that
+/// fails the checksum) goes back to the download
+fn resume_step(cache_path: &Path, checksum: &str) -> &'static str {
+    if fs::read(cache_path).is_ok_and(|d| sha256_hex(&d).eq_ignore_ascii_case(checksum)) {
+        "EXTRACT"
+    } else if part_path(cache_path).exists() {
+        "DOWNLOAD_RESUME"
+    } else {
+        "DOWNLOAD"
+    }
+}
+
+/// GET `url`, asking only for the bytes from `offset` on when resuming
+async fn request_from(client: &reqwest::Client, url: &str, offset: u64) -> Result<reqwest::Response, String> {
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    request.send().await.map_err(|e| e.to_string())
+}
+
+/// Writes a download response into `part`. 206 continues the partial file; a
+/// 200 means the range was ignored, so it starts over.
+async fn write_part(response: &mut reqwest::Response, part: &Path, cancel: &CancelToken) -> Result<(), String> {
+    let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part)
+        .map_err(|e| format!("Failed to write download cache: {}", e))?;
+    stream_to_part(response, &mut file, cancel).await
+}
+
+/// Appends the response body to the partial download. Checks `cancel` before
+/// every chunk; a cancelled download keeps its `.part` so it can resume.
+async fn stream_to_part(response: &mut reqwest::Response, file: &mut fs::File, cancel: &CancelToken) -> Result<(), String> {
+    loop {
+        cancel.check()?;
+        match response.chunk().await.map_err(|e| e.to_string())? {
+            Some(chunk) => file
+                .write_all(&chunk)
+                .map_err(|e| format!("Failed to write download cache: {}", e))?,
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Keys and signatures are published as hex or standard base64
+fn decode_key_material(value: &str) -> Option<Vec<u8>> {
+    use base64::Engine;
+    hex::decode(value)
+        .ok()
+        .or_else(|| base64::engine::general_purpose::STANDARD.decode(value).ok())
+}
+
+/// In-progress download next to its cache file: <cache>.part
+fn part_path(cache_path: &Path) -> PathBuf {
+    let mut name = cache_path.as_os_str().to_owned();
+    name.push(".part");
+    PathBuf::from(name)
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(data))
+}
+
+fn is_gzip(content: &[u8]) -> bool {
+    content.starts_with(&[0x1f, 0x8b])
+}
+
+/// Joins an archive-relative path onto `root`, rejecting anything that would
+/// leave it (absolute paths, drive prefixes, or `..` climbing above the root)
+fn safe_join(root: &Path, rel: &Path) -> Option<PathBuf> {
+    let mut parts: Vec<&std::ffi::OsStr> = vec![];
+    for component in rel.components() {
+        match component {
+            Component::Normal(p) => parts.push(p),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(parts.iter().fold(root.to_path_buf(), |acc, p| acc.join(p)))
+}
+
+/// `safe_join` is lexical only, so a symlink extracted earlier can still route
+/// a later entry outside `root`. Resolves the deepest existing ancestor of
+/// `path` and checks it is still under `root`.
+fn ensure_within(root: &Path, path: &Path) -> Result<(), String> {
+    let root = root.canonicalize().map_err(|e| e.to_string())?;
+    let mut existing = path;
+    while fs::symlink_metadata(existing).is_err() {
+        existing = existing
+            .parent()
+            .ok_or_else(|| format!("No existing ancestor for {}", path.display()))?;
+    }
+    let resolved = existing
+        .canonicalize()
+        .map_err(|e| format!("Cannot resolve {}: {}", existing.display(), e))?;
+    if resolved.starts_with(&root) {
+        Ok(())
+    } else {
+        Err(format!("Path escapes target directory through a symlink: {}", path.display()))
+    }
+}
+
+/// Where a symlink in `link_dir` pointing at `target` ends up. Like
+/// `ensure_within`, existing paths are canonicalized so links already on disk
+/// are followed; the part that doesn't exist yet is applied lexically.
+fn resolve_link_target(link_dir: &Path, target: &Path) -> Result<PathBuf, String> {
+    let mut resolved = link_dir.canonicalize().map_err(|e| format!("Cannot resolve {}: {}", link_dir.display(), e))?;
+    for component in target.components() {
+        match component {
+            Component::Normal(p) => {
+                resolved.push(p);
+                if fs::symlink_metadata(&resolved).is_ok() {
+                    resolved = resolved
+                        .canonicalize()
+                        .map_err(|e| format!("Cannot resolve {}: {}", target.display(), e))?;
+                }
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(format!("Symlink target escapes package: {}", target.display()));
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+#[cfg(unix)]
+fn apply_mode(path: &Path, mode: u32) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o7777)).map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn apply_mode(_path: &Path, _mode: u32) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> Result<(), String> {
+    let _ = fs::remove_file(link);
+    std::os::unix::fs::symlink(target, link).map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &Path, link: &Path) -> Result<(), String> {
+    Err(format!("Symlinks are not supported on this platform: {}", link.display()))
+}
+
+/// (OS, arch) an executable targets, from its PE/ELF/Mach-O header.
+/// Arch is None for universal Mach-O binaries and unknown machines.
+fn binary_platform(data: &[u8]) -> Option<(&'static str, Option<&'static str>)> {
+    let u16_le = |at: usize| data.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+    let u32_le = |at: usize| data.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+    if data.starts_with(b"\x7fELF") {
+        let arch = match u16_le(18)? {
+            0x3E => Some("x86_64"),
+            0xB7 => Some("aarch64"),
+            0x03 => Some("x86"),
+            _ => None,
+        };
+        return Some(("linux", arch));
+    }
+    if data.starts_with(b"MZ") {
+        let pe = u32_le(0x3C)? as usize;
+        if data.get(pe..pe + 4) != Some(b"PE\0\0") {
+            return None;
+        }
+        let arch = match u16_le(pe + 4)? {
+            0x8664 => Some("x86_64"),
+            0xAA64 => Some("aarch64"),
+            0x014C => Some("x86"),
+            _ => None,
+        };
+        return Some(("windows", arch));
+    }
+    match data.get(0..4)? {
+        [0xCF, 0xFA, 0xED, 0xFE] | [0xCE, 0xFA, 0xED, 0xFE] => {
+            let arch = match u32_le(4)? {
+                0x0100_0007 => Some("x86_64"),
+                0x0100_000C => Some("aarch64"),
+                _ => None,
+            };
+            Some(("macos", arch))
+        }
+        [0xCA, 0xFE, 0xBA, 0xBE] => Some(("macos", None)), // universal binary
+        _ => None,
+    }
+}
+
+/// Manifest package key for the running platform, e.g. "win32-x64"
+fn platform_key() -> String {
+    let os = match std::env::consts::OS {
+        "windows" => "win32",
+        "macos" => "darwin",
+        other => other,
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        other => other,
+    };
+    format!("{}-{}", os, arch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockServer, Reply};
+
+    #[tokio::test]
+    async fn download_stops_at_next_chunk_once_cancelled() {
+        let server = MockServer::start(|_| Reply::bytes(200, vec![7; 64 * 1024])).await;
+        let dir = tempfile::tempdir().unwrap();
+        let part = dir.path().join("pkg.zip.part");
+
+        let cancel = CancelToken::default();
+        cancel.cancel();
+        let mut response = reqwest::get(&server.url).await.unwrap();
+        let mut file = fs::File::create(&part).unwrap();
+        let result = stream_to_part(&mut response, &mut file, &cancel).await;
+
+        assert_eq!(result, Err(crate::cancel::CANCELLED.to_string()));
+        assert!(part.exists(), "cancelled download must keep its .part for resume");
+    }
+
+    #[tokio::test]
+    async fn download_streams_whole_body_when_not_cancelled() {
+        let server = MockServer::start(|_| Reply::bytes(200, vec![7; 64 * 1024])).await;
+        let dir = tempfile::tempdir().unwrap();
+        let part = dir.path().join("pkg.zip.part");
+
+        let mut response = reqwest::get(&server.url).await.unwrap();
+        let mut file = fs::File::create(&part).unwrap();
+        stream_to_part(&mut response, &mut file, &CancelToken::default()).await.unwrap();
+
+        assert_eq!(fs::metadata(&part).unwrap().len(), 64 * 1024);
+    }
+
+    #[test]
+    fn interrupted_install_resumes_from_download_not_extract() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("postgres-15-pg.tar.gz");
+        let full = b"complete archive";
+        let checksum = sha256_hex(full);
+
+        assert_eq!(resume_step(&cache, &checksum), "DOWNLOAD");
+
+        // Interrupted mid-transfer: only the partial file exists
+        fs::write(part_path(&cache), &full[..8]).unwrap();
+        assert_eq!(resume_step(&cache, &checksum), "DOWNLOAD_RESUME");
+
+        // A truncated archive in the cache slot must not be extracted
+        fs::write(&cache, &full[..8]).unwrap();
+        assert_eq!(resume_step(&cache, &checksum), "DOWNLOAD_RESUME");
+
+        fs::write(&cache, full).unwrap();
+        assert_eq!(resume_step(&cache, &checksum), "EXTRACT");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn failed_extraction_retries_from_the_cached_download() {
+        use std::io::Write;
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gz.write_all(&tar_with(&[("bin/pg_dump", None, b"\x7fELF\x02\x01\x01\0\0\0\0\0\0\0\0\0\x02\0\0\0")])).unwrap();
+        let archive = gz.finish().unwrap();
+        let checksum = sha256_hex(&archive);
+        let served = archive.clone();
+        let server = MockServer::start(move |_| Reply::bytes(200, served.clone())).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let manager = PulseManager {
+            base_path: dir.path().to_path_buf(),
+            release_path: dir.path().join("release.json"),
+            client: reqwest::Client::new(),
+            config: PulseConfig { trusted_hosts: vec!["127.0.0.1".to_string()], ..Default::default() },
+            cancel: CancelToken::default(),
+        };
+        let url = format!("{}/pg.tar.gz", server.url);
+        let cache = manager.cache_path("postgres-15", &url);
+
+        // A file where the package directory belongs makes extraction fail
+        fs::write(dir.path().join("postgres-15"), b"in the way").unwrap();
+        assert!(manager.fetch_and_extract(|_| {}, "postgres-15", &url, Some(&checksum)).await.is_err());
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(fs::read(&cache).unwrap(), archive, "verified download was not kept for the retry");
+
+        fs::remove_file(dir.path().join("postgres-15")).unwrap();
+        manager.fetch_and_extract(|_| {}, "postgres-15", &url, Some(&checksum)).await.unwrap();
+        assert_eq!(server.requests().len(), 1, "retry downloaded again");
+        assert!(dir.path().join("postgres-15/bin/pg_dump").exists());
+        // keep_download_cache is off: the archive goes once extraction succeeded
+        assert!(!cache.exists());
+    }
+
+    #[tokio::test]
+    async fn resumed_download_appends_the_missing_range() {
+        let full = b"complete archive";
+        let server = MockServer::start(move |request| match request.header("Range") {
+            Some("bytes=8-") => Reply::bytes(206, full[8..].to_vec()),
+            _ => Reply::bytes(200, full.to_vec()),
+        })
+        .await;
+        let dir = tempfile::tempdir().unwrap();
+        let part = dir.path().join("pg.tar.gz.part");
+        fs::write(&part, &full[..8]).unwrap();
+
+        let mut response = request_from(&reqwest::Client::new(), &server.url, 8).await.unwrap();
+        write_part(&mut response, &part, &CancelToken::default()).await.unwrap();
+
+        assert_eq!(fs::read(&part).unwrap(), full);
+        assert_eq!(server.requests()[0].header("Range"), Some("bytes=8-"));
+    }
+
+    #[tokio::test]
+    async fn download_restarts_when_server_ignores_range() {
+        let full = b"complete archive";
+        let server = MockServer::start(move |_| Reply::bytes(200, full.to_vec())).await;
+        let dir = tempfile::tempdir().unwrap();
+        let part = dir.path().join("pg.tar.gz.part");
+        fs::write(&part, &full[..8]).unwrap();
+
+        let mut response = request_from(&reqwest::Client::new(), &server.url, 8).await.unwrap();
+        write_part(&mut response, &part, &CancelToken::default()).await.unwrap();
+
+        assert_eq!(fs::read(&part).unwrap(), full);
+    }
+
+    #[test]
+    fn corrupt_config_is_kept_as_bak_and_defaults_are_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let corrupt = r#"{ "channel": "insider", "supabase_key": "my-key", }"#;
+        fs::write(&path, corrupt).unwrap();
+
+        let (config, warning) = parse_config_or_backup(&path);
+
+        let backup = dir.path().join("config.json.bak");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), corrupt);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            serde_json::to_value(PulseConfig::default()).unwrap()
+        );
+        assert!(warning.unwrap().contains(&backup.display().to_string()));
+    }
+
+    #[test]
+    fn valid_config_loads_without_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let expected = PulseConfig {
+            channel: "insider".to_string(),
+            ..Default::default()
+        };
+        fs::write(&path, serde_json::to_string(&expected).unwrap()).unwrap();
+
+        let (config, warning) = parse_config_or_backup(&path);
+
+        assert_eq!(config.channel, "insider");
+        assert!(warning.is_none());
+        assert!(!dir.path().join("config.json.bak").exists());
+    }
+
+    #[cfg(unix)]
+    fn tar_with(entries: &[(&str, Option<&str>, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, link, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_mode(0o755);
+            match link {
+                Some(target) => {
+                    header.set_entry_type(tar::EntryType::Symlink);
+                    header.set_size(0);
+                    builder.append_link(&mut header, path, target).unwrap();
+                }
+                None => {
+                    header.set_entry_type(tar::EntryType::Regular);
+                    header.set_size(data.len() as u64);
+                    builder.append_data(&mut header, path, *data).unwrap();
+                }
+            }
+        }
+        builder.into_inner().unwrap()
+    }
+
+    #[cfg(unix)]
+    fn extract_each(archive: &[u8], target_dir: &Path) -> Vec<Result<(), String>> {
+        let mut archive = tar::Archive::new(archive);
+        archive
+            .entries()
+            .unwrap()
+            .map(|entry| PulseManager::extract_tar_entry(&mut entry.unwrap(), target_dir))
+            .collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tar_symlink_chain_cannot_escape_package() {
+        let root = tempfile::tempdir().unwrap();
+        let target_dir = root.path().join("pkg");
+        fs::create_dir_all(&target_dir).unwrap();
+
+        // Each link looks harmless lexically; together d/x resolves to root
+        let archive = tar_with(&[
+            ("s", Some("."), b""),
+            ("d/x", Some("../s/.."), b""),
+            ("d/x/evil", None, b"pwned"),
+        ]);
+        let results = extract_each(&archive, &target_dir);
+
+        assert!(results[0].is_ok());
+        let rejected = results[1].as_ref().unwrap_err();
+        assert!(rejected.contains("escapes package"), "{}", rejected);
+        // With the link refused, d/x/evil lands in a plain directory inside the package
+        assert!(results[2].is_ok());
+        assert!(fs::symlink_metadata(target_dir.join("d/x")).unwrap().is_dir(), "escaping link was created");
+        assert_eq!(fs::read(target_dir.join("d/x/evil")).unwrap(), b"pwned");
+        assert!(!root.path().join("evil").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tar_file_entry_replaces_symlink_instead_of_following_it() {
+        let root = tempfile::tempdir().unwrap();
+        let target_dir = root.path().join("pkg");
+        fs::create_dir_all(&target_dir).unwrap();
+        fs::write(root.path().join("outside.txt"), b"original").unwrap();
+
+        let archive = tar_with(&[
+            ("s", Some("."), b""),
+            ("d/y", Some("../s/../outside.txt"), b""),
+            ("d/y", None, b"pwned"),
+        ]);
+        let results = extract_each(&archive, &target_dir);
+
+        assert!(results[2].is_ok());
+        assert_eq!(fs::read(root.path().join("outside.txt")).unwrap(), b"original");
+        assert_eq!(fs::read(target_dir.join("d/y")).unwrap(), b"pwned");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tar_regular_entries_extract() {
+        let root = tempfile::tempdir().unwrap();
+        let archive = tar_with(&[("bin/psql", None, b"binary"), ("lib/link", Some("../bin/psql"), b"")]);
+        let results = extract_each(&archive, root.path());
+
+        assert!(results.iter().all(|r| r.is_ok()), "{:?}", results);
+        let link = root.path().join("lib/link");
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read(&link).unwrap(), b"binary");
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(fs::metadata(&link).unwrap().permissions().mode() & 0o111, 0o111);
+    }
+
+    #[test]
+    fn child_env_prepends_pack_dirs_per_os() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (bin, lib) = (tmp.path().join("bin"), tmp.path().join("lib"));
+        fs::create_dir_all(&bin).unwrap();
+        fs::create_dir_all(&lib).unwrap();
+        let existing = PathBuf::from("/usr/existing");
+        let current = |_: &str| Some(std::ffi::OsString::from(&existing));
+
+        for (os, vars) in [
+            ("windows", vec!["PATH"]),
+            ("macos", vec!["PATH", "DYLD_LIBRARY_PATH"]),
+            ("linux", vec!["PATH", "LD_LIBRARY_PATH"]),
+        ] {
+            let env = child_process_env_for(&bin.join("pg_dump"), os, current);
+            assert_eq!(env.iter().map(|(var, _)| *var).collect::<Vec<_>>(), vars, "{}", os);
+            for (var, value) in env {
+                let paths: Vec<PathBuf> = std::env::split_paths(&value).collect();
+                assert_eq!(paths, [bin.clone(), lib.clone(), existing.clone()], "{} {}", os, var);
+            }
+        }
+    }
+
+    #[test]
+    fn child_env_skips_a_missing_lib_dir_and_unset_vars() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bin = tmp.path().join("bin");
+        fs::create_dir_all(&bin).unwrap();
+
+        let env = child_process_env_for(&bin.join("psql"), "linux", |_| None);
+        assert_eq!(env.len(), 2);
+        for (_, value) in env {
+            assert_eq!(std::env::split_paths(&value).collect::<Vec<_>>(), std::slice::from_ref(&bin));
+        }
+    }
+}
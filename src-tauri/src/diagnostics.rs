@@ -0,0 +1,57 @@
+use std::fs::{self, File};
+use std::io::Write;
+use tauri::{AppHandle, Manager};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use crate::deps::{self, PulseManager};
+use crate::logs::SessionLog;
+use crate::{report, telemetry};
+
+/// Lines of the session log included in a bundle
+const LOG_LINES: usize = 2000;
+
+/// Writes a support bundle to `out_path`: the recent session log, the
+/// effective config, installed driver packages, OS/arch and app version, and
+/// the last migration report. Everything passes through the telemetry
+/// scrubber on top of the config's own secret masking.
+pub fn export_diagnostics(app: &AppHandle, out_path: &str) -> Result<String, String> {
+    let mut entries: Vec<(&str, String)> = vec![];
+
+    let log = fs::read_to_string(&app.state::<SessionLog>().path).unwrap_or_default();
+    let lines: Vec<&str> = log.lines().collect();
+    entries.push(("session.log", lines[lines.len().saturating_sub(LOG_LINES)..].join("\n")));
+
+    let config = match deps::effective_config(app) {
+        Ok(effective) => serde_json::to_string_pretty(&effective).map_err(|e| e.to_string())?,
+        Err(e) => e,
+    };
+    entries.push(("config.json", config));
+
+    let manager = PulseManager::new(app);
+    let packages: Vec<deps::PulsePackage> =
+        manager.installed_packages().iter().map(|id| manager.check_package(id)).collect();
+    entries.push(("packages.json", serde_json::to_string_pretty(&packages).map_err(|e| e.to_string())?));
+
+    let system = serde_json::json!({
+        "app_version": app.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "generated_at": report::now_secs(),
+    });
+    entries.push(("system.json", serde_json::to_string_pretty(&system).map_err(|e| e.to_string())?));
+
+    if let Some(last) = report::load_last_report(app)? {
+        entries.push(("last_report.json", serde_json::to_string_pretty(&last).map_err(|e| e.to_string())?));
+    }
+
+    let file = File::create(out_path).map_err(|e| format!("Failed to create diagnostics bundle: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, contents) in entries {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(telemetry::scrub(&contents).as_bytes()).map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| format!("Failed to write diagnostics bundle: {}", e))?;
+    Ok(out_path.to_string())
+}
@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Window};
+
+use crate::cancel::CancelToken;
+use crate::{deps, paths};
+
+/// Files between `dir_scan_progress` events
+const PROGRESS_EVERY: u64 = 500;
+
+/// Size of a directory tree; partial when `cancelled`
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct DirSize {
+    pub path: String,
+    pub files: u64,
+    pub bytes: u64,
+    pub cancelled: bool,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct DirScanProgress {
+    path: String,
+    files_scanned: u64,
+    bytes: u64,
+}
+
+/// Walks `root` without following symlinks, emitting progress and stopping
+/// early when `token` is cancelled. Blocking: run it via `tasks::run_blocking`.
+pub fn dir_size(window: &Window, root: &Path, token: &CancelToken) -> DirSize {
+    let mut size = DirSize {
+        path: root.display().to_string(),
+        files: 0,
+        bytes: 0,
+        cancelled: false,
+    };
+    let mut pending: Vec<PathBuf> = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        // Unreadable folders are skipped rather than failing the whole scan
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            if token.is_cancelled() {
+                size.cancelled = true;
+                return size;
+            }
+            let Ok(meta) = fs::symlink_metadata(entry.path()) else {
+                continue;
+            };
+            if meta.is_dir() {
+                pending.push(entry.path());
+                continue;
+            }
+            size.files += 1;
+            size.bytes += meta.len();
+            if size.files.is_multiple_of(PROGRESS_EVERY) {
+                let _ = window.emit(
+                    "dir_scan_progress",
+                    DirScanProgress {
+                        path: size.path.clone(),
+                        files_scanned: size.files,
+                        bytes: size.bytes,
+                    },
+                );
+            }
+        }
+    }
+    size
+}
+
+/// Files modified more recently than this are never pruned; they may belong
+/// to a download, backup or export that is still running
+const PRUNE_GRACE: Duration = Duration::from_secs(10 * 60);
+
+/// A cache or leftover temp file the app created
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct TempArtifact {
+    pub path: String,
+    pub kind: String, // DOWNLOAD_CACHE, PARTIAL_DOWNLOAD, PARTIAL_ARCHIVE, TEMP_FILE
+    pub size_bytes: u64,
+    pub age_secs: u64,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct PruneResult {
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+    pub skipped_recent: usize,
+    pub errors: Vec<String>,
+}
+
+fn artifact(path: PathBuf, kind: &str) -> Option<TempArtifact> {
+    let meta = fs::symlink_metadata(&path).ok().filter(|m| m.is_file())?;
+    let age_secs = meta
+        .modified()
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .map_or(0, |d| d.as_secs());
+    Some(TempArtifact { path: path.display().to_string(), kind: kind.to_string(), size_bytes: meta.len(), age_secs })
+}
+
+/// Files directly inside `dir`
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+/// Driver downloads in bin/cache (finished and `.part`), half-written
+/// `.part`/`.partial` archives under userdata/backups, and `devpulse_*`
+/// files in the system temp dir
+pub fn list_temp_artifacts(app: &AppHandle) -> Vec<TempArtifact> {
+    let mut found = vec![];
+
+    let cache = deps::PulseManager::new(app).bin_path().join("cache");
+    for path in files_in(&cache) {
+        let kind = if path.extension().is_some_and(|e| e == "part") { "PARTIAL_DOWNLOAD" } else { "DOWNLOAD_CACHE" };
+        found.extend(artifact(path, kind));
+    }
+
+    let mut pending = vec![paths::get_userdata_dir(app).join("backups")];
+    while let Some(dir) = pending.pop() {
+        for path in files_in(&dir) {
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|e| e == "part" || e == "partial") {
+                found.extend(artifact(path, "PARTIAL_ARCHIVE"));
+            }
+        }
+    }
+
+    for path in files_in(&std::env::temp_dir()) {
+        if path.file_name().is_some_and(|n| n.to_string_lossy().starts_with("devpulse_")) {
+            found.extend(artifact(path, "TEMP_FILE"));
+        }
+    }
+    found
+}
+
+/// Deletes artifacts older than `older_than_hours`. Anything touched within
+/// the last few minutes is kept whatever the cutoff.
+pub fn prune_temp_artifacts(app: &AppHandle, older_than_hours: u64) -> PruneResult {
+    let cutoff = Duration::from_secs(older_than_hours.saturating_mul(3600)).max(PRUNE_GRACE).as_secs();
+    let mut result = PruneResult { removed: vec![], freed_bytes: 0, skipped_recent: 0, errors: vec![] };
+    for artifact in list_temp_artifacts(app) {
+        if artifact.age_secs < cutoff {
+            result.skipped_recent += 1;
+            continue;
+        }
+        match fs::remove_file(&artifact.path) {
+            Ok(()) => {
+                result.freed_bytes += artifact.size_bytes;
+                result.removed.push(artifact.path);
+            }
+            Err(e) => result.errors.push(format!("{}: {}", artifact.path, e)),
+        }
+    }
+    result
+}
@@ -0,0 +1,488 @@
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, Window};
+
+use crate::{functions, http, report, storage};
+
+/// Written next to the exported objects
+const MANIFEST_NAME: &str = "storage_manifest.json";
+
+/// Characters Windows refuses in file names; encoded on every platform so an
+/// export made on Linux can be copied to Windows unchanged
+const ILLEGAL_CHARS: [char; 9] = ['<', '>', ':', '"', '|', '?', '*', '\\', '%'];
+
+/// Device names Windows reserves regardless of extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Longest file name component most filesystems accept
+const MAX_COMPONENT_BYTES: usize = 255;
+/// Stay under Windows' legacy MAX_PATH (260) with room for ".part"
+const MAX_PATH_CHARS: usize = 250;
+
+/// Events emitted every this many objects
+const PROGRESS_EVERY: usize = 25;
+
+/// One object in `storage_manifest.json`
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct ExportEntry {
+    pub key: String,
+    pub path: String, // relative to dest_dir, forward slashes
+    pub bytes: u64,
+    pub status: String, // DOWNLOADED, FAILED
+    pub error: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct ExportManifest {
+    pub bucket_id: String,
+    pub source_url: String,
+    pub exported_at: u64, // unix seconds
+    pub objects: Vec<ExportEntry>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ExportSummary {
+    pub dest_dir: String,
+    pub objects_downloaded: usize,
+    pub objects_failed: usize,
+    pub bytes: u64,
+    pub manifest_path: String,
+}
+
+/// One local file in an `upload_folder_to_bucket` report
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct UploadEntry {
+    pub file: String, // relative to local_dir
+    pub key: String,
+    pub bytes: u64,
+    pub status: String, // UPLOADED, SKIPPED (over the size limit), FAILED
+    pub error: Option<String>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct UploadReport {
+    pub bucket_id: String,
+    pub uploaded: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub files: Vec<UploadEntry>,
+}
+
+/// Payload of the `export_progress` and `upload_progress` events
+#[derive(serde::Serialize, Clone, Debug)]
+struct ExportProgress {
+    bucket_id: String,
+    objects_done: usize,
+    objects_total: usize,
+    bytes_done: u64,
+}
+
+/// Makes one key segment a legal file name: illegal and control characters
+/// become %XX, reserved device names and trailing dots/spaces are escaped
+fn sanitize_segment(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    for c in segment.chars() {
+        if c.is_control() || ILLEGAL_CHARS.contains(&c) {
+            out.push_str(&format!("%{:02X}", c as u32));
+        } else {
+            out.push(c);
+        }
+    }
+    if out == "." || out == ".." {
+        out = out.replace('.', "%2E");
+    }
+    let stem = out.split('.').next().unwrap_or_default().to_ascii_uppercase();
+    if RESERVED_NAMES.contains(&stem.as_str()) {
+        out.insert(0, '_');
+    }
+    if out.ends_with(['.', ' ']) {
+        let last = out.pop().unwrap();
+        out.push_str(&format!("%{:02X}", last as u32));
+    }
+    out
+}
+
+/// Short, stable stand-in for names that are too long: hash + original extension
+fn hashed_name(key: &str, original: &str) -> String {
+    let digest = hex::encode(Sha256::digest(key.as_bytes()));
+    match original.rsplit_once('.') {
+        Some((_, ext)) if !ext.is_empty() && ext.len() <= 16 => format!("{}.{}", &digest[..32], ext),
+        _ => digest[..32].to_string(),
+    }
+}
+
+/// Relative local path for an object key, preserving its folder hierarchy
+fn local_path_for(key: &str, dest_dir: &Path) -> PathBuf {
+    let segments: Vec<String> = key
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(sanitize_segment)
+        .map(|s| if s.len() > MAX_COMPONENT_BYTES { hashed_name(key, &s) } else { s })
+        .collect();
+    let mut rel: PathBuf = segments.iter().collect();
+
+    // Too deep for Windows: flatten into the first folder under a hashed name
+    if dest_dir.join(&rel).to_string_lossy().chars().count() > MAX_PATH_CHARS {
+        let file = hashed_name(key, segments.last().map(String::as_str).unwrap_or_default());
+        rel = match segments.first() {
+            Some(top) if segments.len() > 1 => Path::new(top).join(file),
+            _ => PathBuf::from(file),
+        };
+    }
+    rel
+}
+
+/// Downloads every object of a source bucket into `dest_dir`, recreating the
+/// key hierarchy, and writes `storage_manifest.json` mapping keys to files
+pub async fn download_bucket_to_disk(
+    window: &Window,
+    mirror: &storage::StorageMirror,
+    source_url: &str,
+    bucket_id: &str,
+    dest_dir: &Path,
+) -> Result<ExportSummary, String> {
+    std::fs::create_dir_all(dest_dir).map_err(|e| format!("Cannot create {}: {}", dest_dir.display(), e))?;
+
+    window.emit("log", format!("Export: listing bucket {}...", bucket_id)).unwrap();
+    // Every key at any depth, so nested folders are exported too
+    let objects = mirror.list_objects(bucket_id).await?;
+    window.emit("log", format!("Export: {} objects to download", objects.len())).unwrap();
+
+    let concurrency = mirror.performance().transfer_concurrency;
+    let mut downloads = futures::stream::iter(0..objects.len())
+        .map(|i| {
+            let obj = &objects[i];
+            let rel = local_path_for(&obj.name, dest_dir);
+            async move {
+                let path = dest_dir.join(&rel);
+                let outcome = match path.parent().map(std::fs::create_dir_all) {
+                    Some(Err(e)) => Err(e.to_string()),
+                    _ => http::with_backoff(http::RETRY_ATTEMPTS, || mirror.download_object_to_file(bucket_id, &obj.name, &path)).await,
+                };
+                (obj, rel, outcome)
+            }
+        })
+        .buffer_unordered(concurrency);
+
+    let mut entries = vec![];
+    let mut bytes_done = 0;
+    while let Some((obj, rel, outcome)) = downloads.next().await {
+        let path = rel.to_string_lossy().replace('\\', "/");
+        let entry = match outcome {
+            Ok(bytes) => {
+                bytes_done += bytes;
+                ExportEntry { key: obj.name.clone(), path, bytes, status: "DOWNLOADED".to_string(), error: None }
+            }
+            Err(e) => {
+                window.emit("log", format!("  Failed: {} ({})", obj.name, e)).unwrap();
+                ExportEntry { key: obj.name.clone(), path, bytes: 0, status: "FAILED".to_string(), error: Some(e) }
+            }
+        };
+        entries.push(entry);
+
+        if entries.len().is_multiple_of(PROGRESS_EVERY) || entries.len() == objects.len() {
+            let _ = window.emit("export_progress", ExportProgress {
+                bucket_id: bucket_id.to_string(),
+                objects_done: entries.len(),
+                objects_total: objects.len(),
+                bytes_done,
+            });
+        }
+    }
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let objects_failed = entries.iter().filter(|e| e.status == "FAILED").count();
+    let manifest = ExportManifest {
+        bucket_id: bucket_id.to_string(),
+        source_url: source_url.to_string(),
+        exported_at: report::now_secs(),
+        objects: entries,
+    };
+    let manifest_path = dest_dir.join(MANIFEST_NAME);
+    let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    std::fs::write(&manifest_path, json).map_err(|e| format!("Failed to write {}: {}", MANIFEST_NAME, e))?;
+
+    let summary = ExportSummary {
+        dest_dir: dest_dir.to_string_lossy().to_string(),
+        objects_downloaded: manifest.objects.len() - objects_failed,
+        objects_failed,
+        bytes: bytes_done,
+        manifest_path: manifest_path.to_string_lossy().to_string(),
+    };
+    window.emit("log", format!(
+        "Export complete: {} downloaded, {} failed ({:.2} MB)",
+        summary.objects_downloaded,
+        summary.objects_failed,
+        bytes_done as f64 / (1024.0 * 1024.0)
+    )).unwrap();
+    Ok(summary)
+}
+
+/// "<prefix>/<rel>" with forward slashes and no doubled separators
+fn object_key(prefix: &str, rel: &str) -> String {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        rel.to_string()
+    } else {
+        format!("{}/{}", prefix, rel)
+    }
+}
+
+/// Uploads every file under `local_dir` to a destination bucket under
+/// `prefix`, replacing existing objects. A folder made by
+/// `download_bucket_to_disk` is restored under its original keys.
+pub async fn upload_folder_to_bucket(
+    window: &Window,
+    mirror: &storage::StorageMirror,
+    bucket_id: &str,
+    local_dir: &Path,
+    prefix: &str,
+) -> Result<UploadReport, String> {
+    if !local_dir.is_dir() {
+        return Err(format!("Not a folder: {}", local_dir.display()));
+    }
+
+    // Exports may have encoded or shortened file names; the manifest knows the real keys
+    let original_keys: HashMap<String, String> = std::fs::read_to_string(local_dir.join(MANIFEST_NAME))
+        .ok()
+        .and_then(|data| serde_json::from_str::<ExportManifest>(&data).ok())
+        .map(|m| m.objects.into_iter().map(|o| (o.path, o.key)).collect())
+        .unwrap_or_default();
+    if !original_keys.is_empty() {
+        window.emit("log", format!("Upload: restoring original keys from {}", MANIFEST_NAME)).unwrap();
+    }
+
+    let size_limit = mirror
+        .list_dest_buckets()
+        .await?
+        .into_iter()
+        .find(|b| b.id == bucket_id)
+        .ok_or_else(|| format!("Destination bucket {} does not exist", bucket_id))?
+        .file_size_limit;
+
+    let mut files = vec![];
+    for path in functions::walkdir(local_dir)?.into_iter().filter(|p| p.is_file()) {
+        let rel = path.strip_prefix(local_dir).map_err(|e| e.to_string())?.to_string_lossy().replace('\\', "/");
+        if rel == MANIFEST_NAME || rel.ends_with(".part") {
+            continue;
+        }
+        let key = object_key(prefix, original_keys.get(&rel).unwrap_or(&rel));
+        files.push((path, rel, key));
+    }
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    window.emit("log", format!("Upload: {} files to {}", files.len(), bucket_id)).unwrap();
+
+    let concurrency = mirror.performance().transfer_concurrency;
+    let mut uploads = futures::stream::iter(0..files.len())
+        .map(|i| {
+            let (path, rel, key) = &files[i];
+            async move {
+                let bytes = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+                let mut entry = UploadEntry {
+                    file: rel.clone(),
+                    key: key.clone(),
+                    bytes,
+                    status: "UPLOADED".to_string(),
+                    error: None,
+                };
+                if let Some(limit) = size_limit.filter(|&l| bytes > l) {
+                    entry.status = "SKIPPED".to_string();
+                    entry.error = Some(format!("{} bytes exceeds the bucket limit of {} bytes", bytes, limit));
+                    return entry;
+                }
+                let outcome = match std::fs::read(path) {
+                    Ok(data) => {
+                        let content_type = storage::content_type_for_file(key, &data);
+                        http::with_backoff(http::RETRY_ATTEMPTS, || {
+                            mirror.upsert_object(bucket_id, key, data.clone(), &content_type)
+                        })
+                        .await
+                    }
+                    Err(e) => Err(format!("Cannot read file: {}", e)),
+                };
+                if let Err(e) = outcome {
+                    entry.status = "FAILED".to_string();
+                    entry.error = Some(e);
+                }
+                entry
+            }
+        })
+        .buffer_unordered(concurrency);
+
+    let mut entries = vec![];
+    let mut bytes_done = 0;
+    while let Some(entry) = uploads.next().await {
+        match entry.status.as_str() {
+            "UPLOADED" => bytes_done += entry.bytes,
+            "SKIPPED" => window.emit("log", format!("  Skipped {}: {}", entry.file, entry.error.as_deref().unwrap_or_default())).unwrap(),
+            _ => window.emit("log", format!("  Failed: {} ({})", entry.file, entry.error.as_deref().unwrap_or_default())).unwrap(),
+        }
+        entries.push(entry);
+
+        if entries.len().is_multiple_of(PROGRESS_EVERY) || entries.len() == files.len() {
+            let _ = window.emit("upload_progress", ExportProgress {
+                bucket_id: bucket_id.to_string(),
+                objects_done: entries.len(),
+                objects_total: files.len(),
+                bytes_done,
+            });
+        }
+    }
+    entries.sort_by(|a, b| a.file.cmp(&b.file));
+
+    let count = |s: &str| entries.iter().filter(|e| e.status == s).count();
+    let report = UploadReport {
+        bucket_id: bucket_id.to_string(),
+        uploaded: count("UPLOADED"),
+        skipped: count("SKIPPED"),
+        failed: count("FAILED"),
+        files: entries,
+    };
+    window.emit("log", format!(
+        "Upload complete: {} uploaded, {} skipped, {} failed",
+        report.uploaded, report.skipped, report.failed
+    )).unwrap();
+    Ok(report)
+}
+
+/// One object in a public bucket index
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct PublicIndexEntry {
+    pub key: String,
+    pub url: String,
+    pub bytes: u64,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct PublicIndex {
+    pub bucket_id: String,
+    pub base_public_url: String,
+    pub generated_at: u64, // unix seconds
+    pub total_bytes: u64,
+    pub objects: Vec<PublicIndexEntry>,
+    pub written_to: Vec<String>, // index files written, when an output path was given
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// sitemaps.org urlset; sizes don't fit the schema, so only locations are listed
+fn sitemap_xml(index: &PublicIndex) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for entry in &index.objects {
+        xml.push_str(&format!("  <url><loc>{}</loc></url>\n", xml_escape(&entry.url)));
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// Index entries for every listed object, nested keys included, under `base`
+fn public_entries(base: &str, objects: Vec<storage::StorageObject>) -> Vec<PublicIndexEntry> {
+    objects
+        .into_iter()
+        .map(|o| PublicIndexEntry {
+            url: format!("{}/{}", base, storage::encode_path(&o.name)),
+            bytes: o.size(),
+            key: o.name,
+        })
+        .collect()
+}
+
+/// Lists every object of a public source bucket with its public URL and size.
+/// `base_public_url` defaults to `<source>/storage/v1/object/public/<bucket>`
+/// (pass a CDN origin to index that instead). With `out_path` the index is
+/// written as JSON there, plus a sitemap next to it (`.xml`) when `xml` is set.
+pub async fn export_public_index(
+    window: &Window,
+    mirror: &storage::StorageMirror,
+    source_url: &str,
+    bucket_id: &str,
+    base_public_url: &str,
+    out_path: Option<&str>,
+    xml: bool,
+) -> Result<PublicIndex, String> {
+    let bucket = mirror
+        .list_source_buckets()
+        .await?
+        .into_iter()
+        .find(|b| b.id == bucket_id)
+        .ok_or_else(|| format!("Bucket '{}' not found on the source project.", bucket_id))?;
+    if !bucket.public {
+        return Err(format!(
+            "Bucket '{}' is private; its objects have no public URLs. Use download_bucket_to_disk to audit it instead.",
+            bucket_id
+        ));
+    }
+
+    let base = match base_public_url.trim().trim_end_matches('/') {
+        "" => format!("{}/storage/v1/object/public/{}", source_url.trim_end_matches('/'), storage::encode_path(bucket_id)),
+        given => given.to_string(),
+    };
+    window.emit("log", format!("Public index: listing bucket {}...", bucket_id)).unwrap();
+    let objects = public_entries(&base, mirror.list_objects(bucket_id).await?);
+
+    let mut index = PublicIndex {
+        bucket_id: bucket_id.to_string(),
+        base_public_url: base,
+        generated_at: report::now_secs(),
+        total_bytes: objects.iter().map(|o| o.bytes).sum(),
+        objects,
+        written_to: vec![],
+    };
+    if let Some(out_path) = out_path.filter(|p| !p.trim().is_empty()) {
+        let json = serde_json::to_string_pretty(&index).map_err(|e| e.to_string())?;
+        std::fs::write(out_path, json).map_err(|e| format!("Failed to write {}: {}", out_path, e))?;
+        index.written_to.push(out_path.to_string());
+        if xml {
+            let xml_path = Path::new(out_path).with_extension("xml");
+            std::fs::write(&xml_path, sitemap_xml(&index)).map_err(|e| format!("Failed to write {}: {}", xml_path.display(), e))?;
+            index.written_to.push(xml_path.to_string_lossy().to_string());
+        }
+    }
+    window.emit("log", format!(
+        "Public index: {} objects ({} bytes) in {}",
+        index.objects.len(),
+        index.total_bytes,
+        bucket_id
+    )).unwrap();
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockServer, Reply};
+
+    #[tokio::test]
+    async fn public_index_covers_objects_in_nested_folders() {
+        let server = MockServer::start(|request| {
+            let entry = |name: &str, size: Option<u64>| {
+                serde_json::json!({ "name": name, "id": size.map(|_| name), "metadata": size.map(|s| serde_json::json!({ "size": s })) })
+            };
+            let level = match request.json()["prefix"].as_str().unwrap_or_default() {
+                "" => vec![entry("logo.png", Some(10)), entry("blog", None)],
+                "blog/" => vec![entry("2024", None)],
+                "blog/2024/" => vec![entry("hello world.jpg", Some(20))],
+                _ => vec![],
+            };
+            Reply::json(200, serde_json::Value::Array(level))
+        })
+        .await;
+        let mirror = storage::StorageMirror::new(reqwest::Client::new(), &server.url, "key", &server.url, "key");
+
+        let entries = public_entries("https://cdn.example.com/assets", mirror.list_objects("assets").await.unwrap());
+        let urls: Vec<&str> = entries.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(urls, ["https://cdn.example.com/assets/blog/2024/hello%20world.jpg", "https://cdn.example.com/assets/logo.png"]);
+        assert_eq!(entries.iter().map(|e| e.bytes).sum::<u64>(), 30);
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Emitter, Window};
+
+use crate::db::{quote_ident, Psql};
+
+/// Installed by default everywhere; never worth reporting
+const BUILTIN_EXTENSIONS: [&str; 1] = ["plpgsql"];
+
+/// How one source extension fared on the destination
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ExtensionStatus {
+    pub name: String,
+    pub schema: String,
+    pub source_version: String,
+    pub dest_version: Option<String>,
+    pub status: String, // PRESENT, CREATED, UNAVAILABLE, FAILED
+    pub error: Option<String>,
+}
+
+/// Recreates the source database's extensions on the destination before data
+/// is restored, so extension types (vector, geometry, ...) exist there.
+/// Extensions the destination can't install are reported, not fatal.
+pub async fn migrate_extensions(
+    window: &Window,
+    app: &AppHandle,
+    source_db: &str,
+    dest_db: &str,
+) -> Result<Vec<ExtensionStatus>, String> {
+    let psql = Psql::locate(app)?;
+    let source = psql
+        .query(
+            source_db,
+            "SELECT e.extname, e.extversion, n.nspname FROM pg_extension e \
+             JOIN pg_namespace n ON n.oid = e.extnamespace ORDER BY e.extname",
+        )
+        .await?;
+    let installed: HashMap<String, String> = psql
+        .query(dest_db, "SELECT extname, extversion FROM pg_extension")
+        .await?
+        .into_iter()
+        .filter(|r| r.len() == 2)
+        .map(|r| (r[0].clone(), r[1].clone()))
+        .collect();
+    let available: HashSet<String> = psql
+        .query(dest_db, "SELECT name FROM pg_available_extensions")
+        .await?
+        .into_iter()
+        .filter_map(|r| r.into_iter().next())
+        .collect();
+
+    let mut results = vec![];
+    for row in source.into_iter().filter(|r| r.len() == 3) {
+        let (name, source_version, schema) = (row[0].clone(), row[1].clone(), row[2].clone());
+        if BUILTIN_EXTENSIONS.contains(&name.as_str()) {
+            continue;
+        }
+
+        let mut status = ExtensionStatus {
+            name: name.clone(),
+            schema: schema.clone(),
+            source_version,
+            dest_version: installed.get(&name).cloned(),
+            status: "PRESENT".to_string(),
+            error: None,
+        };
+        if status.dest_version.is_none() {
+            if !available.contains(&name) {
+                status.status = "UNAVAILABLE".to_string();
+            } else {
+                let create = format!(
+                    "CREATE EXTENSION IF NOT EXISTS {} WITH SCHEMA {}",
+                    quote_ident(&name),
+                    quote_ident(&schema)
+                );
+                match psql.execute(dest_db, &[create]).await {
+                    Ok(_) => {
+                        window.emit("log", format!("  Enabled extension {} in schema {}", name, schema)).unwrap();
+                        status.status = "CREATED".to_string();
+                    }
+                    Err(e) => {
+                        status.status = "FAILED".to_string();
+                        status.error = Some(e);
+                    }
+                }
+            }
+        }
+        results.push(status);
+    }
+
+    let blocked: Vec<&str> = results
+        .iter()
+        .filter(|s| s.status == "UNAVAILABLE" || s.status == "FAILED")
+        .map(|s| s.name.as_str())
+        .collect();
+    if !blocked.is_empty() {
+        window.emit("log", format!(
+            "WARNING: Extensions missing on the destination: {}. Enable them in the destination project's dashboard (Database > Extensions) before restoring, or restores will fail with \"type does not exist\".",
+            blocked.join(", ")
+        )).unwrap();
+    }
+    Ok(results)
+}
+
+/// Schemas every Supabase project provisions; a schema dump assumes they exist
+const REQUIRED_SCHEMAS: [&str; 4] = ["auth", "storage", "graphql", "extensions"];
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SchemaCheck {
+    pub present: Vec<String>,
+    pub missing: Vec<String>,
+    pub looks_like_supabase: bool,
+}
+
+/// Confirms the destination has the platform schemas a Supabase restore
+/// relies on, catching a restore aimed at a plain Postgres early
+pub async fn check_destination_schemas(window: &Window, app: &AppHandle, dest_db: &str) -> Result<SchemaCheck, String> {
+    let psql = Psql::locate(app)?;
+    let existing: HashSet<String> = psql
+        .query(dest_db, "SELECT nspname FROM pg_namespace")
+        .await?
+        .into_iter()
+        .filter_map(|r| r.into_iter().next())
+        .collect();
+
+    let (present, missing): (Vec<String>, Vec<String>) = REQUIRED_SCHEMAS
+        .iter()
+        .map(|s| s.to_string())
+        .partition(|s| existing.contains(s));
+    // auth and storage only come from the platform; their absence is the tell
+    let looks_like_supabase = existing.contains("auth") && existing.contains("storage");
+
+    if !missing.is_empty() {
+        window.emit("log", format!(
+            "WARNING: Destination is missing Supabase schemas: {}.{}",
+            missing.join(", "),
+            if looks_like_supabase {
+                ""
+            } else {
+                " It looks like a plain Postgres database, not a Supabase project; restoring a Supabase dump here will fail."
+            }
+        )).unwrap();
+    } else {
+        window.emit("log", "Destination schemas OK: auth, storage, graphql and extensions are present.").unwrap();
+    }
+    Ok(SchemaCheck { present, missing, looks_like_supabase })
+}
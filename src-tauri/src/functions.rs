@@ -2,6 +2,8 @@ use tauri::{Window, Emitter};
 use reqwest::Client;
 use regex::Regex;
 
+use crate::http::{self, SupabaseError};
+
 // "The Pivot": Since we can't download code, we backup config and zip local source.
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
@@ -26,71 +28,284 @@ pub fn extract_project_ref(url: &str) -> Result<String, String> {
         .ok_or_else(|| format!("Invalid Supabase URL format: {}", url))
 }
 
+/// Canonical project identity for comparisons: the project ref for hosted
+/// projects, otherwise the lowercased URL without trailing slashes
+pub fn normalize_project(url: &str) -> String {
+    let cleaned = url.trim().trim_end_matches('/').to_lowercase();
+    extract_project_ref(&cleaned).unwrap_or(cleaned)
+}
+
+/// True when both URLs point at the same Supabase project
+pub fn is_same_project(source_url: &str, dest_url: &str) -> bool {
+    normalize_project(source_url) == normalize_project(dest_url)
+}
+
+/// Refuses a migration from a project into itself unless `allow_same_project`.
+/// Ok(true) when the projects match but the flag allows it, so callers can warn.
+pub fn ensure_distinct_projects(source_url: &str, dest_url: &str, allow_same_project: bool) -> Result<bool, String> {
+    if !is_same_project(source_url, dest_url) {
+        return Ok(false);
+    }
+    if allow_same_project {
+        return Ok(true);
+    }
+    Err(format!(
+        "Source and destination are the same project ({}). Refusing to migrate.",
+        normalize_project(source_url)
+    ))
+}
+
+/// Functions requested per Management API page
+const FUNCTIONS_PAGE_SIZE: usize = 100;
+
+/// The `rel="next"` target of a `Link` response header, if any
+fn next_link(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let link = headers.get(reqwest::header::LINK)?.to_str().ok()?;
+    let re = Regex::new(r#"<([^>]+)>\s*;\s*rel="?next"?"#).ok()?;
+    re.captures(link).map(|c| c[1].to_string())
+}
+
 pub async fn backup_function_config(
     window: &Window, 
+    client: &Client,
     project_url: &str, 
     service_key: &str
 ) -> Result<Vec<FunctionConfig>, String> {
     window.emit("log", "Fetching Edge Function configurations...").unwrap();
-    
+    list_functions(window, client, project_url, service_key).await
+}
+
+/// Every function of the project, following `Link` headers when the API sends
+/// them and `limit`/`offset` paging otherwise
+pub async fn list_functions(
+    window: &Window,
+    client: &Client,
+    project_url: &str,
+    service_key: &str
+) -> Result<Vec<FunctionConfig>, String> {
     // Extract project ref for Management API
     let project_ref = extract_project_ref(project_url)?;
     window.emit("log", format!("Project Ref: {}", project_ref)).unwrap();
     
-    let client = Client::new();
-    
     // Supabase Management API endpoint for functions
     let management_url = format!(
         "https://api.supabase.com/v1/projects/{}/functions", 
         project_ref
     );
-    
-    let response = client
-        .get(&management_url)
-        .header("Authorization", format!("Bearer {}", service_key))
-        .header("Content-Type", "application/json")
-        .send()
-        .await
-        .map_err(|e| format!("API request failed: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        // Management API may require org-level token, not project service key
-        window.emit("log", format!(
-            "Management API returned {}. This API may require an organization access token.", 
-            status
-        )).unwrap();
+
+    let mut configs: Vec<FunctionConfig> = vec![];
+    let mut seen = std::collections::HashSet::new();
+    let mut offset = 0;
+    let mut next_url = Some(format!("{}?limit={}&offset=0", management_url, FUNCTIONS_PAGE_SIZE));
+    let mut page = 0;
+
+    while let Some(url) = next_url.take() {
+        page += 1;
+        let response = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", service_key))
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .map_err(|e| format!("API request failed: {}", e))?;
         
-        // Return empty - user must link local source
-        window.emit("log", "Falling back to local source linking mode.").unwrap();
-        return Ok(vec![]);
+        if !response.status().is_success() {
+            let error = SupabaseError::from_response(response).await;
+            // Management API may require org-level token, not project service key
+            window.emit("log", format!(
+                "Management API returned {}. This API may require an organization access token.", 
+                error
+            )).unwrap();
+            
+            // Return empty - user must link local source
+            window.emit("log", "Falling back to local source linking mode.").unwrap();
+            return Ok(vec![]);
+        }
+
+        let link = next_link(response.headers());
+        let batch: Vec<FunctionConfig> = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        let batch_len = batch.len();
+
+        // An API that ignores paging returns the same list again; stop on repeats
+        let before = configs.len();
+        configs.extend(batch.into_iter().filter(|f| seen.insert(f.slug.clone())));
+        let added = configs.len() - before;
+        window.emit("log", format!("  Page {}: {} functions ({} total)", page, added, configs.len())).unwrap();
+
+        next_url = match link {
+            _ if added == 0 => None,
+            Some(link) => Some(link),
+            None if batch_len >= FUNCTIONS_PAGE_SIZE => {
+                offset += batch_len;
+                Some(format!("{}?limit={}&offset={}", management_url, FUNCTIONS_PAGE_SIZE, offset))
+            }
+            None => None,
+        };
     }
     
-    let configs: Vec<FunctionConfig> = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
-    
     window.emit("log", format!("Found {} function configurations.", configs.len())).unwrap();
     Ok(configs)
 }
 
-pub fn zip_local_source(window: &Window, local_path: &str) -> Result<String, String> {
-    use std::fs::File;
-    use std::io::Write;
-    use zip::write::FileOptions;
-    use zip::ZipWriter;
-    
+/// How long a single asynchronous config update may take before it's reported as stuck
+const RESTORE_OPERATION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct FunctionRestoreResult {
+    pub slug: String,
+    pub status: String, // final status, or FAILED
+    pub error: Option<String>,
+}
+
+/// Re-applies backed-up function settings (name, verify_jwt) to a project.
+/// Updates the API accepts asynchronously are polled until they settle.
+pub async fn restore_function_config(
+    window: &Window,
+    client: &Client,
+    project_url: &str,
+    service_key: &str,
+    configs: &[FunctionConfig],
+) -> Result<Vec<FunctionRestoreResult>, String> {
+    let project_ref = extract_project_ref(project_url)?;
+    let project_api = format!("https://api.supabase.com/v1/projects/{}", project_ref);
+    window.emit("log", format!("Restoring {} function configurations...", configs.len())).unwrap();
+
+    let mut results = vec![];
+    for config in configs {
+        let outcome = async {
+            let response = client
+                .patch(format!("{}/functions/{}", project_api, config.slug))
+                .header("Authorization", format!("Bearer {}", service_key))
+                .json(&serde_json::json!({ "name": config.name, "verify_jwt": config.verify_jwt }))
+                .send()
+                .await
+                .map_err(|e| format!("API request failed: {}", e))?;
+            if !response.status().is_success() {
+                return Err(SupabaseError::from_response(response).await.to_string());
+            }
+
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            let job_id = body["operation_id"].as_str().or(body["job_id"].as_str());
+            match job_id {
+                Some(job_id) => {
+                    let operations_url = format!("{}/operations", project_api);
+                    http::poll_operation(window, client, &operations_url, service_key, job_id, RESTORE_OPERATION_TIMEOUT).await
+                }
+                None => Ok(body["status"].as_str().unwrap_or("UPDATED").to_string()),
+            }
+        }
+        .await;
+
+        match outcome {
+            Ok(status) => {
+                window.emit("log", format!("  Restored {}: {}", config.slug, status)).unwrap();
+                results.push(FunctionRestoreResult { slug: config.slug.clone(), status, error: None });
+            }
+            Err(e) => {
+                window.emit("log", format!("  Failed to restore {}: {}", config.slug, e)).unwrap();
+                results.push(FunctionRestoreResult { slug: config.slug.clone(), status: "FAILED".to_string(), error: Some(e) });
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// Distinguishes archives created in the same nanosecond
+static ARCHIVE_SEQ: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// "<unix nanos>-<pid>-<seq>": unique per call, even for concurrent calls
+fn unique_archive_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let seq = ARCHIVE_SEQ.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    format!("{}-{}-{}", nanos, std::process::id(), seq)
+}
+
+/// Suffix of an archive still being written; renamed away once complete
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// `.partial` archives in `out_dir` left behind by an earlier run that died
+/// mid-write. Ones written by this process may still be in progress and are ignored.
+pub fn stale_partial_archives(out_dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let own = format!("-{}-", std::process::id());
+    std::fs::read_dir(out_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| {
+                    let name = p.file_name().unwrap_or_default().to_string_lossy();
+                    name.ends_with(PARTIAL_SUFFIX) && !name.contains(&own)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Zips `local_path` into a uniquely named `.partial` file in `out_dir` and
+/// renames it only once the archive is finished, so the returned path always
+/// points at a complete zip. The `.partial` file is removed on failure.
+pub fn zip_local_source(
+    window: &Window,
+    local_path: &str,
+    out_dir: &std::path::Path,
+    skip_bad_entries: bool,
+) -> Result<String, String> {
+    archive_source(&|msg| window.emit("log", msg).unwrap(), local_path, out_dir, skip_bad_entries)
+}
+
+fn archive_source(
+    log: &dyn Fn(String),
+    local_path: &str,
+    out_dir: &std::path::Path,
+    skip_bad_entries: bool,
+) -> Result<String, String> {
     let source_path = std::path::Path::new(local_path);
     if !source_path.exists() {
         return Err(format!("Path does not exist: {}", local_path));
     }
+    std::fs::create_dir_all(out_dir).map_err(|e| e.to_string())?;
 
-    window.emit("log", format!("Zipping source from: {}", local_path)).unwrap();
-    
-    // Create output zip file in temp directory
-    let output_path = std::env::temp_dir().join("devpulse_functions_backup.zip");
-    let file = File::create(&output_path)
+    for stale in stale_partial_archives(out_dir) {
+        log(format!(
+            "WARNING: Incomplete archive from an interrupted run: {}. It is not a valid backup; delete it.",
+            stale.display()
+        ));
+    }
+
+    log(format!("Zipping source from: {}", local_path));
+
+    let output_path = out_dir.join(format!("functions_source-{}.zip", unique_archive_id()));
+    let partial_path = output_path.with_extension(format!("zip{}", PARTIAL_SUFFIX));
+    let result = write_source_zip(log, source_path, &partial_path, skip_bad_entries)
+        .and_then(|()| std::fs::rename(&partial_path, &output_path).map_err(|e| format!("Failed to store archive: {}", e)));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&partial_path);
+    }
+    result?;
+
+    let output_str = output_path.to_string_lossy().to_string();
+    log(format!("Source archived: {}", output_str));
+    Ok(output_str)
+}
+
+fn write_source_zip(
+    log: &dyn Fn(String),
+    source_path: &std::path::Path,
+    output_path: &std::path::Path,
+    skip_bad_entries: bool,
+) -> Result<(), String> {
+    use std::fs::File;
+    use std::io::Write;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    let file = File::create(output_path)
         .map_err(|e| format!("Failed to create zip file: {}", e))?;
     
     let mut zip = ZipWriter::new(file);
@@ -99,20 +314,70 @@ pub fn zip_local_source(window: &Window, local_path: &str) -> Result<String, Str
     
     // If it's a directory, walk and add all files
     if source_path.is_dir() {
-        for entry in walkdir(source_path)? {
-            let entry_path = entry;
-            let name = entry_path.strip_prefix(source_path)
-                .map_err(|e| e.to_string())?
-                .to_string_lossy();
+        let mut files = vec![];
+        match functions_root_of(source_path) {
+            // A single function inside a functions root: nest it under its own
+            // name and bring the shared assets along so imports still resolve
+            Some(root) => {
+                let prefix = source_path.strip_prefix(&root).map_err(|e| e.to_string())?;
+                for entry in walkdir(source_path)? {
+                    let rel = entry.strip_prefix(source_path).map_err(|e| e.to_string())?;
+                    files.push((prefix.join(rel), entry));
+                }
+                for asset in SHARED_ASSETS {
+                    let asset_path = root.join(asset);
+                    if asset_path.is_dir() {
+                        files.push((asset.into(), asset_path.clone()));
+                        for entry in walkdir(&asset_path)? {
+                            let rel = entry.strip_prefix(&root).map_err(|e| e.to_string())?;
+                            files.push((rel.to_path_buf(), entry));
+                        }
+                    } else if asset_path.is_file() {
+                        files.push((asset.into(), asset_path));
+                    }
+                }
+            }
+            None => {
+                for entry in walkdir(source_path)? {
+                    let rel = entry.strip_prefix(source_path).map_err(|e| e.to_string())?;
+                    files.push((rel.to_path_buf(), entry));
+                }
+            }
+        }
+
+        let included: Vec<&str> = SHARED_ASSETS
+            .iter()
+            .copied()
+            .filter(|asset| files.iter().any(|(name, _)| name == std::path::Path::new(asset)))
+            .collect();
+        if !included.is_empty() {
+            log(format!("Including shared assets: {}", included.join(", ")));
+        }
+
+        let mut skipped = 0;
+        for (rel, entry_path) in files {
+            // Forward slashes so the layout restores on every platform
+            let name = rel.to_string_lossy().replace('\\', "/");
             
             if entry_path.is_file() {
-                zip.start_file(name.to_string(), options)
-                    .map_err(|e| e.to_string())?;
-                let content = std::fs::read(&entry_path)
+                // Read first so an unreadable file never leaves a half-written entry
+                let content = match std::fs::read(&entry_path) {
+                    Ok(c) => c,
+                    Err(e) if skip_bad_entries => {
+                        log(format!("Skipped {}: {}", name, e));
+                        skipped += 1;
+                        continue;
+                    }
+                    Err(e) => return Err(format!("Failed to read {}: {}", name, e)),
+                };
+                zip.start_file(name, options)
                     .map_err(|e| e.to_string())?;
                 zip.write_all(&content).map_err(|e| e.to_string())?;
             }
         }
+        if skipped > 0 {
+            log(format!("Archive created with {} skipped files.", skipped));
+        }
     } else {
         // Single file
         let name = source_path.file_name()
@@ -126,14 +391,25 @@ pub fn zip_local_source(window: &Window, local_path: &str) -> Result<String, Str
     }
     
     zip.finish().map_err(|e| e.to_string())?;
-    
-    let output_str = output_path.to_string_lossy().to_string();
-    window.emit("log", format!("Source archived: {}", output_str)).unwrap();
-    Ok(output_str)
+    Ok(())
+}
+
+/// Files and folders next to the functions that imports commonly reach for
+const SHARED_ASSETS: [&str; 3] = ["import_map.json", "deno.json", "_shared"];
+
+/// The `supabase/functions` root containing `dir`, when `dir` is one function
+/// directly inside it. None when `dir` is the root itself or sits in any other
+/// layout: a function's own deno.json or import_map.json doesn't make it a root.
+fn functions_root_of(dir: &std::path::Path) -> Option<std::path::PathBuf> {
+    let is_root = |p: &std::path::Path| {
+        p.file_name().is_some_and(|n| n == "functions")
+            && p.parent().and_then(|p| p.file_name()).is_some_and(|n| n == "supabase")
+    };
+    dir.parent().filter(|p| is_root(p)).map(|p| p.to_path_buf())
 }
 
 /// Simple directory walker
-fn walkdir(path: &std::path::Path) -> Result<Vec<std::path::PathBuf>, String> {
+pub fn walkdir(path: &std::path::Path) -> Result<Vec<std::path::PathBuf>, String> {
     let mut results = vec![];
     
     fn visit(dir: &std::path::Path, results: &mut Vec<std::path::PathBuf>) -> Result<(), String> {
@@ -151,3 +427,178 @@ fn walkdir(path: &std::path::Path) -> Result<Vec<std::path::PathBuf>, String> {
     visit(path, &mut results)?;
     Ok(results)
 }
+
+/// Entrypoint check for one function directory in an archive
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct FunctionEntrypointCheck {
+    pub function: String,
+    pub has_entrypoint: bool,
+    pub entrypoint_file: Option<String>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ArchiveValidation {
+    pub functions: Vec<FunctionEntrypointCheck>,
+    pub problems: Vec<String>,
+}
+
+const DEFAULT_ENTRYPOINTS: [&str; 2] = ["index.ts", "index.js"];
+
+/// Checks every top-level function directory in a `zip_local_source` archive
+/// has an entrypoint: the file named by a matching config's `entrypoint`, else
+/// index.ts/index.js. `_`-prefixed folders (e.g. `_shared`) are not functions.
+pub fn validate_function_archive(zip_path: &str, configs: &[FunctionConfig]) -> Result<ArchiveValidation, String> {
+    let file = std::fs::File::open(zip_path).map_err(|e| format!("Cannot open {}: {}", zip_path, e))?;
+    let archive = zip::ZipArchive::new(file).map_err(|e| format!("Not a valid zip archive: {}", e))?;
+
+    // Archives written on Windows use backslash separators
+    let entries: std::collections::BTreeSet<String> = archive
+        .file_names()
+        .map(|n| n.replace('\\', "/"))
+        .collect();
+
+    let functions: std::collections::BTreeSet<&str> = entries
+        .iter()
+        .filter_map(|n| n.split_once('/').map(|(dir, _)| dir))
+        .filter(|dir| !dir.is_empty() && !dir.starts_with('_') && !dir.starts_with('.'))
+        .collect();
+
+    let mut checks = vec![];
+    let mut problems = vec![];
+    if functions.is_empty() {
+        // Zipped from a single function's folder: the entrypoint sits at the root
+        let root_entry = DEFAULT_ENTRYPOINTS.iter().find(|f| entries.contains(**f));
+        if root_entry.is_none() {
+            problems.push("Archive contains no function directories.".to_string());
+        }
+        checks.push(FunctionEntrypointCheck {
+            function: ".".to_string(),
+            has_entrypoint: root_entry.is_some(),
+            entrypoint_file: root_entry.map(|f| f.to_string()),
+        });
+    }
+
+    for function in functions {
+        // Configured entrypoints are URLs/paths; only the file name is comparable
+        let candidates: Vec<String> = match configs.iter().find(|c| c.slug == function || c.name == function) {
+            Some(config) if !config.entrypoint.is_empty() => {
+                vec![config.entrypoint.rsplit('/').next().unwrap_or(&config.entrypoint).to_string()]
+            }
+            _ => DEFAULT_ENTRYPOINTS.iter().map(|f| f.to_string()).collect(),
+        };
+
+        let found = candidates
+            .iter()
+            .find(|file| entries.contains(&format!("{}/{}", function, file)))
+            .cloned();
+        if found.is_none() {
+            problems.push(format!("{}: missing entrypoint ({})", function, candidates.join(" or ")));
+        }
+        checks.push(FunctionEntrypointCheck {
+            function: function.to_string(),
+            has_entrypoint: found.is_some(),
+            entrypoint_file: found,
+        });
+    }
+
+    Ok(ArchiveValidation { functions: checks, problems })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn same_project_ignores_case_and_trailing_slashes() {
+        assert!(is_same_project("https://Abc.supabase.co/", "https://abc.supabase.co"));
+        assert!(is_same_project(" https://abc.supabase.co// ", "HTTPS://ABC.SUPABASE.CO"));
+        assert!(is_same_project("https://db.example.com/", "https://DB.example.com"));
+        assert!(!is_same_project("https://abc.supabase.co", "https://abd.supabase.co"));
+        assert!(!is_same_project("https://db.example.com", "https://db2.example.com"));
+        assert_eq!(normalize_project("https://Abc.supabase.co/"), "abc");
+        assert_eq!(normalize_project("https://DB.example.com/"), "https://db.example.com");
+    }
+
+    #[test]
+    fn same_project_is_refused_unless_allowed() {
+        let error = ensure_distinct_projects("https://abc.supabase.co/", "https://ABC.supabase.co", false).unwrap_err();
+        assert!(error.contains("(abc)"), "{}", error);
+        assert_eq!(ensure_distinct_projects("https://abc.supabase.co", "https://abc.supabase.co", true), Ok(true));
+        assert_eq!(ensure_distinct_projects("https://abc.supabase.co", "https://xyz.supabase.co", false), Ok(false));
+    }
+
+    #[test]
+    fn functions_root_requires_supabase_functions_layout() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("supabase/functions");
+        let hello = root.join("hello");
+        fs::create_dir_all(&hello).unwrap();
+        fs::write(root.join("import_map.json"), "{}").unwrap();
+        // Per-function config must not turn the function into a root
+        fs::write(hello.join("deno.json"), "{}").unwrap();
+
+        assert_eq!(functions_root_of(&hello), Some(root.clone()));
+        assert_eq!(functions_root_of(&root), None);
+    }
+
+    #[test]
+    fn shared_assets_outside_the_layout_are_not_a_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("app");
+        let func = project.join("worker");
+        fs::create_dir_all(&func).unwrap();
+        fs::write(project.join("deno.json"), "{}").unwrap();
+
+        assert_eq!(functions_root_of(&func), None);
+        assert_eq!(functions_root_of(&tmp.path().join("functions/worker")), None);
+    }
+
+    #[test]
+    fn concurrent_backups_write_distinct_archives() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (source, out_dir) = (tmp.path().join("hello"), tmp.path().join("backups"));
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("index.ts"), "export default () => new Response('hi')").unwrap();
+
+        let archives: Vec<String> = std::thread::scope(|scope| {
+            let runs: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| archive_source(&|_| {}, source.to_str().unwrap(), &out_dir, false).unwrap()))
+                .collect();
+            runs.into_iter().map(|run| run.join().unwrap()).collect()
+        });
+
+        let unique: std::collections::HashSet<_> = archives.iter().collect();
+        assert_eq!(unique.len(), archives.len(), "{:?}", archives);
+        for archive in &archives {
+            let mut zip = zip::ZipArchive::new(fs::File::open(archive).unwrap()).unwrap();
+            assert!(zip.by_name("index.ts").is_ok(), "{} is incomplete", archive);
+        }
+        assert_eq!(fs::read_dir(&out_dir).unwrap().count(), archives.len());
+    }
+
+    #[test]
+    fn interrupted_write_leaves_only_a_reported_partial() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (source, out_dir) = (tmp.path().join("hello"), tmp.path().join("backups"));
+        fs::create_dir_all(&source).unwrap();
+        fs::create_dir_all(&out_dir).unwrap();
+        fs::write(source.join("index.ts"), "export default () => new Response('hi')").unwrap();
+        // What a run killed mid-write (another pid) leaves behind: a truncated .partial, no .zip
+        let crashed = out_dir.join(format!("functions_source-1-{}-0.zip{}", std::process::id() + 1, PARTIAL_SUFFIX));
+        fs::write(&crashed, b"PK\x03\x04").unwrap();
+        // This process's own in-flight archive is not stale
+        fs::write(out_dir.join(format!("functions_source-2-{}-0.zip{}", std::process::id(), PARTIAL_SUFFIX)), b"").unwrap();
+
+        assert_eq!(stale_partial_archives(&out_dir), vec![crashed.clone()]);
+        let logs = std::sync::Mutex::new(vec![]);
+        let archive = archive_source(&|msg| logs.lock().unwrap().push(msg), source.to_str().unwrap(), &out_dir, false).unwrap();
+
+        let warnings: Vec<_> = logs.into_inner().unwrap().into_iter().filter(|m| m.starts_with("WARNING")).collect();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains(&crashed.display().to_string()), "{}", warnings[0]);
+        assert!(archive.ends_with(".zip"));
+        let zips = fs::read_dir(&out_dir).unwrap().filter(|e| e.as_ref().unwrap().path().extension().is_some_and(|x| x == "zip")).count();
+        assert_eq!(zips, 1, "only the finished run produced a zip");
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter, Window};
+
+use crate::db::{quote_ident, Psql};
+
+/// Roles the platform creates and manages; never created, altered or granted to
+const MANAGED_ROLES: [&str; 16] = [
+    "postgres",
+    "anon",
+    "authenticated",
+    "authenticator",
+    "service_role",
+    "dashboard_user",
+    "pgbouncer",
+    "supabase_admin",
+    "supabase_auth_admin",
+    "supabase_storage_admin",
+    "supabase_functions_admin",
+    "supabase_realtime_admin",
+    "supabase_replication_admin",
+    "supabase_read_only_user",
+    "pgsodium_keyholder",
+    "pgsodium_keymaker",
+];
+
+/// Schemas owned by the platform; their grants are set up on every project
+pub const MANAGED_SCHEMAS: &str = "'pg_catalog', 'information_schema', 'pg_toast', 'auth', 'storage', 'realtime', \
+     'extensions', 'graphql', 'graphql_public', 'pgbouncer', 'pgsodium', 'pgsodium_masks', 'vault', \
+     'supabase_functions', 'supabase_migrations', 'net', 'cron'";
+
+/// One replayed (or skipped) grant or ownership change
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct GrantStatus {
+    pub grantee: String,
+    pub statement: String,
+    pub status: String, // APPLIED, MANAGED, FAILED
+    pub error: Option<String>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RoleMigration {
+    pub roles_created: Vec<String>,
+    pub grants: Vec<GrantStatus>,
+}
+
+/// Whether `schema` is one of the platform-owned schemas
+pub fn is_managed_schema(schema: &str) -> bool {
+    MANAGED_SCHEMAS.split(',').any(|s| s.trim().trim_matches('\'') == schema)
+}
+
+fn is_managed(role: &str) -> bool {
+    MANAGED_ROLES.contains(&role) || role.starts_with("pg_")
+}
+
+/// "PUBLIC" stays a keyword; everything else is quoted
+fn grantee_sql(role: &str) -> String {
+    if role == "PUBLIC" {
+        role.to_string()
+    } else {
+        quote_ident(role)
+    }
+}
+
+/// Recreates the source's custom roles (as NOLOGIN; passwords can't be read)
+/// and replays table/function grants and table ownership on the destination.
+/// Anything involving a platform-managed role is reported as MANAGED.
+pub async fn migrate_roles_and_grants(
+    window: &Window,
+    app: &AppHandle,
+    source_db: &str,
+    dest_db: &str,
+) -> Result<RoleMigration, String> {
+    let psql = Psql::locate(app)?;
+
+    let source_roles: Vec<String> = psql
+        .query(source_db, "SELECT rolname FROM pg_roles ORDER BY rolname")
+        .await?
+        .into_iter()
+        .filter_map(|r| r.into_iter().next())
+        .filter(|r| !is_managed(r))
+        .collect();
+    let dest_roles: HashSet<String> = psql
+        .query(dest_db, "SELECT rolname FROM pg_roles")
+        .await?
+        .into_iter()
+        .filter_map(|r| r.into_iter().next())
+        .collect();
+
+    let mut roles_created = vec![];
+    for role in source_roles.iter().filter(|r| !dest_roles.contains(*r)) {
+        match psql.execute(dest_db, &[format!("CREATE ROLE {} NOLOGIN", quote_ident(role))]).await {
+            Ok(_) => {
+                window.emit("log", format!("  Created role {} (NOLOGIN; set a password to enable login)", role)).unwrap();
+                roles_created.push(role.clone());
+            }
+            Err(e) => window.emit("log", format!("  Could not create role {}: {}", role, e)).unwrap(),
+        }
+    }
+
+    // (grantee, statement) pairs, in replay order: ownership first, then grants
+    let mut statements: Vec<(String, String)> = vec![];
+    let owners = psql
+        .query(
+            source_db,
+            &format!(
+                "SELECT tableowner, schemaname, tablename FROM pg_tables WHERE schemaname NOT IN ({}) ORDER BY 2, 3",
+                MANAGED_SCHEMAS
+            ),
+        )
+        .await?;
+    for row in owners.into_iter().filter(|r| r.len() == 3) {
+        let statement = format!(
+            "ALTER TABLE {}.{} OWNER TO {}",
+            quote_ident(&row[1]),
+            quote_ident(&row[2]),
+            quote_ident(&row[0])
+        );
+        statements.push((row[0].clone(), statement));
+    }
+
+    let table_grants = psql
+        .query(
+            source_db,
+            &format!(
+                "SELECT grantee, table_schema, table_name, privilege_type FROM information_schema.role_table_grants \
+                 WHERE table_schema NOT IN ({}) AND grantor <> grantee ORDER BY 2, 3, 1, 4",
+                MANAGED_SCHEMAS
+            ),
+        )
+        .await?;
+    for row in table_grants.into_iter().filter(|r| r.len() == 4) {
+        let statement = format!(
+            "GRANT {} ON TABLE {}.{} TO {}",
+            row[3],
+            quote_ident(&row[1]),
+            quote_ident(&row[2]),
+            grantee_sql(&row[0])
+        );
+        statements.push((row[0].clone(), statement));
+    }
+
+    // regprocedure renders the full signature, already quoted where needed
+    let function_grants = psql
+        .query(
+            source_db,
+            &format!(
+                "SELECT CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE a.grantee::regrole::text END, \
+                 p.oid::regprocedure::text, a.privilege_type \
+                 FROM pg_proc p JOIN pg_namespace n ON n.oid = p.pronamespace, aclexplode(p.proacl) a \
+                 WHERE n.nspname NOT IN ({}) AND a.grantee <> p.proowner ORDER BY 2, 1",
+                MANAGED_SCHEMAS
+            ),
+        )
+        .await?;
+    for row in function_grants.into_iter().filter(|r| r.len() == 3) {
+        let statement = format!("GRANT {} ON FUNCTION {} TO {}", row[2], row[1], grantee_sql(&row[0]));
+        statements.push((row[0].clone(), statement));
+    }
+
+    let mut grants = vec![];
+    for (grantee, statement) in statements {
+        let mut status = GrantStatus {
+            grantee: grantee.clone(),
+            statement: statement.clone(),
+            status: "MANAGED".to_string(),
+            error: None,
+        };
+        if !is_managed(&grantee) {
+            match psql.execute(dest_db, &[statement]).await {
+                Ok(_) => status.status = "APPLIED".to_string(),
+                Err(e) => {
+                    status.status = "FAILED".to_string();
+                    status.error = Some(e);
+                }
+            }
+        }
+        grants.push(status);
+    }
+
+    let count = |s: &str| grants.iter().filter(|g| g.status == s).count();
+    window.emit("log", format!(
+        "Roles & grants: {} roles created, {} applied, {} skipped as managed, {} failed",
+        roles_created.len(),
+        count("APPLIED"),
+        count("MANAGED"),
+        count("FAILED")
+    )).unwrap();
+    Ok(RoleMigration { roles_created, grants })
+}
@@ -0,0 +1,115 @@
+use reqwest::Client;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Window};
+
+use crate::cancel::{self, CancelToken, PauseToken};
+
+/// Payload of the `endpoint_health` event
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct EndpointHealth {
+    pub source_ok: bool,
+    pub dest_ok: bool,
+}
+
+/// An endpoint is up when its Storage API answers at all; auth failures
+/// still prove the project is serving. 5xx and paused projects are down.
+async fn ping(client: &Client, project_url: &str, key: &str) -> bool {
+    match client
+        .get(format!("{}/storage/v1/bucket", project_url))
+        .header("Authorization", format!("Bearer {}", key))
+        .header("apikey", key)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+    {
+        Ok(response) => !response.status().is_server_error() && response.status().as_u16() != 540,
+        Err(_) => false,
+    }
+}
+
+/// One round of pings to both projects
+pub async fn probe(client: &Client, source: (&str, &str), dest: (&str, &str)) -> EndpointHealth {
+    let (source_ok, dest_ok) = tokio::join!(ping(client, source.0, source.1), ping(client, dest.0, dest.1));
+    EndpointHealth { source_ok, dest_ok }
+}
+
+/// Background loop pinging both projects during a migration. While either
+/// is down, new transfers are paused (unless the user already paused);
+/// they resume on recovery. An outage longer than `max_outage` stops the
+/// migration with an error instead of failing every remaining object.
+pub struct HealthMonitor {
+    task: tokio::task::JoinHandle<()>,
+    failure: Arc<Mutex<Option<String>>>,
+}
+
+impl HealthMonitor {
+    /// None when probing is disabled (`interval_secs` = 0)
+    #[allow(clippy::too_many_arguments)]
+    pub fn start(
+        window: &Window,
+        client: Client,
+        source: (String, String),
+        dest: (String, String),
+        interval_secs: u64,
+        max_outage_secs: u64,
+        token: CancelToken,
+        pause: PauseToken,
+    ) -> Option<Self> {
+        if interval_secs == 0 {
+            return None;
+        }
+        let failure = Arc::new(Mutex::new(None));
+        let (window, shared) = (window.clone(), failure.clone());
+        let task = tokio::spawn(async move {
+            let mut down_since: Option<Instant> = None;
+            let mut auto_paused = false;
+            loop {
+                tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+                let health = probe(&client, (&source.0, &source.1), (&dest.0, &dest.1)).await;
+                let _ = window.emit("endpoint_health", health.clone());
+
+                if health.source_ok && health.dest_ok {
+                    down_since = None;
+                    if auto_paused {
+                        window.emit("log", "Endpoints healthy again; resuming transfers.").unwrap();
+                        cancel::set_paused(&window, "migration", false);
+                        auto_paused = false;
+                    }
+                    continue;
+                }
+
+                let down = match (health.source_ok, health.dest_ok) {
+                    (false, false) => "Source and destination",
+                    (false, true) => "Source",
+                    _ => "Destination",
+                };
+                let since = *down_since.get_or_insert_with(Instant::now);
+                if !auto_paused && !pause.is_paused() {
+                    window.emit("log", format!("WARNING: {} unreachable; pausing new transfers until it recovers.", down)).unwrap();
+                    auto_paused = cancel::set_paused(&window, "migration", true);
+                }
+                if since.elapsed() >= Duration::from_secs(max_outage_secs) {
+                    let msg = format!("{} unreachable for over {}s; migration stopped.", down, max_outage_secs);
+                    window.emit("log", &msg).unwrap();
+                    *shared.lock().unwrap() = Some(msg);
+                    token.cancel();
+                    break;
+                }
+            }
+        });
+        Some(Self { task, failure })
+    }
+
+    /// Stops probing; returns the outage error if the monitor ended the migration
+    pub fn stop(self) -> Option<String> {
+        self.failure.lock().unwrap().take()
+    }
+}
+
+/// Early returns from the migration must not leave the loop running
+impl Drop for HealthMonitor {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,152 @@
+use reqwest::Client;
+use std::time::Duration;
+use tauri::{Emitter, Window};
+
+/// User-configured action run at a migration stage boundary
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct HookSpec {
+    pub stage: String, // "STORAGE", ... or "*" for every stage
+    pub phase: String, // "pre", "post"
+    pub command: Option<String>,
+    pub webhook_url: Option<String>,
+    #[serde(default = "default_hook_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_hook_timeout() -> u64 {
+    300
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub abort_on_failure: bool,
+    pub steps: Vec<HookSpec>,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            abort_on_failure: true,
+            steps: vec![],
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone)]
+struct StageHookEvent {
+    stage: String,
+    phase: String,
+}
+
+/// Emits `stage_hook` and runs every matching hook in order, waiting for each.
+/// A failing hook aborts the migration unless `abort_on_failure` is off.
+pub async fn run_stage_hooks(
+    window: &Window,
+    client: &Client,
+    config: &HooksConfig,
+    stage: &str,
+    phase: &str,
+) -> Result<(), String> {
+    window
+        .emit(
+            "stage_hook",
+            StageHookEvent {
+                stage: stage.to_string(),
+                phase: phase.to_string(),
+            },
+        )
+        .unwrap();
+
+    let matching = config
+        .steps
+        .iter()
+        .filter(|h| (h.stage == "*" || h.stage.eq_ignore_ascii_case(stage)) && h.phase == phase);
+
+    for hook in matching {
+        let timeout = Duration::from_secs(hook.timeout_secs);
+        let mut result = Ok(());
+
+        if let Some(command) = &hook.command {
+            window.emit("log", format!("HOOK [{} {}]: $ {}", stage, phase, command)).unwrap();
+            result = run_command(window, command, stage, phase, timeout).await;
+        }
+        if let (Ok(()), Some(url)) = (&result, &hook.webhook_url) {
+            window.emit("log", format!("HOOK [{} {}]: POST {}", stage, phase, url)).unwrap();
+            result = call_webhook(window, client, url, stage, phase, timeout).await;
+        }
+
+        if let Err(e) = result {
+            if config.abort_on_failure {
+                return Err(format!("Hook failed at {} {}: {}", stage, phase, e));
+            }
+            window
+                .emit("log", format!("HOOK FAILED (continuing): {}", e))
+                .unwrap();
+        }
+    }
+    Ok(())
+}
+
+async fn run_command(window: &Window, command: &str, stage: &str, phase: &str, timeout: Duration) -> Result<(), String> {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut c = tokio::process::Command::new("cmd");
+        c.arg("/C").arg(command);
+        c
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut c = tokio::process::Command::new("sh");
+        c.arg("-c").arg(command);
+        c
+    };
+    cmd.env("DEVPULSE_STAGE", stage)
+        .env("DEVPULSE_PHASE", phase)
+        .kill_on_drop(true);
+
+    let output = tokio::time::timeout(timeout, cmd.output())
+        .await
+        .map_err(|_| format!("timed out after {}s", timeout.as_secs()))?
+        .map_err(|e| format!("could not start: {}", e))?;
+
+    for line in String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .chain(String::from_utf8_lossy(&output.stderr).lines())
+    {
+        window.emit("log", format!("  | {}", line)).unwrap();
+    }
+
+    if !output.status.success() {
+        return Err(format!("exited with {}", output.status));
+    }
+    Ok(())
+}
+
+async fn call_webhook(
+    window: &Window,
+    client: &Client,
+    url: &str,
+    stage: &str,
+    phase: &str,
+    timeout: Duration,
+) -> Result<(), String> {
+    let res = client
+        .post(url)
+        .timeout(timeout)
+        .json(&serde_json::json!({ "stage": stage, "phase": phase }))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = res.status();
+    let body = res.text().await.unwrap_or_default();
+    if !body.trim().is_empty() {
+        window.emit("log", format!("  | {}", body.trim())).unwrap();
+    }
+
+    if !status.is_success() {
+        return Err(format!("webhook returned {}", status));
+    }
+    Ok(())
+}
@@ -0,0 +1,526 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Client;
+use std::net::{IpAddr, SocketAddr};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Window};
+
+use crate::deps::{self, PulseConfig};
+
+const USER_AGENT: &str = "DevPulse-Migrator/1.0";
+
+/// Redirects followed per request (GitHub assets take one hop to their CDN)
+const MAX_REDIRECTS: usize = 5;
+
+/// Hosts manifests and driver packs may be served from, subdomains included.
+/// The configured release service host and `trusted_hosts` are added at check
+/// time; other Supabase projects are not trusted just for being on supabase.co.
+const TRUSTED_DOWNLOAD_HOSTS: [&str; 2] = ["github.com", "githubusercontent.com"];
+
+/// Resolves hostnames through a DNS-over-HTTPS JSON endpoint instead of the
+/// system resolver. Used on networks where `*.supabase.co` lookups are
+/// blocked or hijacked but HTTPS itself is fine.
+struct DohResolver {
+    endpoint: String,
+    // Plain client: the endpoint is an IP literal, so it never needs DNS itself
+    client: Client,
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let endpoint = self.endpoint.clone();
+        let client = self.client.clone();
+        let host = name.as_str().to_string();
+
+        Box::pin(async move {
+            let mut addrs: Vec<SocketAddr> = vec![];
+            for record_type in ["A", "AAAA"] {
+                let resp = client
+                    .get(&endpoint)
+                    .query(&[("name", host.as_str()), ("type", record_type)])
+                    .header("Accept", "application/dns-json")
+                    .send()
+                    .await?;
+                let body: serde_json::Value = resp.json().await?;
+
+                if let Some(answers) = body["Answer"].as_array() {
+                    addrs.extend(
+                        answers
+                            .iter()
+                            .filter_map(|a| a["data"].as_str())
+                            .filter_map(|d| d.parse::<IpAddr>().ok())
+                            .map(|ip| SocketAddr::new(ip, 0)),
+                    );
+                }
+            }
+
+            if addrs.is_empty() {
+                return Err(format!("DoH lookup returned no addresses for {}", host).into());
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// Builds the HTTP client shared by every module, honouring network config
+pub fn build_client(config: &PulseConfig) -> Result<Client, String> {
+    // Idle-read rather than total timeout so large objects aren't cut off mid-transfer
+    let timeout = Duration::from_secs(config.performance.request_timeout_secs);
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(timeout)
+        .read_timeout(timeout)
+        .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS));
+
+    if let Some(path) = &config.ca_cert_path {
+        let pem = std::fs::read(path).map_err(|e| format!("Cannot read CA certificate {}: {}", path, e))?;
+        let cert = reqwest::Certificate::from_pem(&pem)
+            .map_err(|e| format!("Invalid CA certificate {}: {}", path, e))?;
+        builder = builder.add_root_certificate(cert);
+    }
+    if config.accept_invalid_certs {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    if config.doh_enabled {
+        let resolver = DohResolver {
+            endpoint: config.doh_endpoint.clone(),
+            client: Client::builder()
+                .user_agent(USER_AGENT)
+                .build()
+                .map_err(|e| e.to_string())?,
+        };
+        builder = builder.dns_resolver(Arc::new(resolver));
+    }
+
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// Rejects a download whose final URL (after redirects) left the trusted hosts,
+/// so a tampered manifest can't point installs at an arbitrary server
+pub fn check_download_host(url: &reqwest::Url, config: &PulseConfig) -> Result<(), String> {
+    let host = url.host_str().unwrap_or_default().to_lowercase();
+    let release_host = reqwest::Url::parse(&config.supabase_url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_lowercase()));
+    let trusted = TRUSTED_DOWNLOAD_HOSTS
+        .iter()
+        .map(|h| h.to_string())
+        .chain(release_host)
+        .chain(config.trusted_hosts.iter().map(|h| h.trim().to_lowercase()))
+        .any(|t| !t.is_empty() && (host == t || host.ends_with(&format!(".{}", t))));
+    if trusted {
+        Ok(())
+    } else {
+        Err(format!(
+            "Refusing download from untrusted host {} ({}). Add it to trusted_hosts if it is a known mirror.",
+            host, url
+        ))
+    }
+}
+
+/// Latest rate-limit headers seen from one Supabase service of one project
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct RateLimitState {
+    pub host: String,    // project host, e.g. abc.supabase.co
+    pub service: String, // storage, auth, rest, ...
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    pub reset: Option<String>,
+    pub retry_after_secs: Option<u64>,
+    pub headers: BTreeMap<String, String>, // x-ratelimit-*, retry-after and sb-* as received
+    pub observed_at: u64,                  // unix seconds
+}
+
+impl RateLimitState {
+    /// Under 10% of the window left, or the server asked us to back off
+    pub fn near_limit(&self) -> bool {
+        let low = match (self.remaining, self.limit) {
+            (Some(remaining), Some(limit)) if limit > 0 => remaining * 10 < limit,
+            _ => false,
+        };
+        low || self.retry_after_secs.is_some()
+    }
+}
+
+/// Rate-limit state per (host, service), updated by every observed response.
+/// Managed as app state; clones share the same map.
+#[derive(Clone, Default)]
+pub struct RateLimits(Arc<Mutex<BTreeMap<(String, String), RateLimitState>>>);
+
+/// The app's shared rate-limit state, for mirrors and auth calls to record into
+pub fn rate_limits(app: &AppHandle) -> RateLimits {
+    app.state::<RateLimits>().inner().clone()
+}
+
+/// "https://x.supabase.co/..." -> "x.supabase.co" (with the port, when one is given)
+fn host_of(url: &reqwest::Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    }
+}
+
+/// "https://x.supabase.co/storage/v1/object/..." -> "storage"
+fn service_of(url: &reqwest::Url) -> String {
+    url.path_segments()
+        .and_then(|mut s| s.next())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+impl RateLimits {
+    /// Records rate-limit headers from a response. Chain after `send().await`
+    /// with `.inspect(|r| limits.observe(r))`.
+    pub fn observe(&self, response: &reqwest::Response) {
+        let headers: BTreeMap<String, String> = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| {
+                let name = name.as_str();
+                name.starts_with("x-ratelimit-") || name.starts_with("ratelimit-") || name == "retry-after" || name.starts_with("sb-")
+            })
+            .filter_map(|(name, value)| Some((name.as_str().to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let is_limit_header = |h: &String| h.contains("ratelimit") || h == "retry-after";
+        if !headers.keys().any(is_limit_header) {
+            return;
+        }
+
+        let number = |names: &[&str]| names.iter().find_map(|n| headers.get(*n)).and_then(|v| v.trim().parse().ok());
+        let (host, service) = (host_of(response.url()), service_of(response.url()));
+        let state = RateLimitState {
+            host: host.clone(),
+            service: service.clone(),
+            limit: number(&["x-ratelimit-limit", "ratelimit-limit"]),
+            remaining: number(&["x-ratelimit-remaining", "ratelimit-remaining"]),
+            reset: ["x-ratelimit-reset", "ratelimit-reset"].iter().find_map(|n| headers.get(*n)).cloned(),
+            retry_after_secs: number(&["retry-after"]),
+            observed_at: crate::report::now_secs(),
+            headers,
+        };
+        self.0.lock().unwrap().insert((host, service), state);
+    }
+
+    /// Latest observed state per project and service
+    pub fn snapshot(&self) -> Vec<RateLimitState> {
+        self.0.lock().unwrap().values().cloned().collect()
+    }
+
+    /// State of `service` on the project at `project_url`, when it is close to its limit
+    pub fn near_limit(&self, project_url: &str, service: &str) -> Option<RateLimitState> {
+        let host = host_of(&reqwest::Url::parse(project_url).ok()?);
+        let states = self.0.lock().unwrap();
+        states.get(&(host, service.to_string())).filter(|s| s.near_limit()).cloned()
+    }
+}
+
+/// Shared client for commands, built from the persisted config
+pub fn client_for_app(app: &AppHandle) -> Client {
+    let config = deps::load_config(app);
+    if let Some(path) = &config.ca_cert_path {
+        let _ = app.emit("log", format!("Network: trusting custom CA from {}", path));
+    }
+    if config.accept_invalid_certs {
+        let _ = app.emit(
+            "log",
+            "WARNING: TLS certificate verification is DISABLED (accept_invalid_certs). Connections can be intercepted.",
+        );
+    }
+    if config.doh_enabled {
+        let _ = app.emit(
+            "log",
+            format!("Network: resolving hostnames via DoH ({})", config.doh_endpoint),
+        );
+    }
+
+    build_client(&config).unwrap_or_else(|e| {
+        let _ = app.emit("log", format!("Network config rejected ({}). Using defaults.", e));
+        Client::new()
+    })
+}
+
+/// `SupabaseError::code` of responses from a paused project
+pub const PROJECT_PAUSED: &str = "PROJECT_PAUSED";
+const PROJECT_PAUSED_MESSAGE: &str = "This project appears paused; resume it in the Supabase dashboard";
+
+/// Paused (inactive free-tier) projects answer 540; projects being paused,
+/// restored or under maintenance answer 502/503 with a telling body
+fn is_project_paused(status: u16, body: &str) -> bool {
+    let body = body.to_lowercase();
+    status == 540
+        || (matches!(status, 502 | 503)
+            && ["project is paused", "project paused", "paused project", "being restored", "under maintenance"]
+                .iter()
+                .any(|p| body.contains(p)))
+}
+
+/// Error body returned by Supabase services (storage, auth, management).
+/// Shapes differ per service, so fields are collected from the known variants.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SupabaseError {
+    pub status: u16,
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl std::fmt::Display for SupabaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.code {
+            Some(code) if code != &self.message => {
+                write!(f, "{} ({}, HTTP {})", self.message, code, self.status)
+            }
+            _ => write!(f, "{} (HTTP {})", self.message, self.status),
+        }
+    }
+}
+
+impl SupabaseError {
+    pub fn parse(status: u16, body: &str) -> Self {
+        if is_project_paused(status, body) {
+            return Self {
+                status,
+                code: Some(PROJECT_PAUSED.to_string()),
+                message: PROJECT_PAUSED_MESSAGE.to_string(),
+            };
+        }
+        let json: Option<serde_json::Value> = serde_json::from_str(body).ok();
+        let field = |keys: &[&str]| -> Option<String> {
+            let json = json.as_ref()?;
+            keys.iter().find_map(|k| match &json[*k] {
+                serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+        };
+
+        let message = field(&["message", "msg", "error_description", "error"])
+            .unwrap_or_else(|| {
+                let text = body.trim();
+                if text.is_empty() {
+                    "No error details returned".to_string()
+                } else {
+                    text.to_string()
+                }
+            });
+
+        Self {
+            status,
+            code: field(&["error_code", "error", "code"]),
+            message,
+        }
+    }
+
+    pub fn is_project_paused(&self) -> bool {
+        self.code.as_deref() == Some(PROJECT_PAUSED)
+    }
+
+    /// An upload over the project or bucket size limit. Storage may answer
+    /// HTTP 400 with `"statusCode": "413"` in the body instead of a plain 413.
+    pub fn is_payload_too_large(&self) -> bool {
+        self.status == 413
+            || self.code.as_deref() == Some("Payload too large")
+            || self.message.contains("maximum allowed size")
+    }
+
+    /// The resource already exists. Storage may answer HTTP 400 with
+    /// `"statusCode": "409"` and `"error": "Duplicate"` in the body.
+    pub fn is_conflict(&self) -> bool {
+        self.status == 409 || self.code.as_deref() == Some("Duplicate") || self.message.contains("already exists")
+    }
+
+    /// Rate limited or failed on the server side: worth another attempt
+    pub fn is_transient(&self) -> bool {
+        self.status == 429 || self.status >= 500
+    }
+
+    /// Consumes a failed response and parses its body
+    pub async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        Self::parse(status, &body)
+    }
+}
+
+/// Attempts per request for retried operations
+pub const RETRY_ATTEMPTS: u32 = 3;
+
+/// Runs `op` up to `attempts` times with capped exponential backoff
+/// (0.5s, 1s, 2s ... max 8s). Returns the last error if all attempts fail.
+pub async fn with_backoff<T, F, Fut>(attempts: u32, op: F) -> Result<T, String>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, String>>,
+{
+    with_backoff_if(attempts, op, |_| true).await
+}
+
+/// `with_backoff` that gives up at once on errors `retry` rejects
+pub async fn with_backoff_if<T, E, F, Fut>(attempts: u32, mut op: F, retry: impl Fn(&E) -> bool) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+{
+    let mut delay = Duration::from_millis(500);
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Ok(v) => return Ok(v),
+            Err(e) if attempt >= attempts || !retry(&e) => return Err(e),
+            Err(_) => {
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(Duration::from_secs(8));
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Terminal states reported by asynchronous Management API operations
+const OPERATION_DONE: [&str; 4] = ["COMPLETED", "SUCCEEDED", "SUCCESS", "ACTIVE_HEALTHY"];
+const OPERATION_FAILED: [&str; 4] = ["FAILED", "ERROR", "CANCELLED", "INIT_FAILED"];
+
+/// Polls `{url}/{job_id}` with capped exponential backoff (1s, 2s ... max 16s)
+/// until the operation completes, fails or `timeout` elapses. Returns the final status.
+pub async fn poll_operation(
+    window: &Window,
+    client: &Client,
+    url: &str,
+    key: &str,
+    job_id: &str,
+    timeout: Duration,
+) -> Result<String, String> {
+    let status_url = format!("{}/{}", url.trim_end_matches('/'), job_id);
+    let started = std::time::Instant::now();
+    let mut delay = Duration::from_secs(1);
+
+    loop {
+        let response = client
+            .get(&status_url)
+            .header("Authorization", format!("Bearer {}", key))
+            .send()
+            .await
+            .map_err(|e| format!("Operation poll failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Operation {} poll failed: {}", job_id, SupabaseError::from_response(response).await));
+        }
+        let body: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+        let status = body["status"].as_str().unwrap_or("UNKNOWN").to_uppercase();
+
+        let elapsed = started.elapsed();
+        window
+            .emit("log", format!("  Operation {}: {} ({:.1}s)", job_id, status, elapsed.as_secs_f64()))
+            .unwrap();
+
+        if OPERATION_DONE.contains(&status.as_str()) {
+            return Ok(status);
+        }
+        if OPERATION_FAILED.contains(&status.as_str()) {
+            let detail = body["error"].as_str().or(body["message"].as_str()).unwrap_or("no details");
+            return Err(format!("Operation {} ended with {}: {}", job_id, status, detail));
+        }
+        if elapsed + delay > timeout {
+            return Err(format!("Operation {} still {} after {}s", job_id, status, timeout.as_secs()));
+        }
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(Duration::from_secs(16));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockServer, Reply};
+
+    fn config_for(release_url: &str) -> PulseConfig {
+        PulseConfig {
+            supabase_url: release_url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_the_configured_supabase_project_is_trusted() {
+        let config = config_for("https://releases.supabase.co");
+        let ok = |u: &str| check_download_host(&reqwest::Url::parse(u).unwrap(), &config).is_ok();
+
+        assert!(ok("https://releases.supabase.co/storage/v1/object/public/packs/pg.zip"));
+        assert!(ok("https://objects.githubusercontent.com/pg.zip"));
+        assert!(!ok("https://attacker.supabase.co/storage/v1/object/public/packs/pg.zip"));
+        assert!(!ok("https://evil.supabase.in/pg.zip"));
+    }
+
+    #[tokio::test]
+    async fn redirect_to_untrusted_host_is_rejected() {
+        let mirror = MockServer::start(|_| Reply::bytes(200, b"pack".to_vec())).await;
+        // Same machine under another name: "localhost" is not the release host
+        let elsewhere = mirror.url.replace("127.0.0.1", "localhost");
+        let release = MockServer::start(move |_| Reply::status(302).with_header("Location", &format!("{}/pg.zip", elsewhere))).await;
+
+        let config = config_for(&release.url);
+        let client = build_client(&config).unwrap();
+        let response = client.get(format!("{}/pg.zip", release.url)).send().await.unwrap();
+
+        assert_eq!(response.url().host_str(), Some("localhost"));
+        assert!(check_download_host(response.url(), &config).is_err());
+    }
+
+    #[tokio::test]
+    async fn redirect_within_the_release_host_is_accepted() {
+        let mirror = MockServer::start(|_| Reply::bytes(200, b"pack".to_vec())).await;
+        let target = mirror.url.clone();
+        let release = MockServer::start(move |_| Reply::status(302).with_header("Location", &format!("{}/pg.zip", target))).await;
+
+        let config = config_for(&release.url);
+        let response = build_client(&config).unwrap().get(format!("{}/pg.zip", release.url)).send().await.unwrap();
+
+        assert!(check_download_host(response.url(), &config).is_ok());
+    }
+
+    #[tokio::test]
+    async fn rate_limits_are_kept_per_project_and_service() {
+        let limited = |remaining: &'static str| {
+            move |_: &crate::test_support::Request| {
+                Reply::status(200)
+                    .with_header("x-ratelimit-limit", "100")
+                    .with_header("x-ratelimit-remaining", remaining)
+            }
+        };
+        let (busy, idle) = (MockServer::start(limited("3")).await, MockServer::start(limited("90")).await);
+        let (limits, client) = (RateLimits::default(), Client::new());
+        for url in [
+            format!("{}/storage/v1/bucket", busy.url),
+            format!("{}/storage/v1/bucket", idle.url),
+            format!("{}/auth/v1/admin/users", idle.url),
+        ] {
+            limits.observe(&client.get(url).send().await.unwrap());
+        }
+
+        assert_eq!(limits.snapshot().len(), 3);
+        assert_eq!(limits.near_limit(&busy.url, "storage").and_then(|s| s.remaining), Some(3));
+        assert!(limits.near_limit(&idle.url, "storage").is_none());
+        assert!(limits.near_limit(&busy.url, "auth").is_none());
+        // Separate states share nothing
+        assert!(RateLimits::default().snapshot().is_empty());
+    }
+
+    #[test]
+    fn paused_projects_are_recognized_by_status_and_body() {
+        let paused = SupabaseError::parse(540, "");
+        assert!(paused.is_project_paused());
+        assert_eq!(paused.message, PROJECT_PAUSED_MESSAGE);
+
+        let paused = SupabaseError::parse(503, "<html>This project is paused. Restore it from the dashboard.</html>");
+        assert!(paused.is_project_paused());
+        assert_eq!(paused.status, 503);
+
+        let overloaded = SupabaseError::parse(503, r#"{"message": "Service Unavailable"}"#);
+        assert!(!overloaded.is_project_paused());
+        assert_eq!(overloaded.message, "Service Unavailable");
+        assert_eq!(overloaded.to_string(), "Service Unavailable (HTTP 503)");
+    }
+}
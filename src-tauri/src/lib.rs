@@ -1,21 +1,79 @@
-use tauri::{Emitter, Window};
+use futures::StreamExt;
+use tauri::{Emitter, Manager, Window};
 
 mod auth;
+mod backup;
+mod benchmark;
+mod bucket_index;
+mod cancel;
+mod checkpoint;
+mod comments;
+mod content_types;
+mod cron;
+mod db;
+mod dbsync;
 mod deps;
+mod diagnostics;
+mod disk;
+mod extensions;
+mod export;
 mod storage;
 mod functions;
+mod health;
+mod grants;
+mod hooks;
+mod http;
+mod limits;
+mod logs;
+mod manifest_schema;
+mod monitor;
+mod ownership;
 mod paths;
+mod plan;
+mod policies;
+mod profiles;
+mod realtime;
+mod reconcile;
+mod region;
+mod report;
+mod resume;
+mod s3;
+mod scaffold;
+mod schedules;
+mod schema_diff;
+mod secrets;
+mod sequences;
+mod session_audit;
+mod snapshot;
+mod tasks;
 mod telemetry;
+#[cfg(test)]
+mod test_support;
 
 #[tauri::command]
-async fn verify_connection(window: Window, url: String, key: String) -> Result<String, String> {
+async fn verify_connection(window: Window, url: String, key: String) -> Result<auth::ConnectionCapabilities, String> {
     window
         .emit("log", format!("Connecting to project: {}", url))
         .unwrap();
-    match auth::validate_service_key(&url, &key).await {
+    let (client, limits) = (http::client_for_app(window.app_handle()), http::rate_limits(window.app_handle()));
+    match auth::validate_service_key(&client, &limits, &url, &key).await {
         Ok(msg) => {
             window.emit("log", &msg).unwrap();
-            Ok(msg)
+            let caps = auth::probe_capabilities(&client, &limits, &url, &key).await;
+            window
+                .emit(
+                    "log",
+                    format!(
+                        "Capabilities [role: {}]: storage read={} write={}, auth admin={}, management API={}",
+                        caps.role.as_deref().unwrap_or("unknown"),
+                        caps.can_read_storage,
+                        caps.can_write_storage,
+                        caps.can_admin_auth,
+                        caps.management_api_available
+                    ),
+                )
+                .unwrap();
+            Ok(caps)
         }
         Err(e) => {
             window
@@ -27,55 +85,1074 @@ async fn verify_connection(window: Window, url: String, key: String) -> Result<S
 }
 
 #[tauri::command]
-async fn backup_edge_config(window: Window, url: String, key: String) -> Result<String, String> {
-    match functions::backup_function_config(&window, &url, &key).await {
-        Ok(configs) => Ok(format!("Secured {} function configs.", configs.len())),
-        Err(e) => Err(format!("Edge Config Backup Failed: {}", e))
-    }
+async fn import_users(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+    conflict_policy: auth::ConflictPolicy,
+) -> Result<auth::ImportSummary, String> {
+    let client = http::client_for_app(window.app_handle());
+    auth::import_users(&window, &client, &source_url, &source_key, &dest_url, &dest_key, conflict_policy).await
+}
+
+/// Readiness grid of what each project's key (and optional database URL) allows
+#[tauri::command]
+async fn capability_matrix(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+    source_db_url: Option<String>,
+    dest_db_url: Option<String>,
+) -> Result<Vec<auth::CapabilityRow>, String> {
+    let app = window.app_handle();
+    let (client, limits) = (http::client_for_app(app), http::rate_limits(app));
+    let db_probe = |url: Option<String>| async move {
+        match url {
+            Some(url) => db::can_connect(app, &url).await,
+            None => false,
+        }
+    };
+    let (source, dest, source_db, dest_db) = futures::join!(
+        auth::probe_capabilities(&client, &limits, &source_url, &source_key),
+        auth::probe_capabilities(&client, &limits, &dest_url, &dest_key),
+        db_probe(source_db_url),
+        db_probe(dest_db_url),
+    );
+
+    let matrix = auth::capability_matrix(&source, &dest, (source_db, dest_db));
+    for row in &matrix {
+        window.emit("log", format!("  {:<14} source={} dest={}", row.feature, row.source, row.dest)).unwrap();
+    }
+    Ok(matrix)
+}
+
+#[tauri::command]
+async fn benchmark_transfer(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+    sample_bytes: u64,
+) -> Result<benchmark::TransferBenchmark, String> {
+    let performance = deps::load_config(window.app_handle()).performance;
+    let mirror = storage::StorageMirror::new(
+        http::client_for_app(window.app_handle()),
+        &source_url, &source_key, &dest_url, &dest_key
+    ).with_performance(performance)
+    .with_rate_limits(http::rate_limits(window.app_handle()));
+    benchmark::benchmark_transfer(&window, &mirror, sample_bytes).await
+}
+
+/// Destination objects whose content-type disagrees with their extension or magic bytes
+#[tauri::command]
+async fn audit_content_types(
+    window: Window,
+    dest_url: String,
+    dest_key: String,
+    bucket_id: String,
+) -> Result<Vec<content_types::ContentTypeFinding>, String> {
+    let config = deps::load_config(window.app_handle());
+    let mirror = storage::StorageMirror::for_destination(http::client_for_app(window.app_handle()), &dest_url, &dest_key)
+        .with_performance(config.performance)
+        .with_s3(&config.s3)
+        .with_rate_limits(http::rate_limits(window.app_handle()));
+    content_types::audit_content_types(&window, &mirror, &bucket_id).await
+}
+
+/// Re-sets the content-type of objects flagged by `audit_content_types`
+#[tauri::command]
+async fn fix_content_types(
+    window: Window,
+    dest_url: String,
+    dest_key: String,
+    bucket_id: String,
+    findings: Vec<content_types::ContentTypeFinding>,
+) -> Result<Vec<content_types::ContentTypeFix>, String> {
+    let config = deps::load_config(window.app_handle());
+    let mirror = storage::StorageMirror::for_destination(http::client_for_app(window.app_handle()), &dest_url, &dest_key)
+        .with_performance(config.performance)
+        .with_s3(&config.s3)
+        .with_rate_limits(http::rate_limits(window.app_handle()));
+    Ok(content_types::fix_content_types(&window, &mirror, &bucket_id, &findings).await)
+}
+
+/// Signed upload URL for one destination object, for uploading from the client side
+#[tauri::command]
+async fn create_signed_upload_url(
+    window: Window,
+    dest_url: String,
+    dest_key: String,
+    bucket_id: String,
+    object_name: String,
+    upsert: bool,
+) -> Result<String, String> {
+    let mirror = storage::StorageMirror::for_destination(http::client_for_app(window.app_handle()), &dest_url, &dest_key).with_rate_limits(http::rate_limits(window.app_handle()));
+    mirror
+        .create_signed_upload_url(&bucket_id, &object_name, upsert)
+        .await
+        .map_err(|e| format!("Could not sign upload: {}", e))
+}
+
+#[tauri::command]
+async fn dest_roundtrip_test(
+    window: Window,
+    dest_url: String,
+    dest_key: String,
+) -> Result<benchmark::RoundtripResult, String> {
+    let mirror = storage::StorageMirror::for_destination(http::client_for_app(window.app_handle()), &dest_url, &dest_key).with_rate_limits(http::rate_limits(window.app_handle()));
+    Ok(benchmark::dest_roundtrip(&window, &mirror).await)
+}
+
+/// End-to-end migrate -> verify -> cleanup of a one-object fixture, with per-phase timings
+#[tauri::command]
+async fn smoke_test(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+) -> Result<benchmark::SmokeTestResult, String> {
+    let client = http::client_for_app(window.app_handle());
+    let limits = http::rate_limits(window.app_handle());
+    let source = storage::StorageMirror::for_destination(client.clone(), &source_url, &source_key).with_rate_limits(limits.clone());
+    let dest = storage::StorageMirror::for_destination(client.clone(), &dest_url, &dest_key).with_rate_limits(limits.clone());
+    let mirror = storage::StorageMirror::new(client, &source_url, &source_key, &dest_url, &dest_key).with_rate_limits(limits);
+    Ok(benchmark::smoke_test(&window, &source, &dest, &mirror).await)
+}
+
+/// Objects per delete request when wiping
+const WIPE_BATCH_SIZE: usize = 100;
+
+#[tauri::command]
+async fn wipe_destination_storage(
+    window: Window,
+    dest_url: String,
+    dest_key: String,
+    confirm_token: String,
+    delete_buckets: bool,
+) -> Result<String, String> {
+    // The caller must type the destination project ref to prove intent
+    let project_ref = functions::extract_project_ref(&dest_url)?;
+    if confirm_token.trim().is_empty() || confirm_token.trim() != project_ref {
+        return Err(format!(
+            "Refusing to wipe: confirmation token must match project ref '{}'.",
+            project_ref
+        ));
+    }
+
+    window.emit("log", format!("!!! WIPING DESTINATION STORAGE [{}] !!!", project_ref)).unwrap();
+    telemetry::track_event(&window, telemetry::TelemetryEvent::new(
+        "DESTINATION_WIPE_WARNING",
+        serde_json::json!({ "destination": dest_url, "delete_buckets": delete_buckets })
+    ));
+
+    let mirror = storage::StorageMirror::for_destination(
+        http::client_for_app(window.app_handle()), &dest_url, &dest_key
+    ).with_rate_limits(http::rate_limits(window.app_handle()));
+    let buckets = mirror.list_dest_buckets().await?;
+
+    // Content-hash skips must not trust indexes of objects about to disappear
+    match bucket_index::invalidate_project(window.app_handle(), &dest_url) {
+        Ok(0) => {}
+        Ok(n) => window.emit("log", format!("  Dropped {} destination bucket indexes", n)).unwrap(),
+        Err(e) => window.emit("log", format!("  WARNING: Could not drop destination bucket indexes: {}", e)).unwrap(),
+    }
+
+    let mut deleted_objects = 0;
+    let mut deleted_buckets = 0;
+    for bucket in &buckets {
+        let names: Vec<String> = mirror
+            .list_dest_objects(&bucket.id)
+            .await?
+            .into_iter()
+            .map(|o| o.name)
+            .collect();
+
+        for batch in names.chunks(WIPE_BATCH_SIZE) {
+            mirror.delete_dest_objects(&bucket.id, batch).await?;
+            deleted_objects += batch.len();
+        }
+        window.emit("log", format!("  Wiped {} objects from {}", names.len(), bucket.name)).unwrap();
+
+        if delete_buckets {
+            mirror.delete_dest_bucket(&bucket.id).await?;
+            deleted_buckets += 1;
+            window.emit("log", format!("  Deleted bucket {}", bucket.name)).unwrap();
+        }
+    }
+
+    telemetry::track_event(&window, telemetry::TelemetryEvent::new(
+        "DESTINATION_WIPED",
+        serde_json::json!({ "objects_deleted": deleted_objects, "buckets_deleted": deleted_buckets })
+    ));
+    Ok(format!("Deleted {} objects and {} buckets", deleted_objects, deleted_buckets))
+}
+
+#[tauri::command]
+async fn retry_failed(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+    failed: Vec<report::FailedObject>,
+) -> Result<report::MigrationReport, String> {
+    let app = window.app_handle();
+    let config = deps::load_config(app);
+    let mirror = storage::StorageMirror::new(
+        http::client_for_app(app),
+        &source_url, &source_key, &dest_url, &dest_key
+    ).with_performance(config.performance)
+    .with_s3(&config.s3)
+    .with_collision_strategy(config.collision_strategy)
+    .with_rate_limits(http::rate_limits(app));
+
+    let mut report = report::load_last_report(app)?.unwrap_or_else(report::MigrationReport::new);
+    window.emit("log", format!("=== RETRYING {} FAILED OBJECTS ===", failed.len())).unwrap();
+
+    reconcile::retry_objects(&window, &mirror, &mut report, &failed).await;
+
+    report.finished_at = report::now_secs();
+    report::save_last_report(app, &report)?;
+    window
+        .emit("log", format!("Retry complete. {} objects still failing.", report.failures().count()))
+        .unwrap();
+    Ok(report)
+}
+
+/// Compares per-bucket object counts and optionally re-transfers the missing keys
+#[tauri::command]
+async fn reconcile_migration(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+    retransfer: bool,
+) -> Result<reconcile::ReconciliationReport, String> {
+    let app = window.app_handle();
+    let config = deps::load_config(app);
+    let mirror = storage::StorageMirror::new(
+        http::client_for_app(app),
+        &source_url, &source_key, &dest_url, &dest_key
+    ).with_performance(config.performance)
+    .with_s3(&config.s3)
+    .with_collision_strategy(config.collision_strategy)
+    .with_rate_limits(http::rate_limits(app));
+    let buckets: Vec<String> = mirror.list_source_buckets().await?.into_iter().map(|b| b.id).collect();
+    reconcile::reconcile_buckets(&window, &mirror, &buckets, retransfer).await
+}
+
+/// Per-bucket progress of the latest storage migration, for the resume view
+#[tauri::command]
+fn get_migration_state(app: tauri::AppHandle) -> Result<Vec<checkpoint::BucketProgress>, String> {
+    checkpoint::migration_state(&app)
+}
+
+/// What a re-run would transfer vs skip, for one bucket or (without bucket_id) all of them
+#[tauri::command]
+async fn preview_resume(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+    bucket_id: Option<String>,
+) -> Result<Vec<resume::ResumePreview>, String> {
+    let app = window.app_handle();
+    let config = deps::load_config(app);
+    let mirror = storage::StorageMirror::new(
+        http::client_for_app(app),
+        &source_url, &source_key, &dest_url, &dest_key
+    ).with_performance(config.performance)
+    .with_s3(&config.s3)
+    .with_collision_strategy(config.collision_strategy)
+    .with_rate_limits(http::rate_limits(app));
+
+    let bucket_ids = match bucket_id {
+        Some(id) => vec![id],
+        None => mirror.list_source_buckets().await?.into_iter().map(|b| b.id).collect(),
+    };
+    let mut previews = vec![];
+    for id in bucket_ids {
+        previews.push(resume::preview_resume(app, &mirror, &source_url, &dest_url, &id).await?);
+    }
+    Ok(previews)
+}
+
+#[tauri::command]
+fn get_failed_objects(app: tauri::AppHandle) -> Result<Vec<report::FailedObject>, String> {
+    report::load_failed_objects(&app)
+}
+
+#[tauri::command]
+async fn probe_project_limits(
+    window: Window,
+    url: String,
+    key: String,
+    incoming_bytes: Option<u64>,
+) -> Result<limits::ProjectLimits, String> {
+    let app = window.app_handle();
+    let config = deps::load_config(app);
+    let mirror = storage::StorageMirror::for_destination(http::client_for_app(app), &url, &key)
+        .with_performance(config.performance)
+        .with_rate_limits(http::rate_limits(app));
+    let limits = limits::probe_project_limits(&mirror, config.storage_quota_bytes, incoming_bytes.unwrap_or(0)).await;
+    window.emit("log", format!("Storage limits: {}", limits.note)).unwrap();
+    Ok(limits)
+}
+
+/// Bytes downloaded by the source read probe
+const READ_PROBE_BYTES: u64 = 4096;
+
+/// Preflight: proves the source key can download content from a bucket, not just list it
+#[tauri::command]
+async fn test_source_read(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    bucket_id: String,
+) -> Result<storage::ReadProbe, String> {
+    let app = window.app_handle();
+    let config = deps::load_config(app);
+    let mirror = storage::StorageMirror::new(http::client_for_app(app), &source_url, &source_key, "", "")
+        .with_performance(config.performance)
+        .with_s3(&config.s3)
+        .with_rate_limits(http::rate_limits(app));
+    let probe = mirror.test_source_read(&bucket_id, READ_PROBE_BYTES).await;
+    match &probe.error {
+        None => window.emit("log", format!(
+            "Source read OK: {} bytes of {} ({})",
+            probe.sampled_bytes,
+            probe.object.as_deref().unwrap_or_default(),
+            probe.content_type.as_deref().unwrap_or("unknown type")
+        )).unwrap(),
+        Some(e) => window.emit("log", format!("Source read check failed: {}", e)).unwrap(),
+    }
+    Ok(probe)
+}
+
+/// Source bytes a migration would read, with a warning above `egress_warning_bytes`
+#[tauri::command]
+async fn estimate_egress(window: Window, source_url: String, source_key: String) -> Result<limits::EgressEstimate, String> {
+    let app = window.app_handle();
+    let config = deps::load_config(app);
+    let mirror = storage::StorageMirror::new(http::client_for_app(app), &source_url, &source_key, "", "")
+        .with_performance(config.performance)
+        .with_s3(&config.s3)
+        .with_rate_limits(http::rate_limits(app));
+    let estimate = limits::estimate_egress(&mirror, config.egress_warning_bytes).await?;
+    if let Some(warning) = &estimate.warning {
+        window.emit("log", format!("WARNING: {}", warning)).unwrap();
+    }
+    Ok(estimate)
+}
+
+/// Preflight: both projects' regions and a concurrency suited to the distance between them
+#[tauri::command]
+async fn suggest_concurrency(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+) -> Result<region::RegionSuggestion, String> {
+    let app = window.app_handle();
+    let suggestion = region::suggest_concurrency(app, &http::client_for_app(app), &source_url, &source_key, &dest_url, &dest_key).await;
+    window.emit("log", format!(
+        "Regions: {} -> {}. Suggested concurrency {} (current {}). {}",
+        suggestion.source_region.as_deref().unwrap_or("unknown"),
+        suggestion.dest_region.as_deref().unwrap_or("unknown"),
+        suggestion.suggested_concurrency,
+        suggestion.current_concurrency,
+        suggestion.reason
+    )).unwrap();
+    Ok(suggestion)
+}
+
+/// Accepts a suggested concurrency; returns the (clamped) value saved
+#[tauri::command]
+fn apply_suggested_concurrency(app: tauri::AppHandle, concurrency: usize) -> Result<usize, String> {
+    region::apply_concurrency(&app, concurrency)
+}
+
+/// Preflight guard against migrating a project into itself. Errors when the
+/// URLs name the same project unless `allow_same_project`; true when allowed.
+#[tauri::command]
+fn validate_distinct_projects(source_url: String, dest_url: String, allow_same_project: Option<bool>) -> Result<bool, String> {
+    functions::ensure_distinct_projects(&source_url, &dest_url, allow_same_project.unwrap_or(false))
+}
+
+/// Focused preflight before migrating one bucket: exists, writable, fits
+#[tauri::command]
+async fn check_bucket_target(
+    window: Window,
+    dest_url: String,
+    dest_key: String,
+    bucket_id: String,
+    estimated_bytes: u64,
+) -> Result<limits::BucketTargetCheck, String> {
+    let app = window.app_handle();
+    let config = deps::load_config(app);
+    let mirror = storage::StorageMirror::for_destination(http::client_for_app(app), &dest_url, &dest_key)
+        .with_performance(config.performance)
+        .with_rate_limits(http::rate_limits(app));
+    window.emit("log", format!("Checking destination for bucket {}...", bucket_id)).unwrap();
+    Ok(limits::check_bucket_target(&window, &mirror, &bucket_id, config.storage_quota_bytes, estimated_bytes).await)
+}
+
+/// Finds (or reads the cached) largest single-POST upload the destination accepts
+#[tauri::command]
+async fn probe_upload_limit(
+    window: Window,
+    dest_url: String,
+    dest_key: String,
+    bucket_id: String,
+    refresh: Option<bool>,
+) -> Result<limits::UploadLimit, String> {
+    let app = window.app_handle();
+    let config = deps::load_config(app);
+    let mirror = storage::StorageMirror::for_destination(http::client_for_app(app), &dest_url, &dest_key)
+        .with_performance(config.performance)
+        .with_rate_limits(http::rate_limits(app));
+    window.emit("log", format!("Checking upload size limit on bucket {}...", bucket_id)).unwrap();
+    limits::probe_upload_limit(&window, &mirror, &dest_url, &bucket_id, refresh.unwrap_or(false)).await
+}
+
+/// Stops a running migration from starting new transfers; in-flight ones finish. False if none is running.
+#[tauri::command]
+fn pause_migration(window: Window) -> bool {
+    cancel::set_paused(&window, "migration", true)
+}
+
+/// One health ping of both projects, as emitted by the probe loop during migrations
+#[tauri::command]
+async fn probe_endpoint_health(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+) -> Result<health::EndpointHealth, String> {
+    let client = http::client_for_app(window.app_handle());
+    Ok(health::probe(&client, (&source_url, &source_key), (&dest_url, &dest_key)).await)
+}
+
+/// Lets a paused migration continue where it stopped
+#[tauri::command]
+fn resume_migration(window: Window) -> bool {
+    cancel::set_paused(&window, "migration", false)
+}
+
+/// Asks a running "migration", "install", "backup" or "dir_scan" to stop. False if none is running.
+#[tauri::command]
+fn cancel_operation(app: tauri::AppHandle, operation: String) -> bool {
+    cancel::cancel(&app, &operation)
+}
+
+#[tauri::command]
+async fn sync_database_incremental(
+    window: Window,
+    source_db_url: String,
+    dest_db_url: String,
+    tables: Vec<dbsync::SyncTable>,
+) -> Result<Vec<dbsync::TableSyncResult>, String> {
+    window.emit("log", format!("=== INCREMENTAL DB SYNC ({} tables) ===", tables.len())).unwrap();
+    let app = window.app_handle().clone();
+    // Preflight: upserts into extension-typed columns fail without the extension
+    if let Err(e) = extensions::migrate_extensions(&window, &app, &source_db_url, &dest_db_url).await {
+        window.emit("log", format!("Extension preflight skipped: {}", e)).unwrap();
+    }
+    dbsync::sync_incremental(&window, &app, &source_db_url, &dest_db_url, &tables).await
+}
+
+/// Enables the source database's extensions on the destination ahead of a restore
+#[tauri::command]
+async fn migrate_extensions(
+    window: Window,
+    source_db_url: String,
+    dest_db_url: String,
+) -> Result<Vec<extensions::ExtensionStatus>, String> {
+    let app = window.app_handle().clone();
+    extensions::migrate_extensions(&window, &app, &source_db_url, &dest_db_url).await
+}
+
+/// Preflight: the destination has the Supabase-managed schemas a restore assumes
+#[tauri::command]
+async fn check_destination_schemas(window: Window, dest_db_url: String) -> Result<extensions::SchemaCheck, String> {
+    let app = window.app_handle().clone();
+    extensions::check_destination_schemas(&window, &app, &dest_db_url).await
+}
+
+/// Verifies comment coverage on the destination and restores missing table/column/function comments
+#[tauri::command]
+async fn migrate_comments(window: Window, source_db_url: String, dest_db_url: String) -> Result<comments::CommentReport, String> {
+    let app = window.app_handle().clone();
+    comments::migrate_comments(&window, &app, &source_db_url, &dest_db_url).await
+}
+
+/// Replays the source's pg_cron jobs on the destination, skipping ones already scheduled
+#[tauri::command]
+async fn migrate_cron_jobs(window: Window, source_db_url: String, dest_db_url: String) -> Result<Vec<cron::CronJobStatus>, String> {
+    let app = window.app_handle().clone();
+    cron::migrate_cron_jobs(&window, &app, &source_db_url, &dest_db_url).await
+}
+
+/// Recreates custom roles and replays grants/ownership lost to a `--no-owner` restore
+#[tauri::command]
+async fn migrate_grants(
+    window: Window,
+    source_db_url: String,
+    dest_db_url: String,
+) -> Result<grants::RoleMigration, String> {
+    let app = window.app_handle().clone();
+    grants::migrate_roles_and_grants(&window, &app, &source_db_url, &dest_db_url).await
+}
+
+/// Moves destination sequences past the restored data so new inserts don't collide
+#[tauri::command]
+async fn migrate_sequences(
+    window: Window,
+    source_db_url: String,
+    dest_db_url: String,
+) -> Result<Vec<sequences::SequenceStatus>, String> {
+    let app = window.app_handle().clone();
+    sequences::migrate_sequences(&window, &app, &source_db_url, &dest_db_url).await
+}
+
+/// Restores storage object owners (for owner-scoped RLS) after objects and users are migrated
+#[tauri::command]
+async fn migrate_object_owners(
+    window: Window,
+    source_db_url: String,
+    dest_db_url: String,
+    bucket_id: Option<String>,
+) -> Result<ownership::OwnershipReport, String> {
+    let app = window.app_handle().clone();
+    ownership::migrate_object_owners(&window, &app, &source_db_url, &dest_db_url, bucket_id.as_deref()).await
+}
+
+/// Adds the source's realtime-published tables to the destination publication
+#[tauri::command]
+async fn migrate_realtime_publication(
+    window: Window,
+    source_db_url: String,
+    dest_db_url: String,
+) -> Result<Vec<realtime::PublicationTableStatus>, String> {
+    let app = window.app_handle().clone();
+    realtime::migrate_realtime_publication(&window, &app, &source_db_url, &dest_db_url).await
+}
+
+#[tauri::command]
+async fn backup_edge_config(window: Window, url: String, key: String) -> Result<String, String> {
+    let client = http::client_for_app(window.app_handle());
+    match functions::backup_function_config(&window, &client, &url, &key).await {
+        Ok(configs) => Ok(format!("Secured {} function configs.", configs.len())),
+        Err(e) => Err(format!("Edge Config Backup Failed: {}", e))
+    }
+}
+
+/// Applies backed-up function settings to the destination project
+#[tauri::command]
+async fn restore_edge_config(
+    window: Window,
+    url: String,
+    key: String,
+    configs: Vec<functions::FunctionConfig>,
+) -> Result<Vec<functions::FunctionRestoreResult>, String> {
+    let client = http::client_for_app(window.app_handle());
+    functions::restore_function_config(&window, &client, &url, &key, &configs).await
+}
+
+/// Every Edge Function config of the project, across all API pages
+#[tauri::command]
+async fn list_functions(window: Window, url: String, key: String) -> Result<Vec<functions::FunctionConfig>, String> {
+    let client = http::client_for_app(window.app_handle());
+    functions::list_functions(&window, &client, &url, &key).await
+}
+
+/// Downloads a source bucket into a local folder (offline export, no destination project)
+#[tauri::command]
+async fn download_bucket_to_disk(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    bucket_id: String,
+    dest_dir: String,
+) -> Result<export::ExportSummary, String> {
+    let config = deps::load_config(window.app_handle());
+    let mirror = storage::StorageMirror::new(
+        http::client_for_app(window.app_handle()),
+        &source_url, &source_key, &source_url, &source_key
+    ).with_performance(config.performance)
+    .with_s3(&config.s3)
+    .with_rate_limits(http::rate_limits(window.app_handle()));
+    export::download_bucket_to_disk(&window, &mirror, &source_url, &bucket_id, std::path::Path::new(&dest_dir)).await
+}
+
+/// Indexes a public source bucket's object URLs and sizes (JSON, optionally a sitemap)
+#[tauri::command]
+async fn export_public_index(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    bucket_id: String,
+    base_public_url: String,
+    out_path: Option<String>,
+    xml: bool,
+) -> Result<export::PublicIndex, String> {
+    let config = deps::load_config(window.app_handle());
+    let mirror = storage::StorageMirror::new(
+        http::client_for_app(window.app_handle()),
+        &source_url, &source_key, &source_url, &source_key
+    ).with_performance(config.performance)
+    .with_s3(&config.s3)
+    .with_rate_limits(http::rate_limits(window.app_handle()));
+    export::export_public_index(&window, &mirror, &source_url, &bucket_id, &base_public_url, out_path.as_deref(), xml).await
+}
+
+/// Uploads a local folder (e.g. a bucket export) into a destination bucket under `prefix`
+#[tauri::command]
+async fn upload_folder_to_bucket(
+    window: Window,
+    dest_url: String,
+    dest_key: String,
+    bucket_id: String,
+    local_dir: String,
+    prefix: String,
+) -> Result<export::UploadReport, String> {
+    let config = deps::load_config(window.app_handle());
+    let mirror = storage::StorageMirror::for_destination(http::client_for_app(window.app_handle()), &dest_url, &dest_key)
+        .with_performance(config.performance)
+        .with_s3(&config.s3)
+        .with_rate_limits(http::rate_limits(window.app_handle()));
+    export::upload_folder_to_bucket(&window, &mirror, &bucket_id, std::path::Path::new(&local_dir), &prefix).await
+}
+
+/// Validates a depot manifest against the bundled schema with path-annotated errors
+#[tauri::command]
+async fn preview_manifest(app: tauri::AppHandle, url: String) -> Result<deps::ManifestPreview, String> {
+    deps::PulseManager::new(&app).preview_manifest(&url).await
+}
+
+/// Whether every destination bucket (and the given tables) is empty, or
+/// whether that couldn't be determined
+#[tauri::command]
+async fn assert_destination_empty(
+    window: Window,
+    dest_url: String,
+    dest_key: String,
+    dest_db_url: Option<String>,
+    tables: Option<Vec<String>>,
+) -> Result<limits::DestinationEmptiness, String> {
+    let app = window.app_handle();
+    let mirror = storage::StorageMirror::for_destination(http::client_for_app(app), &dest_url, &dest_key).with_rate_limits(http::rate_limits(app));
+    let check = limits::assert_destination_empty(app, &mirror, dest_db_url.as_deref(), &tables.unwrap_or_default()).await;
+    match check.refusal() {
+        None => window.emit("log", "Destination is empty.").unwrap(),
+        Some(msg) => window.emit("log", msg).unwrap(),
+    }
+    Ok(check)
+}
+
+/// Parses config.json as written and reports field-level errors and warnings
+#[tauri::command]
+fn validate_config_file(app: tauri::AppHandle) -> scaffold::ConfigValidation {
+    scaffold::validate_config_file(&app)
+}
+
+/// Latest rate-limit headers observed per project and Supabase service
+#[tauri::command]
+fn get_rate_limit_state(app: tauri::AppHandle) -> Vec<http::RateLimitState> {
+    http::rate_limits(&app).snapshot()
+}
+
+/// Duplicate, self-pointing or keyless profiles in profiles.json
+#[tauri::command]
+fn lint_profiles(app: tauri::AppHandle) -> Result<Vec<profiles::ProfileFinding>, String> {
+    profiles::lint_profiles(&app)
+}
+
+/// Drops exact duplicate profiles; returns how many were removed
+#[tauri::command]
+fn dedupe_profiles(app: tauri::AppHandle) -> Result<usize, String> {
+    profiles::dedupe_profiles(&app)
+}
+
+/// Runs each driver binary once to prove it starts and finds its libraries
+#[tauri::command]
+async fn warm_up_drivers(window: Window, app: tauri::AppHandle) -> Result<Vec<db::BinaryCheck>, String> {
+    let checks = db::warm_up_drivers(&app).await;
+    for check in &checks {
+        match (&check.version, &check.error) {
+            (Some(version), _) => window.emit("log", format!("  {}: {}", check.binary, version)).unwrap(),
+            (None, error) => window.emit("log", format!("  {}: FAILED ({})", check.binary, error.as_deref().unwrap_or("unknown error"))).unwrap(),
+        }
+    }
+    Ok(checks)
+}
+
+/// Warns when the local pg_dump is older than the source server
+#[tauri::command]
+async fn check_version_compat(window: Window, app: tauri::AppHandle, source_db: String) -> Result<db::VersionCompat, String> {
+    db::check_version_compat(&window, &app, &source_db).await
+}
+
+/// Restores a custom/directory dump using the configured number of parallel jobs
+#[tauri::command]
+async fn restore_database(app: tauri::AppHandle, dest_db: String, dump_path: String) -> Result<usize, String> {
+    let jobs = deps::PulseManager::new(&app).config().performance.restore_jobs;
+    db::restore_database(&app, &dest_db, &dump_path, jobs).await
+}
+
+/// Compares the source and destination schemas (tables, columns, indexes, functions)
+#[tauri::command]
+async fn diff_schema(app: tauri::AppHandle, source_db: String, dest_db: String) -> Result<schema_diff::SchemaDiff, String> {
+    schema_diff::diff_schema(&app, &source_db, &dest_db).await
+}
+
+#[tauri::command]
+async fn link_local_source(window: Window, app: tauri::AppHandle, path: String) -> Result<String, String> {
+    let skip_bad = deps::PulseManager::new(&app).config().skip_bad_archive_entries;
+    let out_dir = source_archive_dir(&app);
+    tasks::run_blocking(move || functions::zip_local_source(&window, &path, &out_dir, skip_bad)).await
+}
+
+fn source_archive_dir(app: &tauri::AppHandle) -> std::path::PathBuf {
+    paths::get_userdata_dir(app).join("backups").join("functions")
+}
+
+/// Archives left half-written by an interrupted `link_local_source`
+#[tauri::command]
+fn find_partial_archives(app: tauri::AppHandle) -> Vec<String> {
+    functions::stale_partial_archives(&source_archive_dir(&app))
+        .into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+#[tauri::command]
+fn init_app(app: tauri::AppHandle) -> Result<String, String> {
+    paths::ensure_directories(&app)?;
+    Ok(format!("App initialized. Root: {:?}", paths::get_app_root(&app)))
+}
+
+#[tauri::command]
+fn get_config(app: tauri::AppHandle) -> Result<deps::PulseConfig, String> {
+    let config_path = paths::get_config_path(&app);
+    if config_path.exists() {
+        Ok(deps::read_config_or_backup(&app, &config_path))
+    } else {
+        Ok(deps::PulseConfig::default())
+    }
+}
+
+#[tauri::command]
+fn save_config(app: tauri::AppHandle, config: deps::PulseConfig) -> Result<String, String> {
+    let config_path = paths::get_config_path(&app);
+    if let Some(parent) = config_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    std::fs::write(&config_path, data).map_err(|e| e.to_string())?;
+    Ok("Config saved".to_string())
+}
+
+/// Fully resolved config (secrets masked) with the source of every setting
+#[tauri::command]
+fn get_effective_config(app: tauri::AppHandle) -> Result<deps::EffectiveConfig, String> {
+    deps::effective_config(&app)
+}
+
+#[tauri::command]
+fn scaffold_config(app: tauri::AppHandle) -> Result<String, String> {
+    scaffold::scaffold_config(&app)
+}
+
+#[tauri::command]
+fn get_performance_defaults() -> deps::PerformanceConfig {
+    deps::PerformanceConfig::default()
+}
+
+#[tauri::command]
+fn list_profiles(app: tauri::AppHandle) -> Result<Vec<serde_json::Value>, String> {
+    let profiles_path = paths::get_profiles_path(&app);
+    if profiles_path.exists() {
+        let data = std::fs::read_to_string(&profiles_path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&data).map_err(|e| e.to_string())
+    } else {
+        Ok(vec![])
+    }
+}
+
+#[tauri::command]
+fn read_log_tail(app: tauri::AppHandle, lines: usize) -> Result<Vec<String>, String> {
+    logs::read_log_tail(&app, lines)
+}
+
+/// Disk usage of "drivers", "backups", "logs" or "userdata". Cancel with
+/// `cancel_operation("dir_scan")` to get the partial total.
+#[tauri::command]
+async fn scan_dir_size(window: Window, target: String) -> Result<disk::DirSize, String> {
+    let app = window.app_handle().clone();
+    let root = match target.as_str() {
+        "drivers" => deps::PulseManager::new(&app).bin_path().clone(),
+        "backups" => paths::get_userdata_dir(&app).join("backups"),
+        "logs" => paths::get_logs_dir(&app),
+        "userdata" => paths::get_userdata_dir(&app),
+        other => return Err(format!("Unknown scan target: {}", other)),
+    };
+
+    let token = cancel::begin(&app, "dir_scan");
+    let (task_window, task_token) = (window.clone(), token.clone());
+    let size = tasks::run_blocking(move || Ok(disk::dir_size(&task_window, &root, &task_token))).await?;
+    let outcome = if size.cancelled { Err(cancel::CANCELLED.to_string()) } else { Ok(()) };
+    cancel::finish(&window, "dir_scan", &outcome);
+    Ok(size)
+}
+
+/// Driver download caches, partial archives and temp files the app left behind
+#[tauri::command]
+fn list_temp_artifacts(app: tauri::AppHandle) -> Vec<disk::TempArtifact> {
+    disk::list_temp_artifacts(&app)
+}
+
+/// Deletes temp artifacts older than `older_than_hours` (never ones modified in the last 10 minutes)
+#[tauri::command]
+fn prune_temp_artifacts(window: Window, older_than_hours: u64) -> disk::PruneResult {
+    let result = disk::prune_temp_artifacts(window.app_handle(), older_than_hours);
+    window.emit("log", format!(
+        "Pruned {} temp files ({} bytes freed, {} recent ones kept)",
+        result.removed.len(),
+        result.freed_bytes,
+        result.skipped_recent
+    )).unwrap();
+    result
 }
 
+/// Re-sends recent log/progress history to a reloaded or newly opened window
 #[tauri::command]
-async fn link_local_source(window: Window, path: String) -> Result<String, String> {
-    functions::zip_local_source(&window, &path)
+fn replay_log_buffer(window: Window) -> Result<usize, String> {
+    logs::replay_log_buffer(&window)
 }
 
 #[tauri::command]
-fn init_app(app: tauri::AppHandle) -> Result<String, String> {
-    paths::ensure_directories(&app)?;
-    Ok(format!("App initialized. Root: {:?}", paths::get_app_root(&app)))
+fn list_log_files(app: tauri::AppHandle) -> Result<Vec<logs::LogFileInfo>, String> {
+    logs::list_log_files(&app)
 }
 
 #[tauri::command]
-fn get_config(app: tauri::AppHandle) -> Result<deps::PulseConfig, String> {
-    let config_path = paths::get_config_path(&app);
-    if config_path.exists() {
-        let data = std::fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-        serde_json::from_str(&data).map_err(|e| e.to_string())
-    } else {
-        Ok(deps::PulseConfig::default())
-    }
+fn get_release_state(app: tauri::AppHandle) -> Option<deps::ReleaseState> {
+    deps::PulseManager::new(&app).release_state()
 }
 
 #[tauri::command]
-fn save_config(app: tauri::AppHandle, config: deps::PulseConfig) -> Result<String, String> {
-    let config_path = paths::get_config_path(&app);
-    if let Some(parent) = config_path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+async fn refresh_release_state(window: Window, app: tauri::AppHandle) -> Result<deps::ReleaseState, String> {
+    deps::PulseManager::new(&app).refresh_release(&window).await
+}
+
+/// Syncs the driver install records with what is actually on disk; returns the changes
+#[tauri::command]
+fn reconcile_registry(app: tauri::AppHandle) -> Result<Vec<deps::RegistryChange>, String> {
+    deps::PulseManager::new(&app).reconcile_registry()
+}
+
+/// Re-hashes an installed driver pack to catch quarantined or corrupted binaries
+#[tauri::command]
+fn verify_installed(app: tauri::AppHandle, package_id: String) -> deps::InstallVerification {
+    deps::PulseManager::new(&app).verify_installed(&package_id)
+}
+
+/// Raw release notes for a channel's active release (defaults to the configured channel)
+#[tauri::command]
+async fn get_release_notes(app: tauri::AppHandle, channel: Option<String>) -> Result<Option<String>, String> {
+    let mgr = deps::PulseManager::new(&app);
+    let channel = channel.unwrap_or_else(|| mgr.config().channel.clone());
+    mgr.release_notes(&channel).await
+}
+
+/// Installs every package the manifest lists; returns the ones that failed
+#[tauri::command]
+async fn install_all(window: Window, app: tauri::AppHandle) -> Result<Vec<(String, String)>, String> {
+    deps::PulseManager::new(&app).install_all(&window).await
+}
+
+/// "Retry install": continues a failed driver install without redoing finished steps
+#[tauri::command]
+async fn resume_install(window: Window, app: tauri::AppHandle, package_id: String) -> Result<String, String> {
+    deps::PulseManager::new(&app).resume_install(&window, &package_id).await
+}
+
+/// Checks an installed driver pack was built for this OS and CPU
+#[tauri::command]
+fn check_binary_platform(app: tauri::AppHandle, package_id: String) -> Result<(), String> {
+    deps::PulseManager::new(&app).check_binary_platform(&package_id)
+}
+
+#[tauri::command]
+async fn list_channels(app: tauri::AppHandle) -> Result<Vec<deps::ChannelInfo>, String> {
+    deps::PulseManager::new(&app).list_channels().await
+}
+
+/// Switches release channel, refusing channels without an active release
+#[tauri::command]
+async fn switch_channel(window: Window, channel: String) -> Result<String, String> {
+    let mgr = deps::PulseManager::new(window.app_handle());
+    let previous = mgr.config().channel.clone();
+    let version = mgr.switch_channel(window.app_handle(), &channel).await?;
+
+    // Re-resolve under the new config so release.json reflects the switch
+    if let Err(e) = deps::PulseManager::new(window.app_handle()).refresh_release(&window).await {
+        window.emit("log", format!("Channel switched, but release state not refreshed: {}", e)).unwrap();
     }
-    let data = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
-    std::fs::write(&config_path, data).map_err(|e| e.to_string())?;
-    Ok("Config saved".to_string())
+
+    telemetry::track_event(&window, telemetry::TelemetryEvent::new(
+        "CHANNEL_SWITCHED",
+        serde_json::json!({ "from": previous, "to": channel, "version": version })
+    ));
+    Ok(version)
 }
 
 #[tauri::command]
-fn list_profiles(app: tauri::AppHandle) -> Result<Vec<serde_json::Value>, String> {
-    let profiles_path = paths::get_profiles_path(&app);
-    if profiles_path.exists() {
-        let data = std::fs::read_to_string(&profiles_path).map_err(|e| e.to_string())?;
-        serde_json::from_str(&data).map_err(|e| e.to_string())
-    } else {
-        Ok(vec![])
+fn export_report(app: tauri::AppHandle, format: String, out_path: String) -> Result<String, String> {
+    report::export_report(&app, &format, &out_path)
+}
+
+/// Zips the session log, redacted config, package versions, system info and last report for bug reports
+#[tauri::command]
+fn export_diagnostics(app: tauri::AppHandle, out_path: String) -> Result<String, String> {
+    diagnostics::export_diagnostics(&app, &out_path)
+}
+
+/// Cross-checks a session's telemetry log for migrations and spans that never finished or don't add up
+#[tauri::command]
+fn audit_session(app: tauri::AppHandle, session_id: String) -> Result<session_audit::SessionAudit, String> {
+    session_audit::audit_session(&app, &session_id)
+}
+
+/// Mirrors log/progress events to a localhost SSE endpoint for remote dashboards
+#[tauri::command]
+async fn start_log_monitor(app: tauri::AppHandle, port: Option<u16>) -> Result<monitor::MonitorInfo, String> {
+    monitor::start(&app, port).await
+}
+
+#[tauri::command]
+fn stop_log_monitor(app: tauri::AppHandle) {
+    monitor::stop(&app);
+}
+
+/// Registers a recurring backup of a saved profile (interval in seconds)
+#[tauri::command]
+fn schedule_backup(app: tauri::AppHandle, profile_id: String, interval: u64) -> Result<schedules::BackupSchedule, String> {
+    schedules::schedule_backup(&app, &profile_id, interval)
+}
+
+#[tauri::command]
+fn list_schedules(app: tauri::AppHandle) -> Result<Vec<schedules::BackupSchedule>, String> {
+    schedules::load_schedules(&app)
+}
+
+#[tauri::command]
+fn cancel_schedule(app: tauri::AppHandle, schedule_id: String) -> Result<(), String> {
+    schedules::cancel_schedule(&app, &schedule_id)
+}
+
+/// Hashes every object in a bucket; also enables hash-based skips on re-migration
+#[tauri::command]
+async fn index_bucket(window: Window, url: String, key: String, bucket_id: String) -> Result<bucket_index::IndexResult, String> {
+    bucket_index::index_bucket(&window, &url, &key, &bucket_id).await
+}
+
+/// Checks a functions archive for entrypoints before a restore relies on it
+#[tauri::command]
+fn validate_function_archive(
+    zip_path: String,
+    configs: Option<Vec<functions::FunctionConfig>>,
+) -> Result<functions::ArchiveValidation, String> {
+    functions::validate_function_archive(&zip_path, &configs.unwrap_or_default())
+}
+
+/// Lists source buckets whose objects are guarded by role/claim-based RLS
+#[tauri::command]
+async fn check_bucket_policies(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    db_url: String,
+) -> Result<Vec<policies::BucketPolicyCheck>, String> {
+    let app = window.app_handle();
+    let mirror = storage::StorageMirror::new(http::client_for_app(app), &source_url, &source_key, "", "").with_rate_limits(http::rate_limits(app));
+    let buckets: Vec<String> = mirror.list_source_buckets().await?.into_iter().map(|b| b.id).collect();
+    policies::check_bucket_policies(app, &db_url, &buckets).await
+}
+
+/// Bundles config, profiles, schedules and logs for moving to another machine
+#[tauri::command]
+fn create_app_snapshot(app: tauri::AppHandle, out_path: String, passphrase: String) -> Result<String, String> {
+    snapshot::create_app_snapshot(&app, &out_path, &passphrase)
+}
+
+#[tauri::command]
+async fn restore_app_snapshot(
+    window: Window,
+    in_path: String,
+    passphrase: String,
+) -> Result<snapshot::RestoreSummary, String> {
+    snapshot::restore_app_snapshot(&window, &in_path, &passphrase).await
+}
+
+/// Spot-checks migrated objects by length plus head/tail samples (default 64 KB each)
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn verify_objects_sampled(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+    bucket_id: String,
+    keys: Option<Vec<String>>,
+    sample_kb: Option<u64>,
+) -> Result<Vec<storage::SampleCheck>, String> {
+    let mirror = storage::StorageMirror::new(
+        http::client_for_app(window.app_handle()),
+        &source_url, &source_key, &dest_url, &dest_key
+    ).with_rate_limits(http::rate_limits(window.app_handle()));
+    let keys = match keys {
+        Some(keys) => keys,
+        None => mirror.list_objects(&bucket_id).await?.into_iter().map(|o| o.name).collect(),
+    };
+    let sample_bytes = sample_kb.unwrap_or(64).max(1) * 1024;
+
+    let mut results = vec![];
+    for key in keys {
+        let check = match mirror.sample_verify(&bucket_id, &key, sample_bytes).await {
+            Ok(check) => {
+                if !(check.content_length_match && check.sampled_match) {
+                    window.emit("log", format!("  Mismatch: {} ({} vs {} bytes)", key, check.source_length, check.dest_length)).unwrap();
+                }
+                check
+            }
+            Err(e) => {
+                window.emit("log", format!("  Could not verify {}: {}", key, e)).unwrap();
+                storage::SampleCheck {
+                    key,
+                    content_length_match: false,
+                    sampled_match: false,
+                    source_length: 0,
+                    dest_length: 0,
+                    error: Some(e),
+                }
+            }
+        };
+        results.push(check);
     }
+    Ok(results)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -88,6 +1165,22 @@ pub fn run() {
             if let Err(e) = paths::ensure_directories(app.handle()) {
                 eprintln!("Failed to initialize directories: {}", e);
             }
+            logs::init_session_log(app.handle());
+            logs::init_log_buffer(app.handle());
+            app.manage(monitor::LogMonitor::default());
+            app.manage(schedules::ScheduleTimers::default());
+            app.manage(cancel::CancelRegistry::default());
+            app.manage(cancel::PauseRegistry::default());
+            app.manage(http::RateLimits::default());
+            schedules::rearm_all(app.handle());
+            if deps::load_config(app.handle()).monitor_enabled {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = monitor::start(&handle, None).await {
+                        let _ = handle.emit("log", e);
+                    }
+                });
+            }
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -103,10 +1196,103 @@ pub fn run() {
             init_app,
             get_config,
             save_config,
-            list_profiles
+            list_profiles,
+            read_log_tail,
+            list_log_files,
+            get_release_state,
+            refresh_release_state,
+            export_report,
+            get_performance_defaults,
+            import_users,
+            benchmark_transfer,
+            scaffold_config,
+            wipe_destination_storage,
+            retry_failed,
+            get_failed_objects,
+            probe_project_limits,
+            sync_database_incremental,
+            start_log_monitor,
+            stop_log_monitor,
+            schedule_backup,
+            list_schedules,
+            cancel_schedule,
+            index_bucket,
+            validate_function_archive,
+            check_bucket_policies,
+            create_app_snapshot,
+            restore_app_snapshot,
+            verify_objects_sampled,
+            list_channels,
+            switch_channel,
+            get_migration_state,
+            check_bucket_target,
+            cancel_operation,
+            list_functions,
+            verify_installed,
+            restore_edge_config,
+            preview_resume,
+            get_release_notes,
+            migrate_extensions,
+            get_effective_config,
+            estimate_egress,
+            check_binary_platform,
+            resume_install,
+            install_all,
+            test_source_read,
+            scan_dir_size,
+            list_temp_artifacts,
+            prune_temp_artifacts,
+            replay_log_buffer,
+            export_plan,
+            import_plan,
+            run_plan,
+            dest_roundtrip_test,
+            check_version_compat,
+            migrate_grants,
+            preview_manifest,
+            download_bucket_to_disk,
+            upload_folder_to_bucket,
+            find_partial_archives,
+            capability_matrix,
+            warm_up_drivers,
+            lint_profiles,
+            dedupe_profiles,
+            get_rate_limit_state,
+            assert_destination_empty,
+            migrate_realtime_publication,
+            validate_config_file,
+            restore_database,
+            diff_schema,
+            export_public_index,
+            smoke_test,
+            suggest_concurrency,
+            apply_suggested_concurrency,
+            migrate_sequences,
+            audit_content_types,
+            fix_content_types,
+            pause_migration,
+            resume_migration,
+            migrate_object_owners,
+            probe_endpoint_health,
+            create_signed_upload_url,
+            reconcile_registry,
+            check_destination_schemas,
+            reconcile_migration,
+            migrate_comments,
+            migrate_cron_jobs,
+            probe_upload_limit,
+            export_diagnostics,
+            audit_session,
+            migrate_bucket,
+            validate_distinct_projects
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                monitor::stop(app);
+            }
+        });
 }
 
 // Replaces the old Docker check. Now checks for local binaries.
@@ -124,16 +1310,203 @@ async fn check_driver_status(window: Window, app: tauri::AppHandle) -> Result<St
     }
 }
 
+/// Objects between checkpoint writes during a bucket transfer
+const PROGRESS_FLUSH_EVERY: usize = 25;
+
+/// `refresh_listings` re-lists each bucket for the transfer instead of
+/// reusing the listing cached during planning
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn perform_migration(
     window: Window,
     source_url: String,
     source_key: String,
     dest_url: String,
     dest_key: String,
+    allow_same_project: Option<bool>,
+    source_db_url: Option<String>,
+    confirm_egress: Option<bool>,
+    require_empty_destination: Option<bool>,
+    refresh_listings: Option<bool>,
+) -> Result<String, String> {
+    let token = cancel::begin(window.app_handle(), "migration");
+    let pause = cancel::begin_pausable(window.app_handle(), "migration");
+    let result = migrate_storage(
+        window.clone(), token, pause, source_url, source_key, dest_url, dest_key, allow_same_project, source_db_url,
+        confirm_egress, require_empty_destination.unwrap_or(false), refresh_listings.unwrap_or(false),
+        MigrationScope::default(),
+    ).await;
+    cancel::finish(&window, "migration", &result);
+    result
+}
+
+/// Narrows and tunes a storage run; the default covers every source bucket
+/// with the saved settings
+#[derive(Default)]
+struct MigrationScope {
+    buckets: Option<Vec<String>>,
+    bucket_remap: std::collections::BTreeMap<String, String>, // source id -> destination id
+    performance: Option<deps::PerformanceConfig>, // None = config.performance
+    s3_enabled: Option<bool>,                     // None = config.s3.enabled
+}
+
+impl MigrationScope {
+    /// The source buckets this run covers; every requested one must exist
+    /// and no two may land in the same destination bucket
+    fn select(&self, buckets: Vec<storage::Bucket>) -> Result<Vec<storage::Bucket>, String> {
+        let buckets = match &self.buckets {
+            None => buckets,
+            Some(ids) => {
+                if let Some(missing) = ids.iter().find(|id| !buckets.iter().any(|b| &b.id == *id)) {
+                    return Err(format!("Bucket {} not found on the source", missing));
+                }
+                buckets.into_iter().filter(|b| ids.contains(&b.id)).collect()
+            }
+        };
+        let mut targets = std::collections::HashMap::new();
+        for bucket in &buckets {
+            let target = self.bucket_remap.get(&bucket.id).unwrap_or(&bucket.id);
+            if let Some(other) = targets.insert(target, &bucket.id) {
+                return Err(format!("Buckets {} and {} would both be copied into {}", other, bucket.id, target));
+            }
+        }
+        Ok(buckets)
+    }
+}
+
+/// Migrates one bucket after a `check_bucket_target` preflight: the
+/// destination bucket must exist (or be creatable), accept writes and fit
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn migrate_bucket(
+    window: Window,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+    bucket_id: String,
+    allow_same_project: Option<bool>,
+    confirm_egress: Option<bool>,
+) -> Result<String, String> {
+    let app = window.app_handle();
+    let config = deps::load_config(app);
+    let mirror = storage::StorageMirror::new(http::client_for_app(app), &source_url, &source_key, &dest_url, &dest_key)
+        .with_performance(config.performance)
+        .with_s3(&config.s3)
+        .with_rate_limits(http::rate_limits(app));
+    let estimated_bytes = mirror.list_objects(&bucket_id).await?.iter().map(|o| o.size()).sum();
+
+    window.emit("log", format!("Checking destination for bucket {}...", bucket_id)).unwrap();
+    let check = limits::check_bucket_target(&window, &mirror, &bucket_id, config.storage_quota_bytes, estimated_bytes).await;
+    if let Some(msg) = check.refusal(&bucket_id) {
+        window.emit("log", &msg).unwrap();
+        return Err(msg);
+    }
+
+    let token = cancel::begin(app, "migration");
+    let pause = cancel::begin_pausable(app, "migration");
+    let scope = MigrationScope { buckets: Some(vec![bucket_id]), ..Default::default() };
+    let result = migrate_storage(
+        window.clone(), token, pause, source_url, source_key, dest_url, dest_key, allow_same_project, None,
+        confirm_egress, false, false, scope,
+    ).await;
+    cancel::finish(&window, "migration", &result);
+    result
+}
+
+/// Sizes of the objects already in the destination bucket, by key. Skip and
+/// Rename depend on the listing, so a failed one fails the bucket; Overwrite
+/// only uses it to confirm index hits, so there everything is sent again.
+async fn dest_object_sizes(
+    mirror: &storage::StorageMirror,
+    dest_bucket: &str,
+    log: &(dyn Fn(String) + Sync),
+) -> Result<std::collections::HashMap<String, u64>, String> {
+    match mirror.list_dest_objects(dest_bucket).await {
+        Ok(objects) => Ok(objects.into_iter().map(|o| (o.name.clone(), o.size())).collect()),
+        Err(e) if mirror.collision_strategy() == storage::CollisionStrategy::Overwrite => {
+            log(format!("  WARNING: Destination listing failed ({}); sending every object", e));
+            Ok(Default::default())
+        }
+        Err(e) => Err(format!("Destination listing failed: {}", e)),
+    }
+}
+
+/// One finished transfer: the object, bytes and content type or the error,
+/// the renamed destination key and how long it took
+type TransferResult<'a> = (&'a storage::StorageObject, Result<(u64, String), String>, Option<String>, u64);
+
+/// Transfers `pending` objects of one bucket, `transfer_concurrency` at a time.
+/// While paused, transfers that have not started wait; ones in flight finish.
+#[allow(clippy::too_many_arguments)]
+fn bucket_transfers<'a>(
+    mirror: &'a storage::StorageMirror,
+    bucket_id: &'a str,
+    objects: &'a [storage::StorageObject],
+    pending: Vec<usize>,
+    dest_sizes: &'a std::collections::HashMap<String, u64>,
+    token: &'a cancel::CancelToken,
+    pause: &'a cancel::PauseToken,
+    log: &'a (dyn Fn(String) + Sync),
+) -> impl futures::Stream<Item = TransferResult<'a>> + 'a {
+    let concurrency = mirror.performance().transfer_concurrency;
+    // Iterate indices: a borrowed-item closure trips the Send check on the command future
+    futures::stream::iter(pending)
+        .map(move |i| {
+            let obj = &objects[i];
+            let existing_size = dest_sizes.get(&obj.name).copied();
+            async move {
+                // Paused: hold new transfers here; ones already past this point finish
+                pause.wait_while_paused(token).await;
+                if token.is_cancelled() {
+                    return (obj, Err(cancel::CANCELLED.to_string()), None, 0);
+                }
+                let Some(dest_key) = mirror.place_object(obj, existing_size) else {
+                    log(format!("  Skipped: {} (already on destination)", obj.name));
+                    return (obj, Err(storage::COLLISION_SKIPPED.to_string()), None, 0);
+                };
+                let started = std::time::Instant::now();
+                let outcome = http::with_backoff(http::RETRY_ATTEMPTS, || mirror.transfer_object_to(bucket_id, obj, &dest_key)).await;
+                match &outcome {
+                    Ok((_, content_type)) if dest_key != obj.name => log(format!("  Synced: {} -> {} [{}] (renamed on collision)", obj.name, dest_key, content_type)),
+                    Ok((_, content_type)) => log(format!("  Synced: {} [{}]", obj.name, content_type)),
+                    Err(e) => log(format!("  Failed: {} ({})", obj.name, e)),
+                }
+                let renamed = Some(dest_key).filter(|k| *k != obj.name);
+                (obj, outcome, renamed, started.elapsed().as_millis() as u64)
+            }
+        })
+        .buffer_unordered(concurrency)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn migrate_storage(
+    window: Window,
+    token: cancel::CancelToken,
+    pause: cancel::PauseToken,
+    source_url: String,
+    source_key: String,
+    dest_url: String,
+    dest_key: String,
+    allow_same_project: Option<bool>,
+    source_db_url: Option<String>,
+    confirm_egress: Option<bool>,
+    require_empty_destination: bool,
+    refresh_listings: bool,
+    scope: MigrationScope,
 ) -> Result<String, String> {
     window.emit("log", "=== MIGRATION INITIATED ===").unwrap();
 
+    // Guard against a fat-fingered self-migration
+    match functions::ensure_distinct_projects(&source_url, &dest_url, allow_same_project.unwrap_or(false)) {
+        Ok(true) => window.emit("log", "WARNING: Source and destination are the same project (allowed by flag).").unwrap(),
+        Ok(false) => {}
+        Err(msg) => {
+            window.emit("log", &msg).unwrap();
+            return Err(msg);
+        }
+    }
+
     // WIRE TELEMETRY - Using the constructor properly
     let event = telemetry::TelemetryEvent::new(
         "MIGRATION_START",
@@ -145,9 +1518,40 @@ async fn perform_migration(
     telemetry::track_event(&window, event);
 
     // WIRE STORAGE - Full sync using all fields and methods
+    let config = deps::load_config(window.app_handle());
+    let client = http::client_for_app(window.app_handle());
+    let rate_limits = http::rate_limits(window.app_handle());
+    let s3 = s3::S3Config { enabled: scope.s3_enabled.unwrap_or(config.s3.enabled), ..config.s3.clone() };
     let mirror = storage::StorageMirror::new(
+        client.clone(),
         &source_url, &source_key, &dest_url, &dest_key
-    );
+    ).with_performance(scope.performance.clone().unwrap_or_else(|| config.performance.clone()))
+    .with_s3(&s3)
+    .with_collision_strategy(config.collision_strategy)
+    .with_upload_limit(limits::cached_upload_limit(window.app_handle(), &dest_url))
+    .with_bucket_remap(scope.bucket_remap.clone())
+    .with_rate_limits(rate_limits.clone());
+
+    // Clean-clone guard: never merge into a populated (or unverifiable) destination
+    if require_empty_destination {
+        let check = limits::assert_destination_empty(window.app_handle(), &mirror, None, &[]).await;
+        if let Some(msg) = check.refusal() {
+            window.emit("log", &msg).unwrap();
+            return Err(msg);
+        }
+        window.emit("log", "Destination is empty.").unwrap();
+    }
+
+    // Preflight: warn when the destination is already close to its quota.
+    // Skipped without a configured quota since it lists the whole destination.
+    if config.storage_quota_bytes.is_some() {
+        let limits = limits::probe_project_limits(&mirror, config.storage_quota_bytes, 0).await;
+        if limits.near_limit {
+            window.emit("log", format!("WARNING: Destination near storage limit. {}", limits.note)).unwrap();
+        }
+    }
+
+    hooks::run_stage_hooks(&window, &client, &config.hooks, "STORAGE", "pre").await?;
     
     window.emit("log", "Scanning source buckets...").unwrap();
     let buckets = match mirror.list_source_buckets().await {
@@ -160,36 +1564,294 @@ async fn perform_migration(
             return Err(e);
         }
     };
+    let buckets = scope.select(buckets)?;
+
+    // Plan: enumerate every bucket up front. Listings are cached on the mirror,
+    // so the transfer phase below reuses them instead of listing again.
+    let (mut planned_objects, mut planned_bytes) = (0, 0);
+    let plan_span = telemetry::TelemetrySpan::start(&window, "STORAGE_PLAN", serde_json::json!({ "buckets": buckets.len() }));
+    for bucket in &buckets {
+        token.check()?;
+        let listing = mirror.list_objects(&bucket.id).await;
+        let (objects_total, bytes_total) = match &listing {
+            Ok(objects) => (objects.len(), objects.iter().map(|o| o.size()).sum()),
+            Err(_) => (0, 0),
+        };
+        planned_objects += objects_total;
+        planned_bytes += bytes_total;
+        let saved = checkpoint::update_bucket(window.app_handle(), &bucket.id, |p| {
+            *p = checkpoint::BucketProgress {
+                id: bucket.id.clone(),
+                name: bucket.name.clone(),
+                objects_total,
+                bytes_total,
+                status: "PENDING".to_string(),
+                ..Default::default()
+            };
+        });
+        if let Err(e) = saved {
+            window.emit("log", format!("Could not record migration progress: {}", e)).unwrap();
+        }
+    }
+    plan_span.end(serde_json::json!({ "objects": planned_objects, "bytes": planned_bytes }));
+    window.emit("log", format!("Planned {} objects across {} buckets", planned_objects, buckets.len())).unwrap();
+
+    // Large jobs need an explicit go-ahead: reading from the source may be billed as egress
+    let egress = limits::egress_estimate(planned_bytes, planned_objects, buckets.len(), config.egress_warning_bytes);
+    if let Some(warning) = &egress.warning {
+        window.emit("log", format!("WARNING: {}", warning)).unwrap();
+        if !confirm_egress.unwrap_or(false) {
+            return Err(format!(
+                "Migration not started: {:.1} GB exceeds the egress warning threshold. Re-run with confirmation.",
+                egress.estimated_gb
+            ));
+        }
+    }
+
+    // Preflight: RLS policies aren't copied with objects; needs a DB connection
+    if let Some(db_url) = &source_db_url {
+        let bucket_ids: Vec<String> = buckets.iter().map(|b| b.id.clone()).collect();
+        match policies::check_bucket_policies(window.app_handle(), db_url, &bucket_ids).await {
+            Ok(checks) => {
+                for check in checks.iter().filter(|c| c.has_custom_policies) {
+                    window.emit("log", format!(
+                        "WARNING: Bucket '{}' is protected by RLS policies ({}). Policies are not migrated with objects; recreate them on the destination or users may lose access.",
+                        check.bucket,
+                        check.policies.join(", ")
+                    )).unwrap();
+                }
+            }
+            Err(e) => window.emit("log", format!("Storage policy check skipped: {}", e)).unwrap(),
+        }
+    }
+
+    // Explicit refresh: re-list at transfer time instead of reusing the plan's listings
+    if refresh_listings {
+        mirror.invalidate_listing(None);
+        window.emit("log", "Refreshing source listings before transfer").unwrap();
+    }
+
+    let mut report = report::MigrationReport::new();
+    let run_span = telemetry::TelemetrySpan::start(&window, "STORAGE_RUN", serde_json::json!({
+        "buckets": buckets.len(),
+        "planned_bytes": planned_bytes,
+    }));
+    report.manual_steps.push(storage::UNMIRRORED_BUCKET_SETTINGS.to_string());
+
+    let dest_bucket_ids: std::collections::HashSet<String> = mirror
+        .list_dest_buckets()
+        .await
+        .map(|b| b.into_iter().map(|b| b.id).collect())
+        .unwrap_or_default();
+
+    let mut rate_limit_warned = false;
+    let health = health::HealthMonitor::start(
+        &window,
+        client.clone(),
+        (source_url.clone(), source_key.clone()),
+        (dest_url.clone(), dest_key.clone()),
+        config.health_probe_interval_secs,
+        config.health_max_outage_secs,
+        token.clone(),
+        pause.clone(),
+    );
 
     // WIRE list_objects for each bucket
     for bucket in &buckets {
-        window.emit("log", format!("Processing bucket: {}", bucket.name)).unwrap();
+        pause.wait_while_paused(&token).await;
+        // Stop between buckets; the report below still records what was done
+        if token.is_cancelled() {
+            break;
+        }
+        let dest_bucket = mirror.dest_bucket(&bucket.id);
+        if dest_bucket == bucket.id {
+            window.emit("log", format!("Processing bucket: {}", bucket.name)).unwrap();
+        } else {
+            window.emit("log", format!("Processing bucket: {} -> {}", bucket.name, dest_bucket)).unwrap();
+        }
+        // Public flag, size limit and MIME allowlist decide how browsers can use the bucket
+        match mirror.mirror_bucket(bucket, dest_bucket_ids.contains(dest_bucket)).await {
+            Ok(true) => window.emit("log", format!("  Created destination bucket {}", dest_bucket)).unwrap(),
+            Ok(false) => window.emit("log", "  Bucket settings mirrored").unwrap(),
+            Err(e) => {
+                window.emit("log", format!("  WARNING: {}", e)).unwrap();
+                report.manual_steps.push(format!("Bucket {}: settings not mirrored ({}). Manual step required.", bucket.id, e));
+            }
+        }
+        let bucket_span = telemetry::TelemetrySpan::start(&window, "STORAGE_BUCKET", serde_json::json!({ "bucket": bucket.id }));
+        let mut bucket_report = report::BucketReport {
+            id: bucket.id.clone(),
+            name: bucket.name.clone(),
+            objects_total: 0,
+            objects_synced: 0,
+            objects_failed: 0,
+            error: None,
+            duration_ms: 0,
+        };
         
-        match mirror.list_objects(&bucket.id).await {
+        let listed = match mirror.list_objects(&bucket.id).await {
             Ok(objects) => {
+                // Content-addressed skip: both sides indexed with matching hashes
+                let source_index = bucket_index::load_index(window.app_handle(), &source_url, &bucket.id);
+                let dest_index = bucket_index::load_index(window.app_handle(), &dest_url, dest_bucket);
+
+                // Sizes already on the destination: confirm index hits still exist there,
+                // and feed the skip/rename collision strategies
+                let needs_dest_listing = (source_index.is_some() && dest_index.is_some())
+                    || !matches!(mirror.collision_strategy(), storage::CollisionStrategy::Overwrite);
+                let dest_sizes = if needs_dest_listing {
+                    dest_object_sizes(&mirror, dest_bucket, &|line| window.emit("log", line).unwrap()).await
+                } else {
+                    Ok(Default::default())
+                };
+                dest_sizes.map(|sizes| (objects, source_index, dest_index, sizes))
+            }
+            Err(e) => Err(e),
+        };
+
+        match listed {
+            Ok((objects, source_index, dest_index, dest_sizes)) => {
                 window.emit("log", format!("  Found {} objects", objects.len())).unwrap();
-                
-                // WIRE upload_object (structure demo - real impl would download first)
-                for obj in &objects {
-                    // In full implementation: 
-                    // 1. Download from source: mirror.download_object(&bucket.id, &obj.name)
-                    // 2. Upload to dest: mirror.upload_object(&bucket.id, &obj.name, data)
-                    window.emit("log", format!("  Synced: {}", obj.name)).unwrap();
-                    
-                    // Call upload_object to wire it (with empty data for now)
-                    let _ = mirror.upload_object(&bucket.id, &obj.name, vec![]).await;
+                bucket_report.objects_total = objects.len();
+
+                let (unchanged, pending): (Vec<usize>, Vec<usize>) = (0..objects.len()).partition(|&i| {
+                    let existing_size = dest_sizes.get(&objects[i].name).copied();
+                    bucket_index::is_unchanged(source_index.as_ref(), dest_index.as_ref(), &objects[i], existing_size)
+                });
+                if !unchanged.is_empty() {
+                    window.emit("log", format!("  Skipping {} objects with matching content hashes", unchanged.len())).unwrap();
+                }
+                let mut objects_done = unchanged.len();
+                let mut bytes_done: u64 = unchanged.iter().map(|&i| objects[i].size()).sum();
+                for i in unchanged {
+                    report.objects.push(report::ObjectOutcome {
+                        bucket: bucket.id.clone(),
+                        key: objects[i].name.clone(),
+                        status: "SKIPPED".to_string(),
+                        bytes: 0,
+                        error: None,
+                        duration_ms: 0,
+                        dest_key: None,
+                    });
+                }
+
+                // Started buckets show as running, so pausing can mark them in the checkpoint
+                let _ = checkpoint::update_bucket(window.app_handle(), &bucket.id, |p| {
+                    p.status = if pause.is_paused() { "PAUSED" } else { "IN_PROGRESS" }.to_string();
+                });
+                let log = |line: String| window.emit("log", line).unwrap();
+                let mut transfers = bucket_transfers(&mirror, &bucket.id, &objects, pending, &dest_sizes, &token, &pause, &log);
+
+                while let Some((obj, outcome, dest_key, duration_ms)) = transfers.next().await {
+                    let (status, bytes, error) = match outcome {
+                        Ok((bytes, _)) => {
+                            bucket_report.objects_synced += 1;
+                            objects_done += 1;
+                            bytes_done += bytes;
+                            ("SYNCED", bytes, None)
+                        }
+                        Err(e) if e == cancel::CANCELLED => ("CANCELLED", 0, Some(e)),
+                        Err(e) if e == storage::COLLISION_SKIPPED => {
+                            objects_done += 1;
+                            ("SKIPPED", 0, Some(e))
+                        }
+                        Err(e) => {
+                            bucket_report.objects_failed += 1;
+                            ("FAILED", 0, Some(e))
+                        }
+                    };
+                    report.objects.push(report::ObjectOutcome {
+                        bucket: bucket.id.clone(),
+                        key: obj.name.clone(),
+                        status: status.to_string(),
+                        bytes,
+                        error,
+                        duration_ms,
+                        dest_key,
+                    });
+
+                    if !rate_limit_warned {
+                        if let Some(limits) = [&source_url, &dest_url].into_iter().find_map(|url| rate_limits.near_limit(url, "storage")) {
+                            window.emit("log", format!(
+                                "WARNING: Approaching the storage rate limit ({} of {} requests left{}). Transfers may slow down; consider lowering transfer_concurrency.",
+                                limits.remaining.map(|r| r.to_string()).unwrap_or_else(|| "?".to_string()),
+                                limits.limit.map(|l| l.to_string()).unwrap_or_else(|| "?".to_string()),
+                                limits.retry_after_secs.map(|s| format!(", retry after {}s", s)).unwrap_or_default()
+                            )).unwrap();
+                            rate_limit_warned = true;
+                        }
+                    }
+
+                    // Periodic flush keeps the resume view current without a write per object;
+                    // while paused every finishing transfer is flushed
+                    let finished = bucket_report.objects_synced + bucket_report.objects_failed;
+                    if finished.is_multiple_of(PROGRESS_FLUSH_EVERY) || pause.is_paused() {
+                        let status = if pause.is_paused() { "PAUSED" } else { "IN_PROGRESS" };
+                        let _ = checkpoint::update_bucket(window.app_handle(), &bucket.id, |p| {
+                            p.status = status.to_string();
+                            p.objects_done = objects_done;
+                            p.bytes_done = bytes_done;
+                        });
+                    }
                 }
+
+                let status = if token.is_cancelled() {
+                    "CANCELLED"
+                } else if bucket_report.objects_failed > 0 {
+                    "PARTIAL"
+                } else {
+                    "DONE"
+                };
+                let _ = checkpoint::update_bucket(window.app_handle(), &bucket.id, |p| {
+                    p.status = status.to_string();
+                    p.objects_done = objects_done;
+                    p.bytes_done = bytes_done;
+                });
             },
             Err(e) => {
                 window.emit("log", format!("  Error listing objects: {}", e)).unwrap();
+                let _ = checkpoint::update_bucket(window.app_handle(), &bucket.id, |p| {
+                    p.status = "FAILED".to_string();
+                });
+                bucket_report.error = Some(e);
             }
         }
+
+        bucket_report.duration_ms = bucket_span.end(serde_json::json!({
+            "synced": bucket_report.objects_synced,
+            "failed": bucket_report.objects_failed,
+        }));
+        report.buckets.push(bucket_report);
+    }
+
+    let outage = health.and_then(|h| h.stop());
+    report.finished_at = report::now_secs();
+    report.duration_ms = run_span.end(serde_json::json!({ "objects_failed": report.failures().count() }));
+    if let Err(e) = report::save_last_report(window.app_handle(), &report) {
+        window.emit("log", format!("Could not save migration report: {}", e)).unwrap();
+    }
+    if let Some(e) = outage {
+        return Err(e);
+    }
+    token.check()?;
+
+    // Opt-in completeness pass: per-bucket counts, missing keys re-transferred once
+    if config.reconcile_after_migration {
+        let bucket_ids: Vec<String> = buckets.iter().map(|b| b.id.clone()).collect();
+        if let Err(e) = reconcile::reconcile_buckets(&window, &mirror, &bucket_ids, true).await {
+            window.emit("log", format!("Reconciliation skipped: {}", e)).unwrap();
+        }
     }
 
+    hooks::run_stage_hooks(&window, &client, &config.hooks, "STORAGE", "post").await?;
+
     // Track completion
     let complete_event = telemetry::TelemetryEvent::new(
         "MIGRATION_COMPLETE",
-        serde_json::json!({ "buckets_processed": buckets.len() })
+        serde_json::json!({
+            "buckets_processed": buckets.len(),
+            "objects_failed": report.failures().count()
+        })
     );
     telemetry::track_event(&window, complete_event);
 
@@ -197,6 +1859,44 @@ async fn perform_migration(
     Ok(format!("Migrated {} buckets", buckets.len()))
 }
 
+/// Saves a migration as a versioned JSON plan (credentials masked) for review or re-runs
+#[tauri::command]
+fn export_plan(app: tauri::AppHandle, plan: plan::MigrationPlan, out_path: String) -> Result<String, String> {
+    plan::export_plan(&app, plan, &out_path)
+}
+
+#[tauri::command]
+fn import_plan(in_path: String) -> Result<plan::MigrationPlan, String> {
+    plan::import_plan(&in_path)
+}
+
+/// Runs an imported plan once its keys have been filled back in
+#[tauri::command]
+async fn run_plan(window: Window, plan: plan::MigrationPlan) -> Result<String, String> {
+    if plan.source_key.is_empty() || plan.dest_key.is_empty() {
+        return Err("The plan's keys are masked. Provide the source and destination keys to run it.".to_string());
+    }
+    if plan.source_db_url.as_deref().is_some_and(|u| u.contains(plan::MASK)) {
+        return Err("The plan's database password is masked. Provide the database URL to run it.".to_string());
+    }
+    // The plan's own bucket selection and transfer settings, not whatever is saved now
+    let scope = MigrationScope {
+        buckets: Some(plan.buckets).filter(|b| !b.is_empty()),
+        bucket_remap: plan.bucket_remap,
+        performance: Some(plan.performance),
+        s3_enabled: Some(plan.s3_enabled),
+    };
+    let token = cancel::begin(window.app_handle(), "migration");
+    let pause = cancel::begin_pausable(window.app_handle(), "migration");
+    let result = migrate_storage(
+        window.clone(), token, pause, plan.source_url, plan.source_key, plan.dest_url, plan.dest_key,
+        Some(plan.allow_same_project), plan.source_db_url, Some(plan.confirm_egress), plan.require_empty_destination,
+        false, scope,
+    ).await;
+    cancel::finish(&window, "migration", &result);
+    result
+}
+
 #[tauri::command]
 async fn discover_local_databases(_window: Window) -> Result<Vec<String>, String> {
     Ok(vec!["localhost:5432".to_string()])
@@ -210,11 +1910,20 @@ struct ProgressEvent {
 
 #[tauri::command]
 async fn backup_database(window: Window, _url: String) -> Result<String, String> {
+    let token = cancel::begin(window.app_handle(), "backup");
+    let (task_window, task_token) = (window.clone(), token.clone());
+    let result = tasks::run_blocking(move || capture_backup(&task_window, &task_token)).await;
+    cancel::finish(&window, "backup", &result);
+    result
+}
+
+fn capture_backup(window: &Window, token: &cancel::CancelToken) -> Result<String, String> {
     window.emit("log", "Initializing Stasis Field...").unwrap();
 
     let stages = vec!["DATABASE", "STORAGE", "FUNCTIONS", "AUTH"];
 
     for stage in stages {
+        token.check()?;
         // 1. Emit RUNNING
         window
             .emit(
@@ -257,22 +1966,30 @@ async fn dry_run_migration(_window: Window, _script: String) -> Result<String, S
 
 #[tauri::command]
 async fn install_drivers(window: Window, app: tauri::AppHandle) -> Result<String, String> {
-    let mgr = deps::PulseManager::new(&app);
+    let token = cancel::begin(&app, "install");
+    let result = install_postgres_drivers(&window, &app, &token).await;
+    cancel::finish(&window, "install", &result);
+    result
+}
+
+async fn install_postgres_drivers(window: &Window, app: &tauri::AppHandle, token: &cancel::CancelToken) -> Result<String, String> {
+    let mgr = deps::PulseManager::new(app).with_cancel(token.clone());
     
     // PRIMARY: Manifest-based install (Orbital Depot)
     window.emit("log", "Connecting to Orbital Depot...").unwrap();
-    match mgr.install_latest(&window, "postgres-15").await {
+    match mgr.install_latest(window, "postgres-15").await {
         Ok(_) => {
             window.emit("log", "Drivers installed from Orbital Depot.").unwrap();
             return Ok("INSTALLED".to_string());
         }
         Err(manifest_err) => {
+            token.check()?;
             window.emit("log", format!("Manifest unavailable: {}. Trying GitHub fallback...", manifest_err)).unwrap();
         }
     }
-    
+
     // FALLBACK: Direct GitHub API (wires GitHubAsset, GitHubRelease)
-    match mgr.install_from_github(&window, "postgres-15", "devpulse-tools", "drivers").await {
+    match mgr.install_from_github(window, "postgres-15", "devpulse-tools", "drivers").await {
         Ok(_) => {
             window.emit("log", "Drivers installed via GitHub fallback.").unwrap();
             Ok("INSTALLED".to_string())
@@ -285,3 +2002,122 @@ async fn install_drivers(window: Window, app: tauri::AppHandle) -> Result<String
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{peak_heap, MockServer, Reply};
+    use std::time::Duration;
+
+    fn objects(count: usize, size: usize) -> Vec<storage::StorageObject> {
+        (0..count)
+            .map(|i| serde_json::from_value(serde_json::json!({ "name": format!("{}.bin", i), "id": i.to_string(), "metadata": { "size": size } })).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn paused_migration_starts_no_transfers_until_resumed() {
+        let source = MockServer::start(|_| Reply::bytes(200, b"hello".to_vec())).await;
+        let dest = MockServer::start(|_| Reply::json(200, serde_json::json!({}))).await;
+        let performance = deps::PerformanceConfig { transfer_concurrency: 2, ..Default::default() };
+        let mirror = storage::StorageMirror::new(reqwest::Client::new(), &source.url, "key", &dest.url, "key")
+            .with_performance(performance);
+        let objects = objects(6, 5);
+        let (token, pause) = (cancel::CancelToken::default(), cancel::PauseToken::default());
+        let dest_sizes = Default::default();
+        let downloads = || source.count("GET", "/storage/v1/object/docs/");
+
+        let mut transfers = bucket_transfers(&mirror, "docs", &objects, (0..6).collect(), &dest_sizes, &token, &pause, &|_| {});
+        for _ in 0..2 {
+            assert!(transfers.next().await.unwrap().1.is_ok());
+        }
+
+        // Transfers already started may finish; nothing new starts while paused
+        pause.set(true);
+        let mut finished = 2;
+        while let Ok(Some(result)) = tokio::time::timeout(Duration::from_secs(1), transfers.next()).await {
+            assert!(result.1.is_ok());
+            finished += 1;
+        }
+        let held_at = downloads();
+        assert!(finished <= 4 && held_at == finished, "{} finished, {} downloaded", finished, held_at);
+        assert!(tokio::time::timeout(Duration::from_millis(600), transfers.next()).await.is_err());
+        assert_eq!(downloads(), held_at);
+
+        pause.set(false);
+        while let Some(result) = transfers.next().await {
+            assert!(result.1.is_ok());
+            finished += 1;
+        }
+        assert_eq!((finished, downloads()), (6, 6));
+    }
+
+    #[test]
+    fn streaming_transfers_hold_bounded_memory_whatever_the_object_sizes() {
+        const OBJECT_BYTES: usize = 8 * 1024 * 1024;
+        // Below a single object: nothing may be held whole
+        const CEILING: usize = 6 * 1024 * 1024;
+        // The servers run on their own threads, so only the transfer side is counted
+        let servers = tokio::runtime::Runtime::new().unwrap();
+        let body = vec![7u8; OBJECT_BYTES];
+        let source = servers.block_on(MockServer::start(move |_| Reply::bytes(200, body.clone())));
+        let dest = servers.block_on(MockServer::start(|_| Reply::json(200, serde_json::json!({}))));
+        let objects = objects(12, OBJECT_BYTES);
+        let (token, pause, dest_sizes) = (cancel::CancelToken::default(), cancel::PauseToken::default(), Default::default());
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+        let peak = |performance: deps::PerformanceConfig| {
+            let mirror = storage::StorageMirror::new(reqwest::Client::new(), &source.url, "key", &dest.url, "key")
+                .with_performance(performance);
+            let ((), peak) = peak_heap(|| {
+                runtime.block_on(async {
+                    let mut transfers = bucket_transfers(&mirror, "docs", &objects, (0..objects.len()).collect(), &dest_sizes, &token, &pause, &|_| {});
+                    while let Some((_, outcome, _, _)) = transfers.next().await {
+                        assert_eq!(outcome.unwrap().0, OBJECT_BYTES as u64);
+                    }
+                })
+            });
+            peak
+        };
+
+        let defaults = deps::PerformanceConfig::default();
+        assert!(defaults.streaming_transfers);
+        let streamed = peak(defaults.clone());
+        let buffered = peak(deps::PerformanceConfig { streaming_transfers: false, ..defaults });
+        assert_eq!(dest.requests().len(), 2 * objects.len());
+        assert!(streamed < CEILING, "streaming peaked at {} bytes", streamed);
+        // The meter sees whole objects when they are buffered
+        assert!(buffered >= CEILING, "buffering peaked at only {} bytes", buffered);
+    }
+
+    #[tokio::test]
+    async fn failed_destination_listing_fails_strategies_that_need_it() {
+        let dest = MockServer::start(|_| Reply::json(500, serde_json::json!({ "message": "listing broke" }))).await;
+        let mirror = |strategy| {
+            storage::StorageMirror::new(reqwest::Client::new(), &dest.url, "key", &dest.url, "key").with_collision_strategy(strategy)
+        };
+
+        for strategy in [storage::CollisionStrategy::Skip, storage::CollisionStrategy::Rename] {
+            let error = dest_object_sizes(&mirror(strategy), "docs", &|_| {}).await.unwrap_err();
+            assert!(error.contains("listing broke"), "{:?}: {}", strategy, error);
+        }
+        let warned = std::sync::Mutex::new(vec![]);
+        let sizes = dest_object_sizes(&mirror(storage::CollisionStrategy::Overwrite), "docs", &|line| warned.lock().unwrap().push(line))
+            .await
+            .unwrap();
+        assert!(sizes.is_empty());
+        assert_eq!(warned.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn destination_listing_gives_sizes_by_key() {
+        let dest = MockServer::start(|_| {
+            Reply::json(200, serde_json::json!([{ "name": "a.txt", "id": "1", "metadata": { "size": 3 } }]))
+        })
+        .await;
+        let mirror = storage::StorageMirror::new(reqwest::Client::new(), &dest.url, "key", &dest.url, "key")
+            .with_collision_strategy(storage::CollisionStrategy::Skip);
+        let sizes = dest_object_sizes(&mirror, "docs", &|_| {}).await.unwrap();
+        assert_eq!(sizes, std::collections::HashMap::from([("a.txt".to_string(), 3)]));
+    }
+}
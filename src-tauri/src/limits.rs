@@ -0,0 +1,412 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager, Window};
+
+use crate::db::{quote_ident, Psql};
+use crate::paths;
+use crate::storage::StorageMirror;
+
+/// Fraction of the quota at which a destination counts as near its limit
+const NEAR_LIMIT_RATIO: f64 = 0.9;
+
+/// Storage usage vs quota for a project. Fields are None when unknown.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ProjectLimits {
+    pub storage_used_bytes: Option<u64>,
+    pub storage_limit_bytes: Option<u64>,
+    pub near_limit: bool,
+    pub note: String,
+}
+
+/// Totals listed object sizes on the destination and compares them (plus
+/// any incoming bytes) with the configured quota. Supabase exposes no quota
+/// endpoint to service keys, so the limit comes from `storage_quota_bytes`.
+pub async fn probe_project_limits(
+    mirror: &StorageMirror,
+    quota_bytes: Option<u64>,
+    incoming_bytes: u64,
+) -> ProjectLimits {
+    let used = match mirror.list_dest_buckets().await {
+        Ok(buckets) => {
+            let mut total = Some(0u64);
+            for bucket in &buckets {
+                match mirror.list_dest_objects(&bucket.id).await {
+                    Ok(objects) => {
+                        total = total.map(|t| t + objects.iter().map(|o| o.size()).sum::<u64>());
+                    }
+                    Err(_) => {
+                        total = None;
+                        break;
+                    }
+                }
+            }
+            total
+        }
+        Err(_) => None,
+    };
+
+    let (near_limit, note) = match (used, quota_bytes) {
+        (Some(used), Some(limit)) => {
+            let projected = used + incoming_bytes;
+            let near = projected as f64 >= limit as f64 * NEAR_LIMIT_RATIO;
+            let note = if projected > limit {
+                format!("Projected usage {} B exceeds the {} B quota.", projected, limit)
+            } else if near {
+                format!("Projected usage {} B is within 10% of the {} B quota.", projected, limit)
+            } else {
+                "Within quota.".to_string()
+            };
+            (near, note)
+        }
+        (Some(_), None) => (false, "Quota unknown. Set storage_quota_bytes in config to enable the check.".to_string()),
+        (None, _) => (false, "Storage usage unknown: destination listing not accessible.".to_string()),
+    };
+
+    ProjectLimits {
+        storage_used_bytes: used,
+        storage_limit_bytes: quota_bytes,
+        near_limit,
+        note,
+    }
+}
+
+/// Bytes a migration would read from the source project. Informational only:
+/// Supabase pricing isn't queried, the total just gates large jobs.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct EgressEstimate {
+    pub total_bytes: u64,
+    pub estimated_gb: f64,
+    pub objects: usize,
+    pub buckets: usize,
+    pub threshold_bytes: u64, // 0 = no confirmation required
+    pub needs_confirmation: bool,
+    pub warning: Option<String>,
+}
+
+pub fn egress_estimate(total_bytes: u64, objects: usize, buckets: usize, threshold_bytes: u64) -> EgressEstimate {
+    let estimated_gb = total_bytes as f64 / 1_000_000_000.0;
+    let needs_confirmation = threshold_bytes > 0 && total_bytes > threshold_bytes;
+    EgressEstimate {
+        total_bytes,
+        estimated_gb,
+        objects,
+        buckets,
+        threshold_bytes,
+        needs_confirmation,
+        warning: needs_confirmation.then(|| {
+            format!(
+                "This migration reads about {:.1} GB from the source project, which may incur egress charges. Confirm before starting.",
+                estimated_gb
+            )
+        }),
+    }
+}
+
+/// Totals the source listing (cached on the mirror, so a following migration reuses it)
+pub async fn estimate_egress(mirror: &StorageMirror, threshold_bytes: u64) -> Result<EgressEstimate, String> {
+    let buckets = mirror.list_source_buckets().await?;
+    let (mut total_bytes, mut objects) = (0u64, 0usize);
+    for bucket in &buckets {
+        let listing = mirror.list_objects(&bucket.id).await?;
+        objects += listing.len();
+        total_bytes += listing.iter().map(|o| o.size()).sum::<u64>();
+    }
+    Ok(egress_estimate(total_bytes, objects, buckets.len(), threshold_bytes))
+}
+
+/// Whether the destination can take a given bucket
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct BucketTargetCheck {
+    pub exists: bool,
+    pub created: bool,
+    pub writable: bool,
+    pub enough_space: Option<bool>, // None when the quota or usage is unknown
+}
+
+impl BucketTargetCheck {
+    /// Why a single-bucket migration must not start, None when it may
+    pub fn refusal(&self, bucket_id: &str) -> Option<String> {
+        if !self.exists {
+            Some(format!("Destination bucket {} is missing and could not be created.", bucket_id))
+        } else if !self.writable {
+            Some(format!("Destination bucket {} does not accept writes with this key.", bucket_id))
+        } else if self.enough_space == Some(false) {
+            Some(format!("Bucket {} does not fit in the destination's remaining storage quota.", bucket_id))
+        } else {
+            None
+        }
+    }
+}
+
+/// Per-bucket preflight: ensures the destination bucket exists (creating it
+/// private if missing), proves write access with a throwaway object, and
+/// compares `estimated_bytes` against the remaining quota when known.
+pub async fn check_bucket_target(
+    window: &Window,
+    mirror: &StorageMirror,
+    bucket_id: &str,
+    quota_bytes: Option<u64>,
+    estimated_bytes: u64,
+) -> BucketTargetCheck {
+    let log = |msg: String| window.emit("log", msg).unwrap();
+
+    let existed = match mirror.list_dest_buckets().await {
+        Ok(buckets) => buckets.iter().any(|b| b.id == bucket_id),
+        Err(e) => {
+            log(format!("  Bucket lookup failed: {}", e));
+            false
+        }
+    };
+    let created = !existed && {
+        match mirror.create_dest_bucket(bucket_id, false).await {
+            Ok(()) => true,
+            Err(e) => {
+                log(format!("  Could not create bucket {}: {}", bucket_id, e));
+                false
+            }
+        }
+    };
+    let exists = existed || created;
+    log(format!(
+        "  Bucket {}: {}",
+        bucket_id,
+        if existed { "exists" } else if created { "created" } else { "MISSING" }
+    ));
+
+    let writable = exists && {
+        let probe = format!(".devpulse-write-probe-{}", crate::report::now_secs());
+        match mirror.upload_object(bucket_id, &probe, b"ok".to_vec(), "text/plain").await {
+            Ok(()) => {
+                let _ = mirror.delete_dest_objects(bucket_id, &[probe]).await;
+                true
+            }
+            Err(e) => {
+                log(format!("  Write check failed: {}", e));
+                false
+            }
+        }
+    };
+    log(format!("  Writable: {}", if writable { "yes" } else { "NO" }));
+
+    let limits = probe_project_limits(mirror, quota_bytes, estimated_bytes).await;
+    let enough_space = match (limits.storage_used_bytes, quota_bytes) {
+        (Some(used), Some(limit)) => Some(used + estimated_bytes <= limit),
+        _ => None,
+    };
+    log(format!("  Space: {}", limits.note));
+
+    BucketTargetCheck {
+        exists,
+        created,
+        writable,
+        enough_space,
+    }
+}
+
+/// Whether the destination is a clean slate. `determined` is false when a
+/// bucket or table couldn't be checked, so `empty` alone can't be trusted.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct DestinationEmptiness {
+    pub empty: bool,
+    pub determined: bool,
+    pub non_empty_buckets: Vec<String>,
+    pub non_empty_tables: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl DestinationEmptiness {
+    /// Error for a migration that requires an empty destination, None when it may proceed
+    pub fn refusal(&self) -> Option<String> {
+        if !self.non_empty_buckets.is_empty() || !self.non_empty_tables.is_empty() {
+            let mut found = self.non_empty_buckets.iter().map(|b| format!("bucket {}", b)).collect::<Vec<_>>();
+            found.extend(self.non_empty_tables.iter().map(|t| format!("table {}", t)));
+            Some(format!("Destination is not empty ({}). Refusing a clean import.", found.join(", ")))
+        } else if !self.determined {
+            Some(format!("Could not confirm the destination is empty: {}", self.errors.join("; ")))
+        } else {
+            None
+        }
+    }
+}
+
+/// Checks every destination bucket (and optionally `tables` in the
+/// destination database, as "schema.table" or "table") for existing content
+pub async fn assert_destination_empty(
+    app: &AppHandle,
+    mirror: &StorageMirror,
+    dest_db: Option<&str>,
+    tables: &[String],
+) -> DestinationEmptiness {
+    let mut result = DestinationEmptiness {
+        empty: false,
+        determined: true,
+        non_empty_buckets: vec![],
+        non_empty_tables: vec![],
+        errors: vec![],
+    };
+
+    match mirror.list_dest_buckets().await {
+        Ok(buckets) => {
+            for bucket in &buckets {
+                match mirror.dest_bucket_is_empty(&bucket.id).await {
+                    Ok(true) => {}
+                    Ok(false) => result.non_empty_buckets.push(bucket.id.clone()),
+                    Err(e) => result.errors.push(e),
+                }
+            }
+        }
+        Err(e) => result.errors.push(format!("Cannot list destination buckets: {}", e)),
+    }
+
+    if let (Some(dest_db), false) = (dest_db, tables.is_empty()) {
+        match Psql::locate(app) {
+            Ok(psql) => {
+                for table in tables {
+                    let qualified = table.split('.').map(quote_ident).collect::<Vec<_>>().join(".");
+                    let sql = format!("SELECT EXISTS (SELECT 1 FROM {})", qualified);
+                    match psql.query(dest_db, &sql).await {
+                        Ok(rows) if rows.first().and_then(|r| r.first()).is_some_and(|v| v == "t") => {
+                            result.non_empty_tables.push(table.clone())
+                        }
+                        Ok(_) => {}
+                        Err(e) => result.errors.push(format!("Table {}: {}", table, e)),
+                    }
+                }
+            }
+            Err(e) => result.errors.push(e),
+        }
+    }
+
+    result.determined = result.errors.is_empty();
+    result.empty = result.determined && result.non_empty_buckets.is_empty() && result.non_empty_tables.is_empty();
+    result
+}
+
+/// Upload probes start here and double until one is rejected
+const PROBE_FLOOR_BYTES: u64 = 1024 * 1024;
+/// Largest probe sent; a destination taking this much is treated as unlimited
+const PROBE_CEILING_BYTES: u64 = 256 * 1024 * 1024;
+/// The binary search stops once the bounds are this close
+const PROBE_PRECISION_BYTES: u64 = 1024 * 1024;
+
+/// Largest single-POST upload a destination accepts
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct UploadLimit {
+    pub max_single_upload_bytes: u64,
+    pub at_least: bool, // every probe up to the ceiling succeeded; the real limit may be higher
+    #[serde(default)]
+    pub bucket_limit_bytes: Option<u64>, // the bucket's own file_size_limit, which caps resumable uploads too
+    #[serde(default)]
+    pub cached: bool,
+    #[serde(default)]
+    pub probes: usize,
+}
+
+/// Destination URL -> probed limit, cached in userdata/upload_limits.json
+fn upload_limits_path(app: &AppHandle) -> PathBuf {
+    paths::get_userdata_dir(app).join("upload_limits.json")
+}
+
+fn load_upload_limits(app: &AppHandle) -> BTreeMap<String, UploadLimit> {
+    fs::read_to_string(upload_limits_path(app))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// The cached single-POST limit for a destination, when one was found.
+/// Objects above it are uploaded through the resumable endpoint.
+pub fn cached_upload_limit(app: &AppHandle, dest_url: &str) -> Option<u64> {
+    load_upload_limits(app)
+        .get(dest_url)
+        .filter(|l| !l.at_least)
+        .map(|l| l.max_single_upload_bytes)
+}
+
+/// Finds the largest single POST `bucket_id` on the destination accepts by
+/// uploading zero-filled probes: doubling from 1 MiB until one is rejected
+/// (413), then binary-searching to 1 MiB. The project-wide result is cached
+/// per destination; `refresh` probes again.
+pub async fn probe_upload_limit(
+    window: &Window,
+    mirror: &StorageMirror,
+    dest_url: &str,
+    bucket_id: &str,
+    refresh: bool,
+) -> Result<UploadLimit, String> {
+    let app = window.app_handle();
+    let bucket_limit_bytes = mirror
+        .list_dest_buckets()
+        .await?
+        .into_iter()
+        .find(|b| b.id == bucket_id)
+        .ok_or_else(|| format!("Bucket {} does not exist on the destination", bucket_id))?
+        .file_size_limit
+        .filter(|l| *l > 0);
+
+    let mut cache = load_upload_limits(app);
+    if let Some(limit) = cache.get(dest_url).filter(|_| !refresh) {
+        return Ok(UploadLimit { bucket_limit_bytes, cached: true, probes: 0, ..limit.clone() });
+    }
+
+    let probe_name = format!(".devpulse-size-probe-{}", crate::report::now_secs());
+    let ceiling = bucket_limit_bytes.map_or(PROBE_CEILING_BYTES, |l| l.min(PROBE_CEILING_BYTES));
+    let mut probes = 0;
+    let (mut accepted, mut rejected) = (0u64, None);
+    let mut size = PROBE_FLOOR_BYTES.min(ceiling);
+    loop {
+        probes += 1;
+        window.emit("log", format!("  Probing a {} byte upload...", size)).unwrap();
+        if mirror.probe_upload(bucket_id, &probe_name, size).await? {
+            accepted = size;
+            if size >= ceiling {
+                break;
+            }
+            size = (size * 2).min(ceiling);
+        } else {
+            rejected = Some(size);
+            break;
+        }
+    }
+    if let Some(mut high) = rejected {
+        while high - accepted > PROBE_PRECISION_BYTES {
+            let mid = accepted + (high - accepted) / 2;
+            probes += 1;
+            if mirror.probe_upload(bucket_id, &probe_name, mid).await? {
+                accepted = mid;
+            } else {
+                high = mid;
+            }
+        }
+    }
+
+    let limit = UploadLimit {
+        max_single_upload_bytes: accepted,
+        at_least: rejected.is_none(),
+        bucket_limit_bytes,
+        cached: false,
+        probes,
+    };
+    // A rejection at the bucket's own limit says nothing about the project's
+    let bucket_bound = bucket_limit_bytes.is_some_and(|l| l <= PROBE_CEILING_BYTES);
+    if !bucket_bound {
+        cache.insert(dest_url.to_string(), limit.clone());
+        if let Ok(data) = serde_json::to_string_pretty(&cache) {
+            let _ = fs::create_dir_all(paths::get_userdata_dir(app));
+            let _ = fs::write(upload_limits_path(app), data);
+        }
+    }
+
+    window.emit("log", format!(
+        "Upload limit: single POSTs up to {}{} bytes{}",
+        if limit.at_least { "at least " } else { "" },
+        limit.max_single_upload_bytes,
+        match bucket_limit_bytes {
+            Some(l) => format!(" (bucket {} caps objects at {} bytes)", bucket_id, l),
+            None if !limit.at_least => "; larger objects will use resumable uploads".to_string(),
+            None => String::new(),
+        }
+    )).unwrap();
+    Ok(limit)
+}
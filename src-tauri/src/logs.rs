@@ -0,0 +1,141 @@
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, EventTarget, Listener, Manager, Window};
+
+use crate::paths;
+
+/// Path of the log file for the running session (managed state)
+pub struct SessionLog {
+    pub path: PathBuf,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct LogFileInfo {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub modified: u64, // unix seconds
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Opens a new session log and mirrors every "log" event into it
+pub fn init_session_log(app: &AppHandle) {
+    let path = paths::get_logs_dir(app).join(format!("session-{}.log", now_secs()));
+    app.manage(SessionLog { path: path.clone() });
+
+    app.listen_any("log", move |event| {
+        // Payloads arrive JSON-encoded; unwrap plain strings for readability
+        let line = serde_json::from_str::<String>(event.payload())
+            .unwrap_or_else(|_| event.payload().to_string());
+        if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+            let _ = writeln!(file, "[{}] {}", now_secs(), line);
+        }
+    });
+}
+
+/// Events kept for replay into reloaded or newly opened windows
+const BUFFERED_EVENTS: [&str; 2] = ["log", "progress_update"];
+
+/// Most recent events kept; older ones are dropped
+const LOG_BUFFER_CAPACITY: usize = 1000;
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct BufferedEvent {
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub at: u64, // unix seconds
+}
+
+/// Bounded history of recent log/progress events (managed state)
+#[derive(Default)]
+pub struct LogBuffer(Mutex<VecDeque<BufferedEvent>>);
+
+/// Starts recording log/progress events into the ring buffer
+pub fn init_log_buffer(app: &AppHandle) {
+    app.manage(LogBuffer::default());
+    for name in BUFFERED_EVENTS {
+        let app_handle = app.clone();
+        app.listen_any(name, move |event| {
+            let payload = serde_json::from_str(event.payload()).unwrap_or_else(|_| event.payload().into());
+            let buffer = app_handle.state::<LogBuffer>();
+            let mut events = buffer.0.lock().unwrap();
+            if events.len() == LOG_BUFFER_CAPACITY {
+                events.pop_front();
+            }
+            events.push_back(BufferedEvent {
+                event: name.to_string(),
+                payload,
+                at: now_secs(),
+            });
+        });
+    }
+}
+
+/// Sends the buffered history to one window as a single `log_replay` event.
+/// A separate event name keeps the replay out of the session log and buffer.
+pub fn replay_log_buffer(window: &Window) -> Result<usize, String> {
+    let events: Vec<BufferedEvent> = window.state::<LogBuffer>().0.lock().unwrap().iter().cloned().collect();
+    let count = events.len();
+    window
+        .emit_to(EventTarget::window(window.label()), "log_replay", events)
+        .map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+/// Returns the last `lines` lines of the current session log.
+/// A missing log file is treated as an empty log.
+pub fn read_log_tail(app: &AppHandle, lines: usize) -> Result<Vec<String>, String> {
+    let path = &app.state::<SessionLog>().path;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let all: Vec<&str> = data.lines().collect();
+    let start = all.len().saturating_sub(lines);
+    Ok(all[start..].iter().map(|l| l.to_string()).collect())
+}
+
+/// Lists log files in the logs directory, newest first
+pub fn list_log_files(app: &AppHandle) -> Result<Vec<LogFileInfo>, String> {
+    let logs_dir = paths::get_logs_dir(app);
+    if !logs_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut files = vec![];
+    for entry in fs::read_dir(&logs_dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let meta = entry.metadata().map_err(|e| e.to_string())?;
+        if !meta.is_file() {
+            continue;
+        }
+
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        files.push(LogFileInfo {
+            name: entry.file_name().to_string_lossy().to_string(),
+            path: entry.path().to_string_lossy().to_string(),
+            size_bytes: meta.len(),
+            modified,
+        });
+    }
+
+    files.sort_by_key(|f| std::cmp::Reverse(f.modified));
+    Ok(files)
+}
@@ -0,0 +1,73 @@
+use serde_json::Value;
+
+/// Contract for depot manifests. Bump the file's `$id` version together with
+/// `PulseManifest` whenever the layout changes.
+const SCHEMA: &str = include_str!("pulse_manifest.schema.json");
+
+/// Validates a manifest against the bundled schema. Errors are path-annotated,
+/// e.g. `packages.win32-x64.checksum: "abc" does not match "^[0-9a-fA-F]{64}$"`.
+pub fn validate_manifest(manifest: &Value) -> Vec<String> {
+    let schema: Value = serde_json::from_str(SCHEMA).expect("bundled manifest schema is valid JSON");
+    let validator = jsonschema::validator_for(&schema).expect("bundled manifest schema compiles");
+    validator
+        .iter_errors(manifest)
+        .map(|error| {
+            let path: Vec<String> = error.instance_path.into_iter().map(|segment| segment.to_string()).collect();
+            let at = if path.is_empty() { "(root)".to_string() } else { path.join(".") };
+            format!("{}: {}", at, error)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> Value {
+        serde_json::json!({
+            "tool": "postgres",
+            "channels": { "stable": { "version": "16.2", "required": false } },
+            "packages": {
+                "postgres-16": {
+                    "win32-x64": {
+                        "url": "https://github.com/devpulse/depot/releases/download/pg16/win.zip",
+                        "checksum": "a".repeat(64),
+                        "size_mb": 42.5
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn valid_manifests_pass() {
+        assert_eq!(validate_manifest(&manifest()), Vec::<String>::new());
+
+        // Older flat layout: OS keys straight under packages
+        let mut flat = manifest();
+        flat["packages"] = flat["packages"]["postgres-16"].clone();
+        flat["pulse_rollout"] = serde_json::json!({ "id": "r1", "type": "toast", "title": "Hi", "message": "New depot" });
+        assert_eq!(validate_manifest(&flat), Vec::<String>::new());
+    }
+
+    #[test]
+    fn violations_are_reported_with_their_path() {
+        let mut bad = manifest();
+        bad["packages"]["postgres-16"]["win32-x64"]["checksum"] = "abc".into();
+        bad["channels"]["stable"]["version"] = "latest".into();
+        bad.as_object_mut().unwrap().remove("tool");
+
+        let errors = validate_manifest(&bad);
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.starts_with("(root): ") && e.contains("\"tool\"")), "{:?}", errors);
+        assert!(errors.iter().any(|e| e.starts_with("channels.stable.version: ")), "{:?}", errors);
+        assert!(errors.iter().any(|e| e.starts_with("packages.postgres-16: ")), "{:?}", errors);
+    }
+
+    #[test]
+    fn wrong_types_and_empty_packages_are_rejected() {
+        let errors = validate_manifest(&serde_json::json!({ "tool": "", "packages": {} }));
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(validate_manifest(&serde_json::json!([])).iter().all(|e| e.starts_with("(root): ")));
+    }
+}
@@ -0,0 +1,150 @@
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, EventId, Listener, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, watch};
+
+use crate::deps;
+
+/// Events mirrored to subscribers, as emitted to the webview
+const MIRRORED_EVENTS: [&str; 5] = ["log", "progress_update", "stage_hook", "db_sync_table", "backup_tick"];
+const TOKEN_LEN: usize = 32;
+/// Buffered events per subscriber before slow readers start missing some
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Where an external dashboard can subscribe (Server-Sent Events)
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct MonitorInfo {
+    pub port: u16,
+    pub token: String,
+    pub url: String,
+}
+
+struct RunningMonitor {
+    info: MonitorInfo,
+    shutdown: watch::Sender<bool>,
+    listeners: Vec<EventId>,
+}
+
+/// The live log endpoint for this session, if one is running (managed state)
+#[derive(Default)]
+pub struct LogMonitor(Mutex<Option<RunningMonitor>>);
+
+/// Binds 127.0.0.1:`port` and mirrors log/progress events to every client
+/// presenting the session token. Returns the running endpoint if already up.
+pub async fn start(app: &AppHandle, port: Option<u16>) -> Result<MonitorInfo, String> {
+    if let Some(running) = app.state::<LogMonitor>().0.lock().unwrap().as_ref() {
+        return Ok(running.info.clone());
+    }
+
+    let port = port.unwrap_or_else(|| deps::load_config(app).monitor_port);
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Cannot bind log monitor on 127.0.0.1:{}: {}", port, e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let token: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LEN)
+        .map(char::from)
+        .collect();
+    let info = MonitorInfo {
+        port,
+        url: format!("http://127.0.0.1:{}/events?token={}", port, token),
+        token: token.clone(),
+    };
+
+    let (events, _) = broadcast::channel::<String>(CHANNEL_CAPACITY);
+    let listeners = MIRRORED_EVENTS
+        .iter()
+        .map(|name| {
+            let events = events.clone();
+            app.listen_any(*name, move |event| {
+                let payload: serde_json::Value = serde_json::from_str(event.payload())
+                    .unwrap_or_else(|_| event.payload().into());
+                let line = serde_json::json!({ "event": name, "payload": payload });
+                // No subscribers is fine; the event is simply dropped
+                let _ = events.send(line.to_string());
+            })
+        })
+        .collect();
+
+    let (shutdown, mut shutdown_rx) = watch::channel(false);
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.changed() => break,
+                accepted = listener.accept() => {
+                    if let Ok((stream, _)) = accepted {
+                        tauri::async_runtime::spawn(serve(stream, token.clone(), events.subscribe()));
+                    }
+                }
+            }
+        }
+    });
+
+    let _ = app.emit("log", format!("Log monitor listening on 127.0.0.1:{}", port));
+    *app.state::<LogMonitor>().0.lock().unwrap() = Some(RunningMonitor {
+        info: info.clone(),
+        shutdown,
+        listeners,
+    });
+    Ok(info)
+}
+
+/// Stops the listener and disconnects subscribers. No-op when not running.
+pub fn stop(app: &AppHandle) {
+    let running = app.state::<LogMonitor>().0.lock().unwrap().take();
+    if let Some(running) = running {
+        // Unlistening drops the last senders, which ends every subscriber stream
+        for id in running.listeners {
+            app.unlisten(id);
+        }
+        let _ = running.shutdown.send(true);
+    }
+}
+
+/// Handles one subscriber: checks the token, then streams events as SSE
+async fn serve(mut stream: TcpStream, token: String, mut events: broadcast::Receiver<String>) {
+    let mut head = vec![0u8; 4096];
+    let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut head)).await;
+    let n = match read {
+        Ok(Ok(n)) if n > 0 => n,
+        _ => return,
+    };
+    let request = String::from_utf8_lossy(&head[..n]);
+    let target = request.split_whitespace().nth(1).unwrap_or("");
+
+    let authorized = target
+        .split_once('?')
+        .filter(|(path, _)| *path == "/events")
+        .map(|(_, query)| query.split('&').any(|pair| pair == format!("token={}", token)))
+        .unwrap_or(false);
+    if !authorized {
+        let _ = stream
+            .write_all(b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await;
+        return;
+    }
+
+    let headers = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+    if stream.write_all(headers.as_bytes()).await.is_err() {
+        return;
+    }
+
+    loop {
+        let line = match events.recv().await {
+            Ok(line) => line,
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                serde_json::json!({ "event": "lagged", "payload": missed }).to_string()
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        if stream.write_all(format!("data: {}\n\n", line).as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
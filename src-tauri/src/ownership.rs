@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter, Window};
+
+use crate::db::{quote_literal, Psql};
+
+/// Objects updated per statement
+const OWNER_BATCH_SIZE: usize = 200;
+
+/// An object whose owner could not be carried over
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct OwnershipIssue {
+    pub bucket_id: String,
+    pub key: String,
+    pub owner: String,
+    pub reason: String,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct OwnershipReport {
+    pub objects_with_owner: usize,
+    pub preserved: usize,
+    pub not_preserved: Vec<OwnershipIssue>,
+}
+
+/// Owner column of storage.objects: `owner_id` (text) on current projects,
+/// only the deprecated `owner` (uuid) on old ones
+async fn owner_columns(psql: &Psql, conn: &str) -> Result<HashSet<String>, String> {
+    Ok(psql
+        .query(
+            conn,
+            "SELECT column_name FROM information_schema.columns \
+             WHERE table_schema = 'storage' AND table_name = 'objects' AND column_name IN ('owner', 'owner_id')",
+        )
+        .await?
+        .into_iter()
+        .filter_map(|r| r.into_iter().next())
+        .collect())
+}
+
+/// Copies object ownership from the source's storage.objects to the
+/// destination's. The Storage API uploads everything as the service role,
+/// which orphans files for owner-scoped RLS policies; this sets the owner
+/// back on objects that were copied and whose user exists on the destination
+/// (run it after migrating users). Limited to `bucket_id` when given.
+pub async fn migrate_object_owners(
+    window: &Window,
+    app: &AppHandle,
+    source_db: &str,
+    dest_db: &str,
+    bucket_id: Option<&str>,
+) -> Result<OwnershipReport, String> {
+    let psql = Psql::locate(app)?;
+
+    let source_columns = owner_columns(&psql, source_db).await?;
+    let owner_expr = if source_columns.contains("owner_id") {
+        "COALESCE(owner_id, owner::text)"
+    } else if source_columns.contains("owner") {
+        "owner::text"
+    } else {
+        return Err("Source storage.objects has no owner column.".to_string());
+    };
+    let bucket_filter = bucket_id.map(|b| format!(" AND bucket_id = {}", quote_literal(b))).unwrap_or_default();
+    let owned: Vec<(String, String, String)> = psql
+        .query(
+            source_db,
+            &format!(
+                "SELECT bucket_id, name, {0} FROM storage.objects WHERE {0} IS NOT NULL{1} ORDER BY 1, 2",
+                owner_expr, bucket_filter
+            ),
+        )
+        .await?
+        .into_iter()
+        .filter(|r| r.len() == 3)
+        .map(|r| (r[0].clone(), r[1].clone(), r[2].clone()))
+        .collect();
+    window.emit("log", format!("Ownership: {} source objects have an owner", owned.len())).unwrap();
+
+    let dest_users: HashSet<String> = psql
+        .query(dest_db, "SELECT id::text FROM auth.users")
+        .await?
+        .into_iter()
+        .filter_map(|r| r.into_iter().next())
+        .collect();
+    let dest_columns = owner_columns(&psql, dest_db).await?;
+    let mut assignments = vec![];
+    if dest_columns.contains("owner_id") {
+        assignments.push("owner_id = v.owner");
+    }
+    if dest_columns.contains("owner") {
+        assignments.push("owner = v.owner::uuid");
+    }
+    if assignments.is_empty() {
+        return Err("Destination storage.objects has no owner column.".to_string());
+    }
+
+    let mut report = OwnershipReport {
+        objects_with_owner: owned.len(),
+        preserved: 0,
+        not_preserved: vec![],
+    };
+    let issue = |(bucket_id, key, owner): &(String, String, String), reason: &str| OwnershipIssue {
+        bucket_id: bucket_id.clone(),
+        key: key.clone(),
+        owner: owner.clone(),
+        reason: reason.to_string(),
+    };
+    let (known, unknown): (Vec<_>, Vec<_>) = owned.iter().partition(|o| dest_users.contains(&o.2));
+    for object in unknown {
+        report.not_preserved.push(issue(object, "Owner is not a user on the destination; migrate users first."));
+    }
+
+    for batch in known.chunks(OWNER_BATCH_SIZE) {
+        let values = batch
+            .iter()
+            .map(|(b, k, o)| format!("({}, {}, {})", quote_literal(b), quote_literal(k), quote_literal(o)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let update = format!(
+            "UPDATE storage.objects o SET {} FROM (VALUES {}) AS v(bucket_id, name, owner) \
+             WHERE o.bucket_id = v.bucket_id AND o.name = v.name RETURNING o.bucket_id, o.name",
+            assignments.join(", "),
+            values
+        );
+        match psql.query(dest_db, &update).await {
+            Ok(rows) => {
+                let updated: HashSet<(String, String)> = rows
+                    .into_iter()
+                    .filter(|r| r.len() == 2)
+                    .map(|r| (r[0].clone(), r[1].clone()))
+                    .collect();
+                for object in batch {
+                    if updated.contains(&(object.0.clone(), object.1.clone())) {
+                        report.preserved += 1;
+                    } else {
+                        report.not_preserved.push(issue(object, "Object not found on the destination."));
+                    }
+                }
+            }
+            Err(e) => {
+                for object in batch {
+                    report.not_preserved.push(issue(object, &format!("Update failed: {}", e)));
+                }
+            }
+        }
+    }
+
+    window.emit("log", format!(
+        "Ownership: {} of {} owners preserved, {} could not be",
+        report.preserved,
+        report.objects_with_owner,
+        report.not_preserved.len()
+    )).unwrap();
+    Ok(report)
+}
@@ -0,0 +1,78 @@
+use regex::Regex;
+use tauri::AppHandle;
+
+use crate::db::{self, Psql};
+
+/// Whether end-user access to a bucket's objects depends on RLS policies
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct BucketPolicyCheck {
+    pub bucket: String,
+    pub has_custom_policies: bool,
+    pub policies: Vec<String>,
+}
+
+struct ObjectPolicy {
+    name: String,
+    roles: String,
+    expression: String, // USING and WITH CHECK, concatenated
+}
+
+/// Expressions that tie access to the caller's identity
+const CLAIM_MARKERS: [&str; 4] = ["auth.uid()", "auth.jwt()", "auth.role()", "request.jwt.claims"];
+
+impl ObjectPolicy {
+    fn references_roles_or_claims(&self) -> bool {
+        let roles_restricted = self.roles.split(',').any(|r| !r.is_empty() && r != "public");
+        roles_restricted || CLAIM_MARKERS.iter().any(|m| self.expression.contains(m))
+    }
+
+    /// Policies without a bucket_id condition apply to every bucket
+    fn applies_to(&self, bucket: &str) -> bool {
+        if !self.expression.contains("bucket_id") {
+            return true;
+        }
+        let pattern = format!(r"bucket_id\s*=\s*{}", regex::escape(&db::quote_literal(bucket)));
+        Regex::new(&pattern).is_ok_and(|re| re.is_match(&self.expression))
+    }
+}
+
+/// Flags buckets whose `storage.objects` policies reference roles or JWT
+/// claims. Those policies live in Postgres and are not carried over by an
+/// object copy, so the destination app may be unable to read its own files.
+pub async fn check_bucket_policies(app: &AppHandle, db_url: &str, buckets: &[String]) -> Result<Vec<BucketPolicyCheck>, String> {
+    let psql = Psql::locate(app)?;
+    let rows = psql
+        .query(
+            db_url,
+            "SELECT policyname, array_to_string(roles, ','), coalesce(qual, '') || ' ' || coalesce(with_check, '') \
+             FROM pg_policies WHERE schemaname = 'storage' AND tablename = 'objects'",
+        )
+        .await?;
+
+    let policies: Vec<ObjectPolicy> = rows
+        .into_iter()
+        .filter(|r| r.len() == 3)
+        .map(|r| ObjectPolicy {
+            name: r[0].clone(),
+            roles: r[1].clone(),
+            expression: r[2].clone(),
+        })
+        .filter(|p| p.references_roles_or_claims())
+        .collect();
+
+    Ok(buckets
+        .iter()
+        .map(|bucket| {
+            let names: Vec<String> = policies
+                .iter()
+                .filter(|p| p.applies_to(bucket))
+                .map(|p| p.name.clone())
+                .collect();
+            BucketPolicyCheck {
+                bucket: bucket.clone(),
+                has_custom_policies: !names.is_empty(),
+                policies: names,
+            }
+        })
+        .collect())
+}
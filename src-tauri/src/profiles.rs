@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::fs;
+use tauri::AppHandle;
+
+use crate::{functions, paths};
+
+/// A saved source/destination pair from profiles.json
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub source_url: String,
+    pub source_key: String,
+    pub dest_url: String,
+    pub dest_key: String,
+    pub db_url: Option<String>, // source Postgres connection string, for database backups
+}
+
+/// Loads profiles.json; a missing file is an empty list
+pub fn load_profiles(app: &AppHandle) -> Result<Vec<Profile>, String> {
+    let path = paths::get_profiles_path(app);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| format!("Corrupt profiles.json: {}", e))
+}
+
+pub fn find_profile(app: &AppHandle, profile_id: &str) -> Result<Profile, String> {
+    load_profiles(app)?
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("Unknown profile: {}", profile_id))
+}
+
+fn save_profiles(app: &AppHandle, profiles: &[Profile]) -> Result<(), String> {
+    let path = paths::get_profiles_path(app);
+    let data = serde_json::to_string_pretty(profiles).map_err(|e| e.to_string())?;
+    fs::write(&path, data).map_err(|e| format!("Failed to write profiles.json: {}", e))
+}
+
+/// One problem found in profiles.json
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ProfileFinding {
+    pub profile_id: String,
+    pub kind: String, // DUPLICATE_ID, DUPLICATE_NAME, DUPLICATE_PAIR, SAME_PROJECT, EMPTY_KEY
+    pub message: String,
+}
+
+/// Checks the profile store for duplicates, self-migrations and missing keys
+pub fn lint_profiles(app: &AppHandle) -> Result<Vec<ProfileFinding>, String> {
+    let profiles = load_profiles(app)?;
+    let mut findings = vec![];
+    let mut finding = |p: &Profile, kind: &str, message: String| {
+        findings.push(ProfileFinding {
+            profile_id: p.id.clone(),
+            kind: kind.to_string(),
+            message,
+        })
+    };
+
+    // First profile seen for each id, name and project pair
+    let mut ids: HashMap<&str, &Profile> = HashMap::new();
+    let mut names: HashMap<String, &Profile> = HashMap::new();
+    let mut pairs: HashMap<(String, String), &Profile> = HashMap::new();
+    for p in &profiles {
+        if let Some(first) = ids.get(p.id.as_str()) {
+            finding(p, "DUPLICATE_ID", format!("Profile id '{}' is also used by '{}'", p.id, first.name));
+        } else {
+            ids.insert(&p.id, p);
+        }
+
+        let name = p.name.trim().to_lowercase();
+        if let Some(first) = names.get(&name) {
+            finding(p, "DUPLICATE_NAME", format!("Name '{}' is also used by profile {}", p.name, first.id));
+        } else {
+            names.insert(name, p);
+        }
+
+        if !p.source_url.is_empty() && !p.dest_url.is_empty() {
+            let pair = (functions::normalize_project(&p.source_url), functions::normalize_project(&p.dest_url));
+            if let Some(first) = pairs.get(&pair) {
+                finding(p, "DUPLICATE_PAIR", format!("'{}' migrates the same source and destination as '{}'", p.name, first.name));
+            } else {
+                pairs.insert(pair, p);
+            }
+            if functions::is_same_project(&p.source_url, &p.dest_url) {
+                finding(p, "SAME_PROJECT", format!("'{}' points source and destination at the same project", p.name));
+            }
+        }
+        for (side, key) in [("source", &p.source_key), ("destination", &p.dest_key)] {
+            if key.trim().is_empty() {
+                finding(p, "EMPTY_KEY", format!("'{}' has no {} key", p.name, side));
+            }
+        }
+    }
+    Ok(findings)
+}
+
+/// Removes profiles that are exact copies of an earlier one. Returns how many were removed.
+pub fn dedupe_profiles(app: &AppHandle) -> Result<usize, String> {
+    let profiles = load_profiles(app)?;
+    let mut kept: Vec<Profile> = vec![];
+    for p in &profiles {
+        if !kept.contains(p) {
+            kept.push(p.clone());
+        }
+    }
+    let removed = profiles.len() - kept.len();
+    if removed > 0 {
+        save_profiles(app, &kept)?;
+    }
+    Ok(removed)
+}
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter, Window};
+
+use crate::db::{quote_ident, quote_literal, Psql};
+
+/// Publication Supabase Realtime streams changes from
+const REALTIME_PUBLICATION: &str = "supabase_realtime";
+
+/// One source publication table and what happened to it on the destination
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct PublicationTableStatus {
+    pub table: String, // schema.table
+    pub status: String, // PRESENT, ADDED, SKIPPED (not on destination), FAILED
+    pub error: Option<String>,
+}
+
+fn tables_in(rows: Vec<Vec<String>>) -> Vec<(String, String)> {
+    rows.into_iter()
+        .filter(|r| r.len() == 2)
+        .map(|r| (r[0].clone(), r[1].clone()))
+        .collect()
+}
+
+/// Adds the tables in the source's `supabase_realtime` publication to the
+/// destination's, so realtime subscriptions keep working after a migration.
+/// Tables missing on the destination are skipped and reported.
+pub async fn migrate_realtime_publication(
+    window: &Window,
+    app: &AppHandle,
+    source_db: &str,
+    dest_db: &str,
+) -> Result<Vec<PublicationTableStatus>, String> {
+    let psql = Psql::locate(app)?;
+    let by_publication = format!(
+        "SELECT schemaname, tablename FROM pg_publication_tables WHERE pubname = {} ORDER BY 1, 2",
+        quote_literal(REALTIME_PUBLICATION)
+    );
+    let source = tables_in(psql.query(source_db, &by_publication).await?);
+    if source.is_empty() {
+        window.emit("log", "Realtime: no tables published on the source.").unwrap();
+        return Ok(vec![]);
+    }
+
+    let has_publication = !psql
+        .query(
+            dest_db,
+            &format!("SELECT 1 FROM pg_publication WHERE pubname = {}", quote_literal(REALTIME_PUBLICATION)),
+        )
+        .await?
+        .is_empty();
+    if !has_publication {
+        psql.execute(dest_db, &[format!("CREATE PUBLICATION {}", quote_ident(REALTIME_PUBLICATION))])
+            .await?;
+        window.emit("log", format!("Realtime: created publication {} on the destination", REALTIME_PUBLICATION)).unwrap();
+    }
+
+    let published: HashSet<(String, String)> = tables_in(psql.query(dest_db, &by_publication).await?).into_iter().collect();
+    let existing: HashSet<(String, String)> = tables_in(psql.query(dest_db, "SELECT schemaname, tablename FROM pg_tables").await?)
+        .into_iter()
+        .collect();
+
+    let mut results = vec![];
+    for (schema, table) in source {
+        let mut status = PublicationTableStatus {
+            table: format!("{}.{}", schema, table),
+            status: "PRESENT".to_string(),
+            error: None,
+        };
+        let key = (schema, table);
+        if !published.contains(&key) {
+            if !existing.contains(&key) {
+                status.status = "SKIPPED".to_string();
+                status.error = Some("Table does not exist on the destination".to_string());
+            } else {
+                let add = format!(
+                    "ALTER PUBLICATION {} ADD TABLE {}.{}",
+                    quote_ident(REALTIME_PUBLICATION),
+                    quote_ident(&key.0),
+                    quote_ident(&key.1)
+                );
+                match psql.execute(dest_db, &[add]).await {
+                    Ok(_) => status.status = "ADDED".to_string(),
+                    Err(e) => {
+                        status.status = "FAILED".to_string();
+                        status.error = Some(e);
+                    }
+                }
+            }
+        }
+        results.push(status);
+    }
+
+    let count = |s: &str| results.iter().filter(|r| r.status == s).count();
+    window.emit("log", format!(
+        "Realtime: {} tables added, {} already published, {} skipped, {} failed",
+        count("ADDED"),
+        count("PRESENT"),
+        count("SKIPPED"),
+        count("FAILED")
+    )).unwrap();
+    Ok(results)
+}
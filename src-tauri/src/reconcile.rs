@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+use tauri::{Emitter, Manager, Window};
+
+use crate::report::{self, FailedObject, MigrationReport};
+use crate::{http, storage};
+
+/// Missing keys listed per bucket in the report; the rest are only counted
+const MISSING_KEYS_SHOWN: usize = 500;
+
+/// Source vs destination object counts of one bucket
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct BucketReconciliation {
+    pub bucket_id: String,
+    pub source_count: usize,
+    pub dest_count_before: usize,
+    pub dest_count_after: usize,
+    pub missing_before: usize,
+    pub missing: Vec<String>, // keys still missing after the follow-up (first MISSING_KEYS_SHOWN)
+    pub error: Option<String>,
+}
+
+/// Payload of the `reconciliation_report` event
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ReconciliationReport {
+    pub buckets: Vec<BucketReconciliation>,
+    pub retransferred: usize,
+    pub matched: bool,
+}
+
+/// Re-attempts objects from a prior run and folds the outcomes into `report`.
+/// Objects that are gone from the source are recorded as SKIPPED.
+pub async fn retry_objects(window: &Window, mirror: &storage::StorageMirror, report: &mut MigrationReport, failed: &[FailedObject]) {
+    for item in failed {
+        let started = std::time::Instant::now();
+
+        let (status, bytes, error) = match mirror.source_object_exists(&item.bucket, &item.key).await {
+            Ok(false) => {
+                window.emit("log", format!("  Skipped: {}/{} (no longer on source)", item.bucket, item.key)).unwrap();
+                ("SKIPPED", 0, Some("No longer exists on source".to_string()))
+            }
+            _ => {
+                // Listing metadata isn't kept in the report; content-type falls back to extension/sniffing
+                let object = storage::StorageObject {
+                    name: item.key.clone(),
+                    id: String::new(),
+                    metadata: None,
+                };
+                match http::with_backoff(http::RETRY_ATTEMPTS, || mirror.transfer_object(&item.bucket, &object)).await {
+                    Ok((bytes, _)) => {
+                        window.emit("log", format!("  Synced: {}/{}", item.bucket, item.key)).unwrap();
+                        ("SYNCED", bytes, None)
+                    }
+                    Err(e) => {
+                        window.emit("log", format!("  Failed: {}/{} ({})", item.bucket, item.key, e)).unwrap();
+                        ("FAILED", 0, Some(e))
+                    }
+                }
+            }
+        };
+
+        report.apply_outcome(report::ObjectOutcome {
+            bucket: item.bucket.clone(),
+            key: item.key.clone(),
+            status: status.to_string(),
+            bytes,
+            error,
+            duration_ms: started.elapsed().as_millis() as u64,
+            dest_key: None,
+        });
+    }
+}
+
+/// Keys of real objects (folder placeholders have no metadata)
+fn object_keys(objects: Vec<storage::StorageObject>) -> HashSet<String> {
+    objects.into_iter().filter(|o| o.metadata.is_some()).map(|o| o.name).collect()
+}
+
+/// (source count, destination count, source keys missing on the destination)
+async fn compare_bucket(mirror: &storage::StorageMirror, bucket_id: &str) -> Result<(usize, usize, Vec<String>), String> {
+    mirror.invalidate_listing(Some(bucket_id));
+    let source = object_keys(mirror.list_objects(bucket_id).await?);
+    // A missing destination bucket just means nothing arrived
+    let dest = object_keys(mirror.list_dest_objects(bucket_id).await.unwrap_or_default());
+    let mut missing: Vec<String> = source.difference(&dest).cloned().collect();
+    missing.sort();
+    Ok((source.len(), dest.len(), missing))
+}
+
+/// Compares per-bucket object counts of source and destination and, with
+/// `retransfer`, copies the missing keys in one follow-up pass (recorded in
+/// the last report). Emits `reconciliation_report` with before/after counts.
+pub async fn reconcile_buckets(
+    window: &Window,
+    mirror: &storage::StorageMirror,
+    bucket_ids: &[String],
+    retransfer: bool,
+) -> Result<ReconciliationReport, String> {
+    window.emit("log", format!("=== RECONCILING {} BUCKETS ===", bucket_ids.len())).unwrap();
+    let mut buckets = vec![];
+    let mut to_retry = vec![];
+    for bucket_id in bucket_ids {
+        let mut entry = BucketReconciliation {
+            bucket_id: bucket_id.clone(),
+            source_count: 0,
+            dest_count_before: 0,
+            dest_count_after: 0,
+            missing_before: 0,
+            missing: vec![],
+            error: None,
+        };
+        match compare_bucket(mirror, bucket_id).await {
+            Ok((source_count, dest_count, missing)) => {
+                entry.source_count = source_count;
+                entry.dest_count_before = dest_count;
+                entry.dest_count_after = dest_count;
+                entry.missing_before = missing.len();
+                if !missing.is_empty() {
+                    window.emit("log", format!(
+                        "  {}: {} on source, {} on destination, {} missing",
+                        bucket_id, source_count, dest_count, missing.len()
+                    )).unwrap();
+                }
+                to_retry.extend(missing.iter().map(|key| FailedObject {
+                    bucket: bucket_id.clone(),
+                    key: key.clone(),
+                    error: None,
+                }));
+                entry.missing = missing;
+            }
+            Err(e) => entry.error = Some(e),
+        }
+        buckets.push(entry);
+    }
+
+    let retransferred = if retransfer && !to_retry.is_empty() {
+        let app = window.app_handle();
+        let mut report = report::load_last_report(app)?.unwrap_or_else(MigrationReport::new);
+        window.emit("log", format!("Reconciliation: re-transferring {} missing objects", to_retry.len())).unwrap();
+        retry_objects(window, mirror, &mut report, &to_retry).await;
+        report.finished_at = report::now_secs();
+        report::save_last_report(app, &report)?;
+
+        for entry in buckets.iter_mut().filter(|b| b.missing_before > 0) {
+            match compare_bucket(mirror, &entry.bucket_id).await {
+                Ok((_, dest_count, missing)) => {
+                    entry.dest_count_after = dest_count;
+                    entry.missing = missing;
+                }
+                Err(e) => entry.error = Some(e),
+            }
+        }
+        to_retry.len()
+    } else {
+        0
+    };
+
+    for entry in &mut buckets {
+        entry.missing.truncate(MISSING_KEYS_SHOWN);
+    }
+    let matched = buckets.iter().all(|b| b.error.is_none() && b.missing.is_empty());
+    let report = ReconciliationReport { buckets, retransferred, matched };
+    window.emit("log", if matched {
+        "Reconciliation: every source object is on the destination.".to_string()
+    } else {
+        format!(
+            "Reconciliation: {} buckets still differ.",
+            report.buckets.iter().filter(|b| b.error.is_some() || !b.missing.is_empty()).count()
+        )
+    }).unwrap();
+    let _ = window.emit("reconciliation_report", report.clone());
+    Ok(report)
+}
@@ -0,0 +1,111 @@
+use reqwest::Client;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::{deps, functions, paths};
+
+/// Concurrency suggested when both projects share a region
+const SAME_REGION_CONCURRENCY: usize = 4;
+/// Same continent (e.g. us-east-1 -> us-west-1)
+const NEARBY_REGION_CONCURRENCY: usize = 8;
+/// Across continents, where per-request latency dominates
+const CROSS_REGION_CONCURRENCY: usize = 16;
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RegionSuggestion {
+    pub source_region: Option<String>,
+    pub dest_region: Option<String>,
+    pub current_concurrency: usize,
+    pub suggested_concurrency: usize,
+    pub reason: String,
+}
+
+/// project ref -> region, cached in userdata/regions.json; regions never change
+fn cache_path(app: &AppHandle) -> PathBuf {
+    paths::get_userdata_dir(app).join("regions.json")
+}
+
+fn load_cache(app: &AppHandle) -> BTreeMap<String, String> {
+    fs::read_to_string(cache_path(app))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Region of a project via the Management API, cached per project ref.
+/// None when the ref can't be derived or the key can't read the project.
+pub async fn resolve_region(app: &AppHandle, client: &Client, project_url: &str, key: &str) -> Option<String> {
+    let project_ref = functions::extract_project_ref(project_url).ok()?;
+    let mut cache = load_cache(app);
+    if let Some(region) = cache.get(&project_ref) {
+        return Some(region.clone());
+    }
+
+    let response = client
+        .get(format!("https://api.supabase.com/v1/projects/{}", project_ref))
+        .header("Authorization", format!("Bearer {}", key))
+        .send()
+        .await
+        .ok()
+        .filter(|r| r.status().is_success())?;
+    let body: serde_json::Value = response.json().await.ok()?;
+    let region = body["region"].as_str().filter(|r| !r.is_empty())?.to_string();
+
+    cache.insert(project_ref, region.clone());
+    // A failed write only costs a lookup next time
+    if let Ok(data) = serde_json::to_string_pretty(&cache) {
+        let _ = fs::create_dir_all(paths::get_userdata_dir(app));
+        let _ = fs::write(cache_path(app), data);
+    }
+    Some(region)
+}
+
+/// "us-east-1" -> "us", "ap-southeast-1" -> "ap"
+fn continent(region: &str) -> &str {
+    region.split('-').next().unwrap_or(region)
+}
+
+/// More objects in flight the further apart the projects are, to hide latency
+fn suggest(source: Option<&str>, dest: Option<&str>, current: usize) -> (usize, String) {
+    match (source, dest) {
+        (Some(s), Some(d)) if s == d => (SAME_REGION_CONCURRENCY, format!("Both projects are in {}.", s)),
+        (Some(s), Some(d)) if continent(s) == continent(d) => {
+            (NEARBY_REGION_CONCURRENCY, format!("{} -> {} is cross-region on the same continent.", s, d))
+        }
+        (Some(s), Some(d)) => (CROSS_REGION_CONCURRENCY, format!("{} -> {} is cross-continent; more parallel requests hide the latency.", s, d)),
+        _ => (current, "Region unknown for at least one project (the key can't read the Management API); keeping the current setting.".to_string()),
+    }
+}
+
+/// Resolves both regions and suggests a transfer concurrency for the pair
+pub async fn suggest_concurrency(
+    app: &AppHandle,
+    client: &Client,
+    source_url: &str,
+    source_key: &str,
+    dest_url: &str,
+    dest_key: &str,
+) -> RegionSuggestion {
+    let source_region = resolve_region(app, client, source_url, source_key).await;
+    let dest_region = resolve_region(app, client, dest_url, dest_key).await;
+    let current = deps::load_config(app).performance.transfer_concurrency;
+    let (suggested, reason) = suggest(source_region.as_deref(), dest_region.as_deref(), current);
+    RegionSuggestion {
+        source_region,
+        dest_region,
+        current_concurrency: current,
+        suggested_concurrency: suggested,
+        reason,
+    }
+}
+
+/// Saves an accepted suggestion as `performance.transfer_concurrency`
+pub fn apply_concurrency(app: &AppHandle, concurrency: usize) -> Result<usize, String> {
+    let config = deps::update_config(app, |config| {
+        config.performance.transfer_concurrency = concurrency;
+        config.performance = config.performance.clone().clamped();
+    })?;
+    Ok(config.performance.transfer_concurrency)
+}
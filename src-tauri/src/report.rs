@@ -0,0 +1,177 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use crate::paths;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct ObjectOutcome {
+    pub bucket: String,
+    pub key: String,
+    pub status: String, // SYNCED, FAILED, SKIPPED
+    pub bytes: u64,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub dest_key: Option<String>, // set when the object was stored under another key (renamed on collision)
+}
+
+/// An object to re-attempt, as recorded from a prior run
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct FailedObject {
+    pub bucket: String,
+    pub key: String,
+    pub error: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct BucketReport {
+    pub id: String,
+    pub name: String,
+    pub objects_total: usize,
+    pub objects_synced: usize,
+    pub objects_failed: usize,
+    pub error: Option<String>, // set when the bucket could not be listed
+    pub duration_ms: u64,
+}
+
+/// Outcome of a storage migration run
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+pub struct MigrationReport {
+    pub started_at: u64, // unix seconds
+    pub finished_at: u64,
+    pub duration_ms: u64,
+    pub buckets: Vec<BucketReport>,
+    pub objects: Vec<ObjectOutcome>,
+    #[serde(default)]
+    pub manual_steps: Vec<String>, // settings the tool could not carry over
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl MigrationReport {
+    pub fn new() -> Self {
+        Self {
+            started_at: now_secs(),
+            ..Default::default()
+        }
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &ObjectOutcome> {
+        self.objects.iter().filter(|o| o.status == "FAILED")
+    }
+
+    pub fn failed_objects(&self) -> Vec<FailedObject> {
+        self.failures()
+            .map(|o| FailedObject {
+                bucket: o.bucket.clone(),
+                key: o.key.clone(),
+                error: o.error.clone(),
+            })
+            .collect()
+    }
+
+    /// Replaces the outcome for the same bucket/key (or appends it)
+    /// and recomputes the per-bucket counters
+    pub fn apply_outcome(&mut self, outcome: ObjectOutcome) {
+        match self
+            .objects
+            .iter_mut()
+            .find(|o| o.bucket == outcome.bucket && o.key == outcome.key)
+        {
+            Some(existing) => *existing = outcome,
+            None => self.objects.push(outcome),
+        }
+
+        for bucket in &mut self.buckets {
+            let objects = self.objects.iter().filter(|o| o.bucket == bucket.id);
+            let (synced, failed) = objects.fold((0, 0), |(s, f), o| match o.status.as_str() {
+                "SYNCED" => (s + 1, f),
+                "FAILED" => (s, f + 1),
+                _ => (s, f),
+            });
+            bucket.objects_synced = synced;
+            bucket.objects_failed = failed;
+        }
+    }
+
+    /// One row per object: bucket,key,status,bytes,error
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("bucket,key,status,bytes,error\n");
+        for o in &self.objects {
+            out.push_str(&format!(
+                "{},{},{},{},{}\n",
+                csv_field(&o.bucket),
+                csv_field(&o.key),
+                csv_field(&o.status),
+                o.bytes,
+                csv_field(o.error.as_deref().unwrap_or(""))
+            ));
+        }
+        out
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn last_report_path(app: &AppHandle) -> PathBuf {
+    paths::get_userdata_dir(app).join("last_report.json")
+}
+
+fn failed_objects_path(app: &AppHandle) -> PathBuf {
+    paths::get_userdata_dir(app).join("failed_objects.json")
+}
+
+/// Saves the report and the failure list derived from it (for one-click retry)
+pub fn save_last_report(app: &AppHandle, report: &MigrationReport) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    fs::write(last_report_path(app), data).map_err(|e| e.to_string())?;
+
+    let failed = serde_json::to_string_pretty(&report.failed_objects()).map_err(|e| e.to_string())?;
+    fs::write(failed_objects_path(app), failed).map_err(|e| e.to_string())
+}
+
+/// Latest persisted failure list; empty when none has been recorded
+pub fn load_failed_objects(app: &AppHandle) -> Result<Vec<FailedObject>, String> {
+    let path = failed_objects_path(app);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| e.to_string())
+}
+
+pub fn load_last_report(app: &AppHandle) -> Result<Option<MigrationReport>, String> {
+    let path = last_report_path(app);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map(Some).map_err(|e| e.to_string())
+}
+
+/// Writes the last report to `out_path` as "json" or "csv"
+pub fn export_report(app: &AppHandle, format: &str, out_path: &str) -> Result<String, String> {
+    let report = load_last_report(app)?.ok_or("No migration report available yet.")?;
+
+    let data = match format {
+        "json" => serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?,
+        "csv" => report.to_csv(),
+        other => return Err(format!("Unsupported report format: {}", other)),
+    };
+
+    fs::write(out_path, data).map_err(|e| format!("Failed to write report: {}", e))?;
+    Ok(out_path.to_string())
+}
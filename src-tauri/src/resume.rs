@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use crate::{bucket_index, checkpoint, storage};
+
+/// Skipped by a content-hash match that the destination listing confirms
+pub const SKIP_FILTERED: &str = "filtered";
+/// Left alone by the skip/rename collision strategy
+pub const SKIP_COLLISION: &str = "collision";
+
+/// One object in a resume preview. `reason` is set for skipped objects, with
+/// the migration's own rules: "filtered" or "collision" (see `skip_reason`).
+/// `dest_key` differs from `key` when the rename strategy moves the object.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ResumeEntry {
+    pub key: String,
+    pub size: u64,
+    pub reason: Option<String>,
+    pub dest_key: Option<String>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ResumePreview {
+    pub bucket_id: String,
+    pub checkpoint_status: Option<String>,
+    pub to_transfer: Vec<ResumeEntry>,
+    pub to_skip: Vec<ResumeEntry>,
+}
+
+/// Why a migration run leaves `object` alone (None = it transfers). The same
+/// two checks `migrate_storage` makes: the content-hash partition, then the
+/// collision strategy's placement.
+pub fn skip_reason(
+    mirror: &storage::StorageMirror,
+    source_index: Option<&bucket_index::BucketIndex>,
+    dest_index: Option<&bucket_index::BucketIndex>,
+    object: &storage::StorageObject,
+    existing_size: Option<u64>,
+) -> Option<&'static str> {
+    if bucket_index::is_unchanged(source_index, dest_index, object, existing_size) {
+        Some(SKIP_FILTERED)
+    } else if mirror.place_object(object, existing_size).is_none() {
+        Some(SKIP_COLLISION)
+    } else {
+        None
+    }
+}
+
+/// What a re-run of one bucket would transfer and what it would leave alone
+pub async fn preview_resume(
+    app: &AppHandle,
+    mirror: &storage::StorageMirror,
+    source_url: &str,
+    dest_url: &str,
+    bucket_id: &str,
+) -> Result<ResumePreview, String> {
+    let objects = mirror.list_objects(bucket_id).await?;
+    // A missing destination bucket just means nothing is there yet
+    let dest_sizes: HashMap<String, u64> = mirror
+        .list_dest_objects(bucket_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|o| (o.name.clone(), o.size()))
+        .collect();
+
+    let checkpoint_status = checkpoint::load(app)?
+        .storage_buckets
+        .get(bucket_id)
+        .map(|p| p.status.clone());
+
+    let source_index = bucket_index::load_index(app, source_url, bucket_id);
+    let dest_index = bucket_index::load_index(app, dest_url, bucket_id);
+
+    let mut preview = ResumePreview {
+        bucket_id: bucket_id.to_string(),
+        checkpoint_status,
+        to_transfer: vec![],
+        to_skip: vec![],
+    };
+    for object in &objects {
+        let existing_size = dest_sizes.get(&object.name).copied();
+        let reason = skip_reason(mirror, source_index.as_ref(), dest_index.as_ref(), object, existing_size);
+        let entry = ResumeEntry {
+            key: object.name.clone(),
+            size: object.size(),
+            reason: reason.map(str::to_string),
+            dest_key: reason.is_none().then(|| mirror.place_object(object, existing_size)).flatten(),
+        };
+        match reason {
+            Some(_) => preview.to_skip.push(entry),
+            None => preview.to_transfer.push(entry),
+        }
+    }
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storage::CollisionStrategy;
+
+    fn object(name: &str, size: u64) -> storage::StorageObject {
+        serde_json::from_value(serde_json::json!({ "name": name, "id": "x", "metadata": { "size": size } })).unwrap()
+    }
+
+    fn mirror(collision: CollisionStrategy) -> storage::StorageMirror {
+        storage::StorageMirror::new(reqwest::Client::new(), "http://src", "k", "http://dst", "k").with_collision_strategy(collision)
+    }
+
+    fn index(size: u64) -> bucket_index::BucketIndex {
+        serde_json::from_value(serde_json::json!({
+            "project": "p", "bucket_id": "docs", "indexed_at": 0,
+            "objects": { "a.txt": { "size": size, "sha256": "abc" } }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn same_name_and_size_is_overwritten_without_a_hash_match() {
+        // The migration doesn't skip on name+size alone under the default strategy
+        let reason = skip_reason(&mirror(CollisionStrategy::Overwrite), None, None, &object("a.txt", 3), Some(3));
+        assert_eq!(reason, None);
+    }
+
+    #[test]
+    fn hash_match_skips_only_while_on_destination() {
+        let (src, dst) = (index(3), index(3));
+        let m = mirror(CollisionStrategy::Overwrite);
+        assert_eq!(skip_reason(&m, Some(&src), Some(&dst), &object("a.txt", 3), Some(3)), Some(SKIP_FILTERED));
+        assert_eq!(skip_reason(&m, Some(&src), Some(&dst), &object("a.txt", 3), None), None);
+    }
+
+    #[test]
+    fn collision_strategies_match_the_migration() {
+        let obj = object("a.txt", 3);
+        assert_eq!(skip_reason(&mirror(CollisionStrategy::Skip), None, None, &obj, Some(9)), Some(SKIP_COLLISION));
+        assert_eq!(skip_reason(&mirror(CollisionStrategy::Skip), None, None, &obj, None), None);
+        assert_eq!(skip_reason(&mirror(CollisionStrategy::Rename), None, None, &obj, Some(3)), Some(SKIP_COLLISION));
+        assert_eq!(skip_reason(&mirror(CollisionStrategy::Rename), None, None, &obj, Some(9)), None);
+    }
+}
@@ -0,0 +1,233 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use regex::Regex;
+use reqwest::{Client, Method, RequestBuilder};
+use ring::hmac;
+use sha2::{Digest, Sha256};
+
+use crate::report;
+use crate::storage::StorageObject;
+
+/// SigV4 leaves only the RFC 3986 unreserved characters unescaped
+const SIGV4_UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+/// Bodies are streamed, so their hash isn't known up front (allowed over HTTPS)
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Access key pair from Project Settings > Storage > S3 Connection
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+/// Opt-in S3 protocol transfers. Sides without credentials use the REST API.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct S3Config {
+    pub enabled: bool,
+    pub region: String, // project region, part of the signature
+    pub source: Option<S3Credentials>,
+    pub dest: Option<S3Credentials>,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            region: "us-east-1".to_string(),
+            source: None,
+            dest: None,
+        }
+    }
+}
+
+/// A project's `{url}/storage/v1/s3` endpoint with its signing credentials
+#[derive(Clone, Debug)]
+pub struct S3Endpoint {
+    base_url: String,
+    region: String,
+    credentials: S3Credentials,
+}
+
+impl S3Endpoint {
+    pub fn new(project_url: &str, region: &str, credentials: S3Credentials) -> Self {
+        Self {
+            base_url: format!("{}/storage/v1/s3", project_url.trim_end_matches('/')),
+            region: region.to_string(),
+            credentials,
+        }
+    }
+
+    fn object_path(&self, bucket_id: &str, object_name: &str) -> String {
+        let mut path = format!("/{}", encode(bucket_id));
+        for segment in object_name.split('/') {
+            path.push('/');
+            path.push_str(&encode(segment));
+        }
+        path
+    }
+
+    /// Builds a SigV4-signed request. `path` and `query` must already be encoded
+    /// and `query` sorted by key, since both are signed exactly as sent.
+    pub fn request(&self, client: &Client, method: Method, path: &str, query: &str) -> Result<RequestBuilder, String> {
+        let mut url = format!("{}{}", self.base_url, path);
+        if !query.is_empty() {
+            url = format!("{}?{}", url, query);
+        }
+        let parsed = reqwest::Url::parse(&url).map_err(|e| format!("Invalid S3 URL {}: {}", url, e))?;
+        let host = match parsed.port() {
+            Some(port) => format!("{}:{}", parsed.host_str().unwrap_or_default(), port),
+            None => parsed.host_str().unwrap_or_default().to_string(),
+        };
+
+        let (date, amz_date) = amz_timestamp(report::now_secs());
+        let canonical_request = format!(
+            "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method.as_str(),
+            parsed.path(),
+            query,
+            host,
+            UNSIGNED_PAYLOAD,
+            amz_date,
+            UNSIGNED_PAYLOAD
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let mut key = format!("AWS4{}", self.credentials.secret_access_key).into_bytes();
+        for part in [date.as_str(), self.region.as_str(), "s3", "aws4_request"] {
+            key = hmac_sha256(&key, part.as_bytes());
+        }
+        let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+
+        Ok(client
+            .request(method, parsed)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", UNSIGNED_PAYLOAD)
+            .header(
+                "Authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+                    self.credentials.access_key_id, scope, signature
+                ),
+            ))
+    }
+
+    pub fn get_object(&self, client: &Client, bucket_id: &str, object_name: &str) -> Result<RequestBuilder, String> {
+        self.request(client, Method::GET, &self.object_path(bucket_id, object_name), "")
+    }
+
+    pub fn put_object(&self, client: &Client, bucket_id: &str, object_name: &str) -> Result<RequestBuilder, String> {
+        self.request(client, Method::PUT, &self.object_path(bucket_id, object_name), "")
+    }
+
+    /// ListObjectsV2 over the whole bucket (recursive), following continuation tokens
+    pub async fn list_objects(&self, client: &Client, bucket_id: &str, page_size: usize) -> Result<Vec<StorageObject>, String> {
+        let path = format!("/{}", encode(bucket_id));
+        let mut objects = vec![];
+        let mut token: Option<String> = None;
+
+        loop {
+            // Keys sorted: continuation-token < list-type < max-keys
+            let mut query = vec![];
+            if let Some(t) = &token {
+                query.push(format!("continuation-token={}", encode(t)));
+            }
+            query.push("list-type=2".to_string());
+            query.push(format!("max-keys={}", page_size));
+
+            let res = self
+                .request(client, Method::GET, &path, &query.join("&"))?
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            let status = res.status();
+            let body = res.text().await.map_err(|e| e.to_string())?;
+            if !status.is_success() {
+                return Err(format!(
+                    "S3 list of {} failed (HTTP {}): {}",
+                    bucket_id,
+                    status.as_u16(),
+                    xml_value(&body, "Message").unwrap_or(body)
+                ));
+            }
+
+            objects.extend(parse_contents(&body));
+            token = match xml_value(&body, "IsTruncated").as_deref() {
+                Some("true") => xml_value(&body, "NextContinuationToken"),
+                _ => None,
+            };
+            if token.is_none() {
+                break;
+            }
+        }
+        Ok(objects)
+    }
+}
+
+fn encode(value: &str) -> String {
+    utf8_percent_encode(value, SIGV4_UNRESERVED).to_string()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data).as_ref().to_vec()
+}
+
+/// ("YYYYMMDD", "YYYYMMDDTHHMMSSZ") for a unix timestamp (UTC)
+fn amz_timestamp(secs: u64) -> (String, String) {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let date = format!("{:04}{:02}{:02}", year, month, day);
+    let datetime = format!("{}T{:02}{:02}{:02}Z", date, rem / 3_600, rem % 3_600 / 60, rem % 60);
+    (date, datetime)
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn xml_value(body: &str, tag: &str) -> Option<String> {
+    let re = Regex::new(&format!(r"<{tag}>([^<]*)</{tag}>")).ok()?;
+    re.captures(body).map(|c| xml_unescape(&c[1]))
+}
+
+/// <Contents> entries as StorageObjects (size in metadata, like the REST listing)
+fn parse_contents(body: &str) -> Vec<StorageObject> {
+    let Ok(re) = Regex::new(r"(?s)<Contents>(.*?)</Contents>") else {
+        return vec![];
+    };
+    re.captures_iter(body)
+        .filter_map(|c| {
+            let entry = &c[1];
+            let name = xml_value(entry, "Key")?;
+            let size = xml_value(entry, "Size").and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
+            Some(StorageObject {
+                name,
+                id: xml_value(entry, "ETag").unwrap_or_default(),
+                metadata: Some(serde_json::json!({ "size": size })),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,233 @@
+use std::fs;
+use tauri::AppHandle;
+
+use crate::deps::{self, PulseConfig};
+
+/// Per-field documentation emitted into config.example.json as "// key" entries.
+/// Keep in sync with `PulseConfig`; undocumented fields are flagged in the output.
+const FIELD_DOCS: &[(&str, &str)] = &[
+    ("channel", "Release channel for driver packs: \"stable\" or \"insider\"."),
+    ("supabase_url", "DevPulse release service URL. Leave as-is unless self-hosting the Orbital Depot."),
+    ("supabase_key", "Anon (public) key for the release service. REQUIRED: installs fall back to GitHub while this is empty."),
+    ("skip_bad_archive_entries", "true = skip unreadable archive entries and keep going; false = abort on the first one."),
+    ("doh_enabled", "Resolve hostnames over DNS-over-HTTPS. Only enable on networks that block *.supabase.co lookups."),
+    ("doh_endpoint", "DoH JSON endpoint. Must be an IP literal so it can be reached without DNS."),
+    ("keep_download_cache", "Keep verified driver archives in bin/cache after a successful install."),
+    ("hooks", "Commands/webhooks run around migration stages. Each step: { stage, phase: pre|post, command?, webhook_url?, timeout_secs }."),
+    ("abort_on_failure", "Abort the migration when a hook fails (non-zero exit or non-2xx response)."),
+    ("steps", "Hook definitions. stage \"*\" matches every stage."),
+    ("ca_cert_path", "Optional path to a PEM CA certificate trusted in addition to the system roots (self-hosted Supabase, TLS-intercepting proxies)."),
+    ("accept_invalid_certs", "DANGEROUS. true disables TLS certificate verification for every request. Prefer ca_cert_path."),
+    ("storage_quota_bytes", "Destination storage quota in bytes (Free plan: 1073741824). Enables the near-limit preflight warning; null = unknown."),
+    ("monitor_enabled", "Start the live log monitor (Server-Sent Events on 127.0.0.1) when the app launches."),
+    ("monitor_port", "Localhost port for the log monitor. 0 = pick a free port. Subscribers need the per-session token."),
+    ("backup_retention", "Scheduled backup archives kept per profile; older ones are deleted after each run (minimum 1)."),
+    ("s3", "Opt-in S3 protocol transfers (faster for bulk copies). The REST API stays the default and is used for any side without credentials."),
+    ("enabled", "Turn S3 transfers on. Requires credentials for at least one side."),
+    ("region", "Project region used in request signing, e.g. \"us-east-1\"."),
+    ("source", "Source S3 keys: { access_key_id, secret_access_key } from Project Settings > Storage. null = REST."),
+    ("dest", "Destination S3 keys: { access_key_id, secret_access_key }. null = REST."),
+    ("trusted_hosts", "Extra hosts driver downloads may come from (e.g. an internal mirror). GitHub and the release service host are always allowed."),
+    ("egress_warning_bytes", "Migrations that read more than this many bytes from the source must be confirmed (egress may be billed). 0 = never ask."),
+    ("manifest_public_key", "Pinned ed25519 public key (hex or base64) that depot manifests must be signed with. Empty = signatures not checked."),
+    ("require_signed_manifest", "Refuse manifests without a valid signature (from the release row or a <manifest>.sig file)."),
+    ("abort_on_version_mismatch", "Fail installs when the manifest version, package file name and installed pg_dump disagree, instead of warning."),
+    ("collision_strategy", "When the destination already has an object at the same key: \"overwrite\" (default), \"skip\", or \"rename\" (differently-sized objects are stored as <name>-migrated.<ext> and recorded in the report)."),
+    ("health_probe_interval_secs", "Seconds between source/destination health pings during a migration. While either is down, new transfers pause. 0 = off."),
+    ("health_max_outage_secs", "How long a migration waits paused for an unreachable endpoint before failing."),
+    ("reconcile_after_migration", "After a migration, compare source and destination object counts per bucket and re-transfer any missing keys once."),
+    ("performance", "Transfer tuning. Out-of-range values are clamped on load."),
+    ("transfer_concurrency", "Objects copied in parallel per bucket (1-32)."),
+    ("upload_chunk_bytes", "Upload bodies are streamed in chunks of this many bytes (256 KB - 64 MB)."),
+    ("list_page_size", "Objects fetched per storage list request (10-1000)."),
+    ("max_bytes_per_sec", "Bandwidth cap across all transfers. 0 = unlimited."),
+    ("request_timeout_secs", "Connect and idle-read timeout per request (5-600)."),
+    ("signed_uploads", "Upload through per-object signed URLs (PUT) instead of the standard POST. Falls back automatically when the destination doesn't support them."),
+    ("streaming_transfers", "Pipe each download straight into its upload so memory stays bounded by transfer_concurrency, not object size. Off = buffer each object."),
+    ("restore_jobs", "Parallel pg_restore jobs (1-32, capped at the CPU count). Needs a custom or directory format dump."),
+];
+
+/// Values shown instead of the defaults where the default is not a usable example
+const PLACEHOLDERS: &[(&str, &str)] = &[("supabase_key", "<paste-anon-key-here>")];
+
+fn doc_for(key: &str) -> &'static str {
+    FIELD_DOCS
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, d)| *d)
+        .unwrap_or("Undocumented field.")
+}
+
+/// Renders an object with a "// key" doc entry before every field.
+/// serde_json would sort the keys, so the template is assembled by hand.
+fn render_object(value: &serde_json::Value, indent: usize) -> String {
+    let pad = "  ".repeat(indent + 1);
+    let mut lines = vec![];
+
+    if let Some(map) = value.as_object() {
+        for (key, val) in map {
+            let rendered = match PLACEHOLDERS.iter().find(|(k, _)| k == key) {
+                Some((_, placeholder)) => serde_json::to_string(placeholder).unwrap(),
+                None if val.is_object() => render_object(val, indent + 1),
+                None => serde_json::to_string(val).unwrap(),
+            };
+            lines.push(format!(
+                "{}{}: {},\n{}{}: {}",
+                pad,
+                serde_json::to_string(&format!("// {}", key)).unwrap(),
+                serde_json::to_string(doc_for(key)).unwrap(),
+                pad,
+                serde_json::to_string(key).unwrap(),
+                rendered
+            ));
+        }
+    }
+
+    format!("{{\n{}\n{}}}", lines.join(",\n"), "  ".repeat(indent))
+}
+
+/// Writes config.example.json next to config.json. The real config is never
+/// touched; "// key" entries are ignored when the example is copied over it.
+pub fn scaffold_config(app: &AppHandle) -> Result<String, String> {
+    let config_path = deps::config_path(app);
+    let example_path = config_path.with_file_name("config.example.json");
+
+    if let Some(parent) = example_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let defaults = serde_json::to_value(PulseConfig::default()).map_err(|e| e.to_string())?;
+    fs::write(&example_path, render_object(&defaults, 0) + "\n").map_err(|e| e.to_string())?;
+
+    Ok(example_path.to_string_lossy().to_string())
+}
+
+/// One problem found in config.json. Line and column are 1-based.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ConfigDiagnostic {
+    pub severity: String, // error, warning
+    pub field: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ConfigValidation {
+    pub path: String,
+    pub valid: bool, // loads without falling back to defaults
+    pub diagnostics: Vec<ConfigDiagnostic>,
+    pub pretty: Option<String>, // normalized JSON when the file is at least valid JSON
+}
+
+/// Channels the release service publishes
+const KNOWN_CHANNELS: [&str; 2] = ["stable", "insider"];
+/// Settings that must be absolute http(s) URLs
+const URL_SETTINGS: [&str; 2] = ["supabase_url", "doh_endpoint"];
+
+fn diagnostic(severity: &str, field: Option<&str>, message: String) -> ConfigDiagnostic {
+    ConfigDiagnostic {
+        severity: severity.to_string(),
+        field: field.map(str::to_string),
+        line: None,
+        column: None,
+        message,
+    }
+}
+
+/// The JSON key the error position falls on: backtick-quoted in serde's
+/// message, else the last `"key":` on that line before the column
+fn field_at(raw: &str, err: &serde_json::Error) -> Option<String> {
+    let message = err.to_string();
+    if let Some(start) = message.find('`') {
+        if let Some(len) = message[start + 1..].find('`') {
+            return Some(message[start + 1..start + 1 + len].to_string());
+        }
+    }
+    let line = raw.lines().nth(err.line().checked_sub(1)?)?;
+    let upto: String = line.chars().take(err.column()).collect();
+    let re = regex::Regex::new(r#""([^"]+)"\s*:"#).ok()?;
+    re.captures_iter(&upto).last().map(|c| c[1].to_string())
+}
+
+fn parse_error(raw: &str, err: &serde_json::Error) -> ConfigDiagnostic {
+    let mut diag = diagnostic("error", None, err.to_string());
+    diag.field = field_at(raw, err);
+    diag.line = Some(err.line());
+    diag.column = Some(err.column());
+    diag
+}
+
+/// Reads config.json as written (no env overrides) and reports exactly where
+/// it fails to load, plus warnings for values that load but look wrong
+pub fn validate_config_file(app: &AppHandle) -> ConfigValidation {
+    let path = deps::config_path(app);
+    let mut result = ConfigValidation {
+        path: path.to_string_lossy().to_string(),
+        valid: true,
+        diagnostics: vec![],
+        pretty: None,
+    };
+    let raw = match fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(e) => {
+            result.diagnostics.push(diagnostic("warning", None, format!("Cannot read config ({}). Defaults are used.", e)));
+            return result;
+        }
+    };
+
+    let value: serde_json::Value = match serde_json::from_str(&raw) {
+        Ok(value) => value,
+        Err(e) => {
+            result.valid = false;
+            result.diagnostics.push(parse_error(&raw, &e));
+            return result;
+        }
+    };
+    result.pretty = serde_json::to_string_pretty(&value).ok();
+
+    let config = match serde_json::from_str::<PulseConfig>(&raw) {
+        Ok(config) => config,
+        Err(e) => {
+            result.valid = false;
+            result.diagnostics.push(parse_error(&raw, &e));
+            return result;
+        }
+    };
+
+    let known: Vec<String> = serde_json::to_value(PulseConfig::default())
+        .ok()
+        .and_then(|v| v.as_object().map(|m| m.keys().cloned().collect()))
+        .unwrap_or_default();
+    for key in value.as_object().into_iter().flat_map(|m| m.keys()) {
+        if !known.contains(key) && !key.starts_with("//") {
+            result.diagnostics.push(diagnostic("warning", Some(key), format!("Unknown setting '{}' is ignored.", key)));
+        }
+    }
+
+    if !KNOWN_CHANNELS.contains(&config.channel.as_str()) {
+        result.diagnostics.push(diagnostic(
+            "warning",
+            Some("channel"),
+            format!("Channel '{}' is not one of {}.", config.channel, KNOWN_CHANNELS.join(", ")),
+        ));
+    }
+    for key in URL_SETTINGS {
+        let url = value[key].as_str().unwrap_or_default();
+        if !url.is_empty() && !url.starts_with("https://") && !url.starts_with("http://") {
+            result.diagnostics.push(diagnostic("warning", Some(key), format!("'{}' is missing the https:// scheme.", url)));
+        }
+    }
+    let clamped = serde_json::to_value(config.performance.clone().clamped()).unwrap_or_default();
+    let given = serde_json::to_value(&config.performance).unwrap_or_default();
+    for (key, val) in given.as_object().into_iter().flatten() {
+        if clamped[key] != *val {
+            result.diagnostics.push(diagnostic(
+                "warning",
+                Some(&format!("performance.{}", key)),
+                format!("{} is out of range and will be used as {}.", val, clamped[key]),
+            ));
+        }
+    }
+    result
+}
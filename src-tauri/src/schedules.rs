@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{backup, deps, paths, profiles, report};
+
+/// Shortest allowed interval between scheduled backups
+const MIN_INTERVAL_SECS: u64 = 60;
+
+/// A recurring backup of one profile (persisted in userdata/schedules.json)
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct BackupSchedule {
+    pub id: String,
+    pub profile_id: String,
+    pub interval_secs: u64,
+    pub created_at: u64,       // unix seconds
+    pub last_run: Option<u64>, // unix seconds of the last finished attempt
+}
+
+/// Payload of the `backup_tick` event
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct BackupTick {
+    pub schedule_id: String,
+    pub profile_id: String,
+    pub status: String, // RUNNING, DONE, FAILED
+    pub archive: Option<String>,
+    pub pruned: usize,
+    pub error: Option<String>,
+}
+
+/// Timers for armed schedules, keyed by schedule id (managed state)
+#[derive(Default)]
+pub struct ScheduleTimers(Mutex<HashMap<String, JoinHandle<()>>>);
+
+pub fn schedules_path(app: &AppHandle) -> PathBuf {
+    paths::get_userdata_dir(app).join("schedules.json")
+}
+
+pub fn load_schedules(app: &AppHandle) -> Result<Vec<BackupSchedule>, String> {
+    let path = schedules_path(app);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| format!("Corrupt schedules.json: {}", e))
+}
+
+fn save_schedules(app: &AppHandle, schedules: &[BackupSchedule]) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(schedules).map_err(|e| e.to_string())?;
+    fs::write(schedules_path(app), data).map_err(|e| e.to_string())
+}
+
+/// Registers and arms a recurring backup for `profile_id`
+pub fn schedule_backup(app: &AppHandle, profile_id: &str, interval_secs: u64) -> Result<BackupSchedule, String> {
+    profiles::find_profile(app, profile_id)?;
+    if interval_secs < MIN_INTERVAL_SECS {
+        return Err(format!("Backup interval must be at least {} seconds", MIN_INTERVAL_SECS));
+    }
+
+    let now = report::now_secs();
+    let schedule = BackupSchedule {
+        id: format!("{}-{}", profile_id, now),
+        profile_id: profile_id.to_string(),
+        interval_secs,
+        created_at: now,
+        last_run: None,
+    };
+
+    let mut schedules = load_schedules(app)?;
+    schedules.push(schedule.clone());
+    save_schedules(app, &schedules)?;
+    arm(app, &schedule);
+    Ok(schedule)
+}
+
+/// Stops and forgets a schedule. Existing archives are kept.
+pub fn cancel_schedule(app: &AppHandle, schedule_id: &str) -> Result<(), String> {
+    let mut schedules = load_schedules(app)?;
+    let before = schedules.len();
+    schedules.retain(|s| s.id != schedule_id);
+    if schedules.len() == before {
+        return Err(format!("Unknown schedule: {}", schedule_id));
+    }
+    save_schedules(app, &schedules)?;
+
+    if let Some(timer) = app.state::<ScheduleTimers>().0.lock().unwrap().remove(schedule_id) {
+        timer.abort();
+    }
+    Ok(())
+}
+
+/// Re-arms every persisted schedule (called once from `run()` setup)
+pub fn rearm_all(app: &AppHandle) {
+    match load_schedules(app) {
+        Ok(schedules) => schedules.iter().for_each(|s| arm(app, s)),
+        Err(e) => {
+            let _ = app.emit("log", format!("Backup schedules not restored: {}", e));
+        }
+    }
+}
+
+/// Starts the timer for a schedule. The first run is due one interval after
+/// the last recorded run, so a restart resumes the cadence instead of resetting it.
+fn arm(app: &AppHandle, schedule: &BackupSchedule) {
+    let (app_handle, schedule_id) = (app.clone(), schedule.id.clone());
+    let last = schedule.last_run.unwrap_or(schedule.created_at);
+    let mut delay = (last + schedule.interval_secs).saturating_sub(report::now_secs());
+    let interval = schedule.interval_secs;
+
+    let timer = tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(delay)).await;
+            run_scheduled(&app_handle, &schedule_id).await;
+            delay = interval;
+        }
+    });
+
+    let previous = app.state::<ScheduleTimers>().0.lock().unwrap().insert(schedule.id.clone(), timer);
+    if let Some(previous) = previous {
+        previous.abort();
+    }
+}
+
+async fn run_scheduled(app: &AppHandle, schedule_id: &str) {
+    let Some(schedule) = load_schedules(app)
+        .ok()
+        .and_then(|all| all.into_iter().find(|s| s.id == schedule_id))
+    else {
+        return;
+    };
+
+    let tick = |status: &str, archive: Option<String>, pruned: usize, error: Option<String>| BackupTick {
+        schedule_id: schedule.id.clone(),
+        profile_id: schedule.profile_id.clone(),
+        status: status.to_string(),
+        archive,
+        pruned,
+        error,
+    };
+    let _ = app.emit("backup_tick", tick("RUNNING", None, 0, None));
+
+    let outcome = match profiles::find_profile(app, &schedule.profile_id) {
+        Ok(profile) => backup::run_profile_backup(app, &profile).await,
+        Err(e) => Err(e),
+    };
+
+    let event = match outcome {
+        Ok(archive) => {
+            let keep = deps::load_config(app).backup_retention.max(1);
+            let dir = backup::profile_backup_dir(app, &schedule.profile_id);
+            let pruned = backup::prune_backups(&dir, keep).unwrap_or_else(|e| {
+                let _ = app.emit("log", format!("Backup pruning failed: {}", e));
+                0
+            });
+            let _ = app.emit("log", format!("Scheduled backup written: {}", archive.display()));
+            tick("DONE", Some(archive.to_string_lossy().to_string()), pruned, None)
+        }
+        Err(e) => {
+            let _ = app.emit("log", format!("Scheduled backup failed ({}): {}", schedule.profile_id, e));
+            tick("FAILED", None, 0, Some(e))
+        }
+    };
+    let _ = app.emit("backup_tick", event);
+
+    // Record the attempt so a restart keeps the cadence
+    if let Ok(mut schedules) = load_schedules(app) {
+        if let Some(s) = schedules.iter_mut().find(|s| s.id == schedule_id) {
+            s.last_run = Some(report::now_secs());
+            let _ = save_schedules(app, &schedules);
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Emitter};
+
+use crate::{db, grants};
+
+/// An object present on both sides with a different definition
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SchemaDifference {
+    pub object: String, // e.g. "column public.users.email"
+    pub source: String,
+    pub dest: String,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SchemaDiff {
+    pub only_in_source: Vec<String>,
+    pub only_in_dest: Vec<String>,
+    pub differing: Vec<SchemaDifference>,
+}
+
+/// Splits a SQL script into statements on `;`, ignoring semicolons inside
+/// quotes and dollar-quoted bodies. `--` comment lines are dropped.
+fn statements(sql: &str) -> Vec<String> {
+    let text: String = sql
+        .lines()
+        .filter(|l| !l.trim_start().starts_with("--"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut out = vec![];
+    let mut current = String::new();
+    let mut quote: Option<String> = None; // "'", "\"" or a $tag$
+    let mut rest = text.as_str();
+    while let Some(c) = rest.chars().next() {
+        if let Some(q) = &quote {
+            if rest.starts_with(q.as_str()) {
+                current.push_str(q);
+                rest = &rest[q.len()..];
+                quote = None;
+                continue;
+            }
+        } else if c == '\'' || c == '"' {
+            quote = Some(c.to_string());
+        } else if c == '$' {
+            let tag_len = rest[1..].find('$').filter(|n| rest[1..1 + n].chars().all(|c| c.is_alphanumeric() || c == '_'));
+            if let Some(n) = tag_len {
+                let tag = &rest[..n + 2];
+                current.push_str(tag);
+                rest = &rest[tag.len()..];
+                quote = Some(tag.to_string());
+                continue;
+            }
+        } else if c == ';' {
+            out.push(std::mem::take(&mut current));
+            rest = &rest[1..];
+            continue;
+        }
+        current.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out.push(current);
+    out.into_iter().map(|s| normalize(&s)).filter(|s| !s.is_empty()).collect()
+}
+
+/// Collapses whitespace so formatting differences don't show up as changes
+fn normalize(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Splits `a, b(c, d), e` on top-level commas, skipping quoted text
+fn split_top_level(list: &str) -> Vec<String> {
+    let mut parts = vec![];
+    let (mut depth, mut start, mut quoted) = (0i32, 0, false);
+    for (i, c) in list.char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            _ if quoted => {}
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(list[start..i].trim().to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(list[start..].trim().to_string());
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+/// "public.users" -> Some("public"); unqualified names have no schema
+fn schema_of(name: &str) -> Option<&str> {
+    name.split_once('.').map(|(s, _)| s.trim_matches('"'))
+}
+
+/// Tables, columns, indexes and functions keyed by kind and qualified name,
+/// with their normalized definitions. Platform-managed schemas are left out.
+fn schema_objects(sql: &str) -> BTreeMap<String, String> {
+    let mut objects = BTreeMap::new();
+    for stmt in statements(sql) {
+        if let Some(rest) = stmt.strip_prefix("CREATE TABLE ").or_else(|| stmt.strip_prefix("CREATE UNLOGGED TABLE ")) {
+            let (Some(open), Some(close)) = (rest.find('('), rest.rfind(')')) else { continue };
+            let table = rest[..open].trim();
+            if schema_of(table).is_some_and(grants::is_managed_schema) {
+                continue;
+            }
+            let mut columns = vec![];
+            for item in split_top_level(&rest[open + 1..close]) {
+                if item.starts_with("CONSTRAINT ") {
+                    continue;
+                }
+                let name = item.split_whitespace().next().unwrap_or_default().to_string();
+                objects.insert(format!("column {}.{}", table, name), item.clone());
+                columns.push(name);
+            }
+            columns.sort();
+            objects.insert(format!("table {}", table), columns.join(", "));
+        } else if stmt.starts_with("CREATE INDEX ") || stmt.starts_with("CREATE UNIQUE INDEX ") {
+            let Some((head, target)) = stmt.split_once(" ON ") else { continue };
+            let name = head.split_whitespace().last().unwrap_or_default();
+            let table = target.trim_start_matches("ONLY ").split_whitespace().next().unwrap_or_default();
+            let Some(schema) = schema_of(table) else { continue };
+            if !grants::is_managed_schema(schema) {
+                objects.insert(format!("index {}.{}", schema, name), stmt.clone());
+            }
+        } else if let Some(rest) = stmt.strip_prefix("CREATE FUNCTION ").or_else(|| stmt.strip_prefix("CREATE PROCEDURE ")) {
+            // The signature ends at the parenthesis closing the argument list
+            let mut depth = 0;
+            let end = rest.char_indices().find_map(|(i, c)| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(i + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                None
+            });
+            let signature = &rest[..end.unwrap_or(rest.len())];
+            if !schema_of(signature).is_some_and(grants::is_managed_schema) {
+                objects.insert(format!("function {}", signature), stmt.clone());
+            }
+        }
+    }
+    objects
+}
+
+/// Compares two normalized object maps
+fn diff(source: &BTreeMap<String, String>, dest: &BTreeMap<String, String>) -> SchemaDiff {
+    let mut result = SchemaDiff { only_in_source: vec![], only_in_dest: vec![], differing: vec![] };
+    for (object, def) in source {
+        match dest.get(object) {
+            None => result.only_in_source.push(object.clone()),
+            Some(other) if other != def => result.differing.push(SchemaDifference {
+                object: object.clone(),
+                source: def.clone(),
+                dest: other.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    result.only_in_dest = dest.keys().filter(|k| !source.contains_key(*k)).cloned().collect();
+    result
+}
+
+/// Dumps both schemas (`pg_dump --schema-only`) and reports tables, columns,
+/// indexes and functions that are missing on either side or defined differently
+pub async fn diff_schema(app: &AppHandle, source_db: &str, dest_db: &str) -> Result<SchemaDiff, String> {
+    let _ = app.emit("log", "Schema diff: dumping source schema...");
+    let source = schema_objects(&db::dump_schema(app, source_db).await?);
+    let _ = app.emit("log", "Schema diff: dumping destination schema...");
+    let dest = schema_objects(&db::dump_schema(app, dest_db).await?);
+
+    let result = diff(&source, &dest);
+    let _ = app.emit("log", format!(
+        "Schema diff: {} only in source, {} only in destination, {} differing",
+        result.only_in_source.len(),
+        result.only_in_dest.len(),
+        result.differing.len()
+    ));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trimmed from `pg_dump --schema-only` output
+    const DUMP: &str = r#"--
+-- PostgreSQL database dump
+--
+
+SET statement_timeout = 0;
+SELECT pg_catalog.set_config('search_path', '', false);
+
+--
+-- Name: add(integer, integer); Type: FUNCTION; Schema: public; Owner: postgres
+--
+
+CREATE FUNCTION public.add(a integer, b integer) RETURNS integer
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  RAISE NOTICE 'adding; %', a;
+  RETURN a + b;
+END;
+$$;
+
+CREATE FUNCTION public.add(a numeric, b numeric) RETURNS numeric
+    LANGUAGE sql IMMUTABLE
+    AS $_$SELECT $1 + $2;$_$;
+
+CREATE TABLE public.notes (
+    id bigint NOT NULL,
+    body text DEFAULT 'it''s; fine, really'::text,
+    tags text[] DEFAULT '{}'::text[],
+    CONSTRAINT notes_body_check CHECK ((length(body) < 1000))
+);
+
+CREATE TABLE auth.users (
+    id uuid NOT NULL
+);
+
+CREATE UNIQUE INDEX notes_id_key ON ONLY public.notes USING btree (id);
+
+CREATE INDEX users_id_idx ON auth.users USING btree (id);
+"#;
+
+    #[test]
+    fn statements_keep_quoted_and_dollar_quoted_semicolons() {
+        let stmts = statements(DUMP);
+        assert_eq!(stmts.len(), 8, "{:#?}", stmts);
+        assert_eq!(stmts[1], "SELECT pg_catalog.set_config('search_path', '', false)");
+        assert!(stmts[2].ends_with("RAISE NOTICE 'adding; %', a; RETURN a + b; END; $$"), "{}", stmts[2]);
+        assert!(stmts[3].ends_with("AS $_$SELECT $1 + $2;$_$"), "{}", stmts[3]);
+        assert!(stmts[4].contains("DEFAULT 'it''s; fine, really'::text"), "{}", stmts[4]);
+    }
+
+    #[test]
+    fn schema_objects_from_a_dump() {
+        let objects = schema_objects(DUMP);
+        let keys: Vec<&str> = objects.keys().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            [
+                "column public.notes.body",
+                "column public.notes.id",
+                "column public.notes.tags",
+                "function public.add(a integer, b integer)",
+                "function public.add(a numeric, b numeric)",
+                "index public.notes_id_key",
+                "table public.notes",
+            ]
+        );
+        assert_eq!(objects["table public.notes"], "body, id, tags");
+        assert_eq!(objects["column public.notes.body"], "body text DEFAULT 'it''s; fine, really'::text");
+        assert!(objects["index public.notes_id_key"].starts_with("CREATE UNIQUE INDEX notes_id_key ON ONLY public.notes"));
+    }
+
+    #[test]
+    fn changed_overload_is_reported_on_its_own() {
+        let changed = DUMP.replace("$_$SELECT $1 + $2;$_$", "$_$SELECT $2 + $1;$_$");
+        let result = diff(&schema_objects(DUMP), &schema_objects(&changed));
+        assert!(result.only_in_source.is_empty() && result.only_in_dest.is_empty());
+        let objects: Vec<&str> = result.differing.iter().map(|d| d.object.as_str()).collect();
+        assert_eq!(objects, ["function public.add(a numeric, b numeric)"]);
+    }
+}
@@ -0,0 +1,126 @@
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::num::NonZeroU32;
+
+/// Marks a sealed value: enc:v1:<base64(salt | nonce | ciphertext+tag)>
+const PREFIX: &str = "enc:v1:";
+const SALT_LEN: usize = 16;
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<LessSafeKey, String> {
+    let mut key = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    let unbound = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| "Invalid encryption key".to_string())?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
+
+/// Encrypts a secret with a passphrase (AES-256-GCM, PBKDF2-derived key)
+pub fn seal(passphrase: &str, plaintext: &str) -> Result<String, String> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt).map_err(|_| "No secure randomness available".to_string())?;
+    rng.fill(&mut nonce).map_err(|_| "No secure randomness available".to_string())?;
+
+    let mut data = plaintext.as_bytes().to_vec();
+    derive_key(passphrase, &salt)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| "Encryption failed".to_string())?;
+
+    let mut packed = salt.to_vec();
+    packed.extend_from_slice(&nonce);
+    packed.extend_from_slice(&data);
+    Ok(format!("{}{}", PREFIX, base64::engine::general_purpose::STANDARD.encode(packed)))
+}
+
+/// Decrypts a value produced by `seal`. A wrong passphrase is an error.
+pub fn open(passphrase: &str, sealed: &str) -> Result<String, String> {
+    let encoded = sealed.strip_prefix(PREFIX).ok_or("Value is not encrypted")?;
+    let packed = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Corrupt encrypted value: {}", e))?;
+    if packed.len() < SALT_LEN + NONCE_LEN {
+        return Err("Corrupt encrypted value: too short".to_string());
+    }
+
+    let (salt, rest) = packed.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| "Corrupt encrypted value".to_string())?;
+
+    let mut data = ciphertext.to_vec();
+    let plain = derive_key(passphrase, salt)?
+        .open_in_place(nonce, Aad::empty(), &mut data)
+        .map_err(|_| "Wrong passphrase or corrupt encrypted value".to_string())?;
+    String::from_utf8(plain.to_vec()).map_err(|e| e.to_string())
+}
+
+/// Seals the named string fields of a JSON object in place (empty values are left as-is)
+pub fn seal_fields(value: &mut serde_json::Value, fields: &[&str], passphrase: &str) -> Result<(), String> {
+    for field in fields {
+        if let Some(serde_json::Value::String(s)) = value.get_mut(*field) {
+            if !s.is_empty() && !is_sealed(s) {
+                *s = seal(passphrase, s)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reverses `seal_fields`
+pub fn open_fields(value: &mut serde_json::Value, fields: &[&str], passphrase: &str) -> Result<(), String> {
+    for field in fields {
+        if let Some(serde_json::Value::String(s)) = value.get_mut(*field) {
+            if is_sealed(s) {
+                *s = open(passphrase, s)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_value_opens_only_with_its_passphrase() {
+        let sealed = seal("correct horse", "service-role-key").unwrap();
+        assert!(is_sealed(&sealed) && !sealed.contains("service-role-key"));
+        assert_ne!(sealed, seal("correct horse", "service-role-key").unwrap(), "salt and nonce must be fresh");
+        assert_eq!(open("correct horse", &sealed).unwrap(), "service-role-key");
+
+        let wrong = open("battery staple", &sealed).unwrap_err();
+        assert!(wrong.contains("Wrong passphrase"), "{}", wrong);
+        assert!(open("correct horse", "service-role-key").is_err());
+        assert!(open("correct horse", "enc:v1:AAAA").unwrap_err().contains("too short"));
+    }
+
+    #[test]
+    fn seal_fields_touches_only_named_non_empty_strings() {
+        let mut profile = serde_json::json!({ "name": "prod", "source_key": "secret", "dest_key": "", "db_url": null });
+        seal_fields(&mut profile, &["source_key", "dest_key", "db_url"], "pass").unwrap();
+        assert!(is_sealed(profile["source_key"].as_str().unwrap()));
+        assert_eq!((profile["name"].as_str(), profile["dest_key"].as_str()), (Some("prod"), Some("")));
+        assert!(profile["db_url"].is_null());
+
+        // Already sealed values are not sealed twice
+        let once = profile.clone();
+        seal_fields(&mut profile, &["source_key"], "pass").unwrap();
+        assert_eq!(profile, once);
+
+        open_fields(&mut profile, &["source_key", "dest_key"], "pass").unwrap();
+        assert_eq!(profile["source_key"], "secret");
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Window};
+
+use crate::db::{quote_ident, quote_literal, Psql};
+use crate::grants::MANAGED_SCHEMAS;
+
+/// How one destination sequence was brought in line after a data restore
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SequenceStatus {
+    pub sequence: String, // schema.name
+    pub owned_by: Option<String>, // schema.table.column for serial/identity sequences
+    pub source_value: Option<i64>, // source last_value; None if never used
+    pub dest_max: Option<i64>, // MAX(owning column) on the destination
+    pub set_to: Option<i64>,
+    pub status: String, // ADJUSTED, UNUSED, MISSING, FAILED
+    pub error: Option<String>,
+}
+
+/// Sequence plus its owning column (if any) for every non-platform sequence
+fn sequences_sql() -> String {
+    format!(
+        "SELECT n.nspname, c.relname, tn.nspname, t.relname, a.attname \
+         FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+         LEFT JOIN pg_depend d ON d.objid = c.oid AND d.classid = 'pg_class'::regclass \
+           AND d.refclassid = 'pg_class'::regclass AND d.deptype IN ('a', 'i') \
+         LEFT JOIN pg_class t ON t.oid = d.refobjid \
+         LEFT JOIN pg_namespace tn ON tn.oid = t.relnamespace \
+         LEFT JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid \
+         WHERE c.relkind = 'S' AND n.nspname NOT IN ({}) ORDER BY 1, 2",
+        MANAGED_SCHEMAS
+    )
+}
+
+/// After data is restored, moves each destination sequence past both the
+/// source's `last_value` and the highest value already in its owning column,
+/// so the next insert doesn't collide with a migrated primary key.
+/// Sequences in platform-managed schemas are left alone.
+pub async fn migrate_sequences(
+    window: &Window,
+    app: &AppHandle,
+    source_db: &str,
+    dest_db: &str,
+) -> Result<Vec<SequenceStatus>, String> {
+    let psql = Psql::locate(app)?;
+
+    // last_value is NULL (empty) for sequences that were never called
+    let source_values: HashMap<String, Option<i64>> = psql
+        .query(
+            source_db,
+            &format!(
+                "SELECT schemaname, sequencename, last_value FROM pg_sequences WHERE schemaname NOT IN ({})",
+                MANAGED_SCHEMAS
+            ),
+        )
+        .await?
+        .into_iter()
+        .filter(|r| r.len() == 3)
+        .map(|r| (format!("{}.{}", r[0], r[1]), r[2].parse().ok()))
+        .collect();
+    let dest_sequences = psql.query(dest_db, &sequences_sql()).await?;
+
+    let mut results = vec![];
+    for row in dest_sequences.into_iter().filter(|r| r.len() == 5) {
+        let name = format!("{}.{}", row[0], row[1]);
+        let qualified = format!("{}.{}", quote_ident(&row[0]), quote_ident(&row[1]));
+        let owned_by = (!row[4].is_empty()).then(|| format!("{}.{}.{}", row[2], row[3], row[4]));
+        let mut status = SequenceStatus {
+            sequence: name.clone(),
+            owned_by: owned_by.clone(),
+            source_value: source_values.get(&name).copied().flatten(),
+            dest_max: None,
+            set_to: None,
+            status: "UNUSED".to_string(),
+            error: None,
+        };
+        if !source_values.contains_key(&name) {
+            status.status = "MISSING".to_string(); // only on the destination
+        }
+
+        if owned_by.is_some() {
+            let max_sql = format!(
+                "SELECT MAX({}) FROM {}.{}",
+                quote_ident(&row[4]),
+                quote_ident(&row[2]),
+                quote_ident(&row[3])
+            );
+            match psql.query(dest_db, &max_sql).await {
+                Ok(rows) => status.dest_max = rows.first().and_then(|r| r.first()).and_then(|v| v.parse().ok()),
+                Err(e) => {
+                    status.status = "FAILED".to_string();
+                    status.error = Some(e);
+                    results.push(status);
+                    continue;
+                }
+            }
+        }
+
+        // Nothing to move past: leave the sequence at its start value
+        let Some(target) = status.source_value.max(status.dest_max).filter(|v| *v > 0) else {
+            results.push(status);
+            continue;
+        };
+        let setval = format!("SELECT setval({}, {}, true)", quote_literal(&qualified), target);
+        match psql.execute(dest_db, &[setval]).await {
+            Ok(_) => {
+                status.set_to = Some(target);
+                status.status = "ADJUSTED".to_string();
+            }
+            Err(e) => {
+                status.status = "FAILED".to_string();
+                status.error = Some(e);
+            }
+        }
+        results.push(status);
+    }
+
+    let count = |s: &str| results.iter().filter(|r| r.status == s).count();
+    window.emit("log", format!(
+        "Sequences: {} adjusted, {} unused, {} failed",
+        count("ADJUSTED"),
+        count("UNUSED"),
+        count("FAILED")
+    )).unwrap();
+    for failed in results.iter().filter(|r| r.status == "FAILED") {
+        window.emit("log", format!("  Could not reset {}: {}", failed.sequence, failed.error.as_deref().unwrap_or_default())).unwrap();
+    }
+    Ok(results)
+}
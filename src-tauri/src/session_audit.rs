@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::fs;
+use tauri::AppHandle;
+
+use crate::{paths, telemetry};
+
+/// One way a session's telemetry doesn't add up
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct Inconsistency {
+    pub kind: String, // UNFINISHED_MIGRATION, ORPHAN_COMPLETE, UNCLOSED_SPAN, ORPHAN_SPAN_END, ABORTED_SPAN, BUCKET_COUNT_MISMATCH, UNREADABLE_EVENT
+    pub line: usize, // 1-based line in the telemetry log
+    pub event_type: String,
+    pub message: String,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SessionAudit {
+    pub session_id: String,
+    pub events: usize,
+    pub inconsistencies: Vec<Inconsistency>,
+}
+
+/// Counts for the migration currently open in the log
+#[derive(Default)]
+struct OpenMigration {
+    line: usize,
+    planned_buckets: Option<u64>, // STORAGE_RUN_START data.buckets
+    bucket_spans: u64,            // STORAGE_BUCKET_END events
+}
+
+/// Replays a session's telemetry log (see `telemetry::track_event`) and
+/// cross-checks start/end pairs: every MIGRATION_START must reach
+/// MIGRATION_COMPLETE or a recorded stop, every span must end, and the
+/// bucket counts in the run, bucket and completion events must agree.
+pub fn audit_session(app: &AppHandle, session_id: &str) -> Result<SessionAudit, String> {
+    if session_id.is_empty() || session_id.contains(['/', '\\']) || session_id.contains("..") {
+        return Err(format!("Invalid session id: {}", session_id));
+    }
+    let path = telemetry::session_log_path(&paths::get_logs_dir(app), session_id);
+    let data = fs::read_to_string(&path).map_err(|e| format!("No telemetry log for session {}: {}", session_id, e))?;
+
+    let mut audit = SessionAudit { session_id: session_id.to_string(), events: 0, inconsistencies: vec![] };
+    let mut flag = |kind: &str, line: usize, event_type: &str, message: String| {
+        audit.inconsistencies.push(Inconsistency {
+            kind: kind.to_string(),
+            line,
+            event_type: event_type.to_string(),
+            message,
+        })
+    };
+
+    let mut events = 0;
+    let mut migration: Option<OpenMigration> = None;
+    // span_id -> (line, event type) for spans not yet ended
+    let mut open_spans: HashMap<String, (usize, String)> = HashMap::new();
+    for (index, text) in data.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let line = index + 1;
+        let event: telemetry::TelemetryEvent = match serde_json::from_str(text) {
+            Ok(event) => event,
+            Err(e) => {
+                flag("UNREADABLE_EVENT", line, "", format!("Not a telemetry event: {}", e));
+                continue;
+            }
+        };
+        events += 1;
+        let kind = event.event_type.as_str();
+        let payload = &event.payload;
+
+        match kind {
+            "MIGRATION_START" => {
+                if let Some(open) = migration.take() {
+                    flag("UNFINISHED_MIGRATION", open.line, "MIGRATION_START", format!(
+                        "Migration started on line {} never completed or stopped before the next one began",
+                        open.line
+                    ));
+                }
+                migration = Some(OpenMigration { line, ..Default::default() });
+            }
+            "MIGRATION_COMPLETE" => match migration.take() {
+                Some(open) => {
+                    let processed = payload["buckets_processed"].as_u64();
+                    if let (Some(planned), Some(processed)) = (open.planned_buckets, processed) {
+                        if planned != processed {
+                            flag("BUCKET_COUNT_MISMATCH", line, kind, format!(
+                                "Run planned {} buckets but completion reports {}",
+                                planned, processed
+                            ));
+                        }
+                    }
+                    if let Some(processed) = processed.filter(|p| *p != open.bucket_spans) {
+                        flag("BUCKET_COUNT_MISMATCH", line, kind, format!(
+                            "Completion reports {} buckets but {} bucket spans were recorded",
+                            processed, open.bucket_spans
+                        ));
+                    }
+                }
+                None => flag("ORPHAN_COMPLETE", line, kind, "MIGRATION_COMPLETE without a MIGRATION_START".to_string()),
+            },
+            "OPERATION_STOPPED" if payload["operation"] == "migration" => {
+                migration = None;
+            }
+            _ => {}
+        }
+
+        let span_id = payload["span_id"].as_str().map(str::to_string);
+        if let (Some(name), Some(span_id)) = (kind.strip_suffix("_START"), span_id.clone()) {
+            open_spans.insert(span_id, (line, name.to_string()));
+            if name == "STORAGE_RUN" {
+                if let Some(open) = migration.as_mut() {
+                    open.planned_buckets = payload["data"]["buckets"].as_u64();
+                }
+            }
+        } else if let (Some(name), Some(span_id)) = (kind.strip_suffix("_END"), span_id) {
+            if open_spans.remove(&span_id).is_none() {
+                flag("ORPHAN_SPAN_END", line, kind, format!("{} span {} ended without starting", name, span_id));
+            }
+            if payload["aborted"] == true {
+                flag("ABORTED_SPAN", line, kind, format!("{} span {} was dropped before it ended", name, span_id));
+            }
+            if name == "STORAGE_BUCKET" {
+                if let Some(open) = migration.as_mut() {
+                    open.bucket_spans += 1;
+                }
+            }
+        }
+    }
+
+    if let Some(open) = migration {
+        flag("UNFINISHED_MIGRATION", open.line, "MIGRATION_START", format!(
+            "Migration started on line {} has no MIGRATION_COMPLETE or stop event; the session may have crashed",
+            open.line
+        ));
+    }
+    let mut unclosed: Vec<(usize, String, String)> =
+        open_spans.into_iter().map(|(id, (line, name))| (line, name, id)).collect();
+    unclosed.sort();
+    for (line, name, span_id) in unclosed {
+        flag("UNCLOSED_SPAN", line, &format!("{}_START", name), format!("{} span {} never ended", name, span_id));
+    }
+
+    audit.events = events;
+    audit.inconsistencies.sort_by_key(|i| i.line);
+    Ok(audit)
+}
@@ -0,0 +1,363 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, Window};
+
+use crate::{deps, paths, report, schedules, secrets};
+
+/// Bumped whenever the archive layout changes; older apps refuse newer snapshots
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Config/profile fields that hold credentials and are sealed in the archive
+const CONFIG_SECRETS: [&str; 1] = ["supabase_key"];
+const PROFILE_SECRETS: [&str; 3] = ["source_key", "dest_key", "db_url"];
+const S3_SECRETS: [&str; 1] = ["secret_access_key"];
+
+/// The S3 credential objects nested under config.s3
+fn s3_credentials(config: &mut serde_json::Value) -> impl Iterator<Item = &mut serde_json::Value> {
+    config
+        .get_mut("s3")
+        .and_then(|s3| s3.as_object_mut())
+        .into_iter()
+        .flat_map(|s3| s3.iter_mut())
+        .filter(|(side, _)| *side == "source" || *side == "dest")
+        .map(|(_, creds)| creds)
+}
+
+/// snapshot.json at the archive root
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    pub app_version: String,
+    pub created_at: u64,        // unix seconds
+    pub packages: Vec<String>, // installed driver packs (binaries are re-downloaded)
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RestoreSummary {
+    pub restored: Vec<String>,
+    pub packages_reinstalled: Vec<String>,
+    pub packages_failed: Vec<String>,
+}
+
+/// Where the archived app state lives on disk
+struct SnapshotPaths {
+    config: PathBuf,
+    profiles: PathBuf,
+    schedules: PathBuf,
+    release: PathBuf,
+    logs_dir: PathBuf,
+}
+
+impl SnapshotPaths {
+    fn for_app(app: &AppHandle, mgr: &deps::PulseManager) -> Self {
+        Self {
+            config: deps::config_path(app),
+            profiles: paths::get_profiles_path(app),
+            schedules: schedules::schedules_path(app),
+            release: mgr.release_path().clone(),
+            logs_dir: paths::get_logs_dir(app),
+        }
+    }
+}
+
+fn append(archive: &mut tar::Builder<GzEncoder<File>>, path: &str, data: &[u8]) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(report::now_secs());
+    header.set_cksum();
+    archive
+        .append_data(&mut header, path, data)
+        .map_err(|e| format!("Cannot add {} to snapshot: {}", path, e))
+}
+
+fn read_json(path: &Path) -> Result<Option<serde_json::Value>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data)
+        .map(Some)
+        .map_err(|e| format!("Corrupt {}: {}", path.display(), e))
+}
+
+fn to_bytes(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| e.to_string())
+}
+
+/// Bundles config, profiles, schedules, release state, the installed package
+/// list and logs into a .tar.gz. Credentials are sealed with `passphrase`.
+pub fn create_app_snapshot(app: &AppHandle, out_path: &str, passphrase: &str) -> Result<String, String> {
+    if passphrase.is_empty() {
+        return Err("A passphrase is required to protect credentials in the snapshot.".to_string());
+    }
+
+    let mgr = deps::PulseManager::new(app);
+    write_snapshot(&SnapshotPaths::for_app(app, &mgr), mgr.installed_packages(), out_path, passphrase)
+}
+
+fn write_snapshot(paths: &SnapshotPaths, packages: Vec<String>, out_path: &str, passphrase: &str) -> Result<String, String> {
+    let manifest = SnapshotManifest {
+        version: SNAPSHOT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: report::now_secs(),
+        packages,
+    };
+
+    let file = File::create(out_path).map_err(|e| format!("Cannot create {}: {}", out_path, e))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    append(&mut archive, "snapshot.json", &serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?)?;
+
+    if let Some(mut config) = read_json(&paths.config)? {
+        secrets::seal_fields(&mut config, &CONFIG_SECRETS, passphrase)?;
+        for creds in s3_credentials(&mut config) {
+            secrets::seal_fields(creds, &S3_SECRETS, passphrase)?;
+        }
+        append(&mut archive, "config.json", &to_bytes(&config)?)?;
+    }
+    if let Some(mut profiles) = read_json(&paths.profiles)? {
+        if let Some(list) = profiles.as_array_mut() {
+            for profile in list {
+                secrets::seal_fields(profile, &PROFILE_SECRETS, passphrase)?;
+            }
+        }
+        append(&mut archive, "profiles.json", &to_bytes(&profiles)?)?;
+    }
+    for (name, path) in [("schedules.json", &paths.schedules), ("release.json", &paths.release)] {
+        if path.exists() {
+            append(&mut archive, name, &fs::read(path).map_err(|e| e.to_string())?)?;
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir(&paths.logs_dir) {
+        for entry in entries.filter_map(|e| e.ok()).filter(|e| e.path().is_file()) {
+            let name = format!("logs/{}", entry.file_name().to_string_lossy());
+            append(&mut archive, &name, &fs::read(entry.path()).map_err(|e| e.to_string())?)?;
+        }
+    }
+
+    let encoder = archive.into_inner().map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())?;
+    Ok(out_path.to_string())
+}
+
+/// Restores a snapshot over the current app state, then re-arms schedules
+/// and re-downloads driver packs that were installed on the old machine
+pub async fn restore_app_snapshot(window: &Window, in_path: &str, passphrase: &str) -> Result<RestoreSummary, String> {
+    let app = window.app_handle();
+    let mgr = deps::PulseManager::new(app);
+    let (manifest, restored) = unpack_snapshot(&SnapshotPaths::for_app(app, &mgr), in_path, passphrase)?;
+
+    schedules::rearm_all(app);
+
+    // Fresh manager so installs use the restored config (channel, network settings)
+    let mgr = deps::PulseManager::new(app);
+    let mut packages_reinstalled = vec![];
+    let mut packages_failed = vec![];
+    let installed = mgr.installed_packages();
+    for package in manifest.packages.iter().filter(|p| !installed.contains(p)) {
+        window.emit("log", format!("Snapshot: re-downloading driver pack {}...", package)).unwrap();
+        match mgr.install_latest(window, package).await {
+            Ok(_) => packages_reinstalled.push(package.clone()),
+            Err(e) => {
+                window.emit("log", format!("Could not reinstall {}: {}", package, e)).unwrap();
+                packages_failed.push(package.clone());
+            }
+        }
+    }
+
+    window.emit("log", format!("Snapshot restored ({} files).", restored.len())).unwrap();
+    Ok(RestoreSummary {
+        restored,
+        packages_reinstalled,
+        packages_failed,
+    })
+}
+
+/// Checks the archive version, decrypts the credentials and writes the files
+/// back. Nothing is written unless every sealed value opens.
+fn unpack_snapshot(paths: &SnapshotPaths, in_path: &str, passphrase: &str) -> Result<(SnapshotManifest, Vec<String>), String> {
+    let file = File::open(in_path).map_err(|e| format!("Cannot open {}: {}", in_path, e))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut files: HashMap<String, Vec<u8>> = HashMap::new();
+    for entry in archive.entries().map_err(|e| format!("Not a snapshot archive: {}", e))? {
+        let mut entry = entry.map_err(|e| e.to_string())?;
+        let name = entry.path().map_err(|e| e.to_string())?.to_string_lossy().to_string();
+        let mut data = vec![];
+        entry.read_to_end(&mut data).map_err(|e| e.to_string())?;
+        files.insert(name, data);
+    }
+
+    let manifest: SnapshotManifest = files
+        .get("snapshot.json")
+        .ok_or("Not a DevPulse snapshot (snapshot.json missing)")
+        .and_then(|d| serde_json::from_slice(d).map_err(|_| "Corrupt snapshot.json"))?;
+    if manifest.version != SNAPSHOT_VERSION {
+        return Err(format!(
+            "Unsupported snapshot version {} (this app reads version {}).",
+            manifest.version, SNAPSHOT_VERSION
+        ));
+    }
+
+    // Decrypt everything before writing anything, so a wrong passphrase changes nothing
+    let parse = |name: &str| -> Result<Option<serde_json::Value>, String> {
+        files
+            .get(name)
+            .map(|d| serde_json::from_slice(d).map_err(|e| format!("Corrupt {}: {}", name, e)))
+            .transpose()
+    };
+    let mut config = parse("config.json")?;
+    if let Some(config) = config.as_mut() {
+        secrets::open_fields(config, &CONFIG_SECRETS, passphrase)?;
+        for creds in s3_credentials(config) {
+            secrets::open_fields(creds, &S3_SECRETS, passphrase)?;
+        }
+    }
+    let mut profiles = parse("profiles.json")?;
+    if let Some(list) = profiles.as_mut().and_then(|p| p.as_array_mut()) {
+        for profile in list {
+            secrets::open_fields(profile, &PROFILE_SECRETS, passphrase)?;
+        }
+    }
+
+    let mut restored = vec![];
+    let mut write = |name: &str, path: &Path, data: &[u8]| -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(path, data).map_err(|e| format!("Cannot restore {}: {}", name, e))?;
+        restored.push(name.to_string());
+        Ok(())
+    };
+
+    if let Some(config) = &config {
+        write("config.json", &paths.config, &to_bytes(config)?)?;
+    }
+    if let Some(profiles) = &profiles {
+        write("profiles.json", &paths.profiles, &to_bytes(profiles)?)?;
+    }
+    if let Some(data) = files.get("schedules.json") {
+        write("schedules.json", &paths.schedules, data)?;
+    }
+    if let Some(data) = files.get("release.json") {
+        write("release.json", &paths.release, data)?;
+    }
+    for (name, data) in files.iter().filter(|(n, _)| n.starts_with("logs/")) {
+        // Only plain file names: never let an entry escape the logs directory
+        let file_name = &name["logs/".len()..];
+        if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name.contains("..") {
+            continue;
+        }
+        write(name, &paths.logs_dir.join(file_name), data)?;
+    }
+    Ok((manifest, restored))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths_in(dir: &Path) -> SnapshotPaths {
+        SnapshotPaths {
+            config: dir.join("config.json"),
+            profiles: dir.join("profiles.json"),
+            schedules: dir.join("schedules.json"),
+            release: dir.join("release.json"),
+            logs_dir: dir.join("logs"),
+        }
+    }
+
+    /// Hand-built archive, for layouts `write_snapshot` never produces
+    fn raw_archive(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut archive = tar::Builder::new(GzEncoder::new(File::create(path).unwrap(), Compression::default()));
+        for (name, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            // Written raw: append_data refuses names with `..`
+            header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(data.len() as u64);
+            header.set_mode(0o600);
+            header.set_cksum();
+            archive.append(&header, *data).unwrap();
+        }
+        archive.into_inner().unwrap().finish().unwrap();
+    }
+
+    fn manifest(version: u32) -> Vec<u8> {
+        serde_json::to_vec(&SnapshotManifest { version, app_version: "1.0.0".to_string(), created_at: 0, packages: vec![] }).unwrap()
+    }
+
+    #[test]
+    fn snapshot_round_trips_with_credentials_sealed() {
+        let (old, new) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (from, to) = (paths_in(old.path()), paths_in(new.path()));
+        let config = serde_json::json!({
+            "channel": "stable",
+            "supabase_key": "config-secret",
+            "s3": { "enabled": true, "source": { "access_key_id": "AK", "secret_access_key": "s3-secret" } },
+        });
+        let profiles = serde_json::json!([{ "name": "prod", "source_key": "profile-secret", "dest_key": "", "db_url": "postgres://x" }]);
+        fs::write(&from.config, config.to_string()).unwrap();
+        fs::write(&from.profiles, profiles.to_string()).unwrap();
+        fs::write(&from.schedules, b"[]").unwrap();
+        fs::create_dir_all(&from.logs_dir).unwrap();
+        fs::write(from.logs_dir.join("session.log"), b"migrated").unwrap();
+
+        let archive = old.path().join("snapshot.tar.gz");
+        let archive = archive.to_str().unwrap();
+        write_snapshot(&from, vec!["postgres-15".to_string()], archive, "pass").unwrap();
+        let mut raw = vec![];
+        GzDecoder::new(File::open(archive).unwrap()).read_to_end(&mut raw).unwrap();
+        let raw = String::from_utf8_lossy(&raw);
+        for secret in ["config-secret", "s3-secret", "profile-secret", "postgres://x"] {
+            assert!(!raw.contains(secret), "{} stored in the clear", secret);
+        }
+
+        assert!(unpack_snapshot(&to, archive, "wrong").unwrap_err().contains("Wrong passphrase"));
+        assert!(fs::read_dir(new.path()).unwrap().next().is_none(), "a wrong passphrase wrote files");
+
+        let (manifest, mut restored) = unpack_snapshot(&to, archive, "pass").unwrap();
+        assert_eq!(manifest.packages, ["postgres-15"]);
+        restored.sort();
+        assert_eq!(restored, ["config.json", "logs/session.log", "profiles.json", "schedules.json"]);
+        let read = |path: &Path| serde_json::from_slice::<serde_json::Value>(&fs::read(path).unwrap()).unwrap();
+        assert_eq!(read(&to.config), config);
+        assert_eq!(read(&to.profiles), profiles);
+        assert_eq!(fs::read(to.logs_dir.join("session.log")).unwrap(), b"migrated");
+    }
+
+    #[test]
+    fn newer_snapshot_versions_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("snapshot.tar.gz");
+        raw_archive(&archive, &[("snapshot.json", &manifest(SNAPSHOT_VERSION + 1)), ("schedules.json", b"[]")]);
+
+        let error = unpack_snapshot(&paths_in(dir.path()), archive.to_str().unwrap(), "pass").unwrap_err();
+        assert!(error.contains("Unsupported snapshot version"), "{}", error);
+        assert!(!dir.path().join("schedules.json").exists());
+    }
+
+    #[test]
+    fn log_entries_cannot_leave_the_logs_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("snapshot.tar.gz");
+        raw_archive(&archive, &[
+            ("snapshot.json", &manifest(SNAPSHOT_VERSION)),
+            ("logs/../escaped.log", b"x"),
+            ("logs/nested/deep.log", b"x"),
+            ("logs/..", b"x"),
+            ("logs/ok.log", b"kept"),
+        ]);
+        let paths = paths_in(&dir.path().join("app"));
+
+        let (_, restored) = unpack_snapshot(&paths, archive.to_str().unwrap(), "pass").unwrap();
+        assert_eq!(restored, ["logs/ok.log"]);
+        assert_eq!(fs::read(paths.logs_dir.join("ok.log")).unwrap(), b"kept");
+        assert!(!dir.path().join("app/escaped.log").exists());
+        assert!(!paths.logs_dir.join("nested").exists());
+    }
+}
@@ -1,118 +1,1568 @@
+use futures::StreamExt;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-#[derive(Serialize, Deserialize, Debug)]
+use crate::deps::PerformanceConfig;
+use crate::http::{self, SupabaseError};
+use crate::s3::{S3Config, S3Endpoint};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[allow(dead_code)]
 pub struct Bucket {
     pub id: String,
     pub name: String,
     pub public: bool,
+    #[serde(default)]
+    pub file_size_limit: Option<u64>, // bytes; None = project default
+    #[serde(default)]
+    pub allowed_mime_types: Option<Vec<String>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Access settings the Storage API neither reads nor writes, so they can't be mirrored
+pub const UNMIRRORED_BUCKET_SETTINGS: &str =
+    "CORS origins and signed-URL expiry are project-level settings the Storage API doesn't expose. Manual step required: copy them in the destination dashboard.";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[allow(dead_code)]
 pub struct StorageObject {
     pub name: String,
+    #[serde(deserialize_with = "null_as_empty")]
     pub id: String,
-    // Add other fields as needed (metadata, etc.)
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Folder placeholders come back from the list endpoint with `"id": null`
+fn null_as_empty<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+impl StorageObject {
+    /// Size recorded in listing metadata (0 when absent, e.g. folders)
+    pub fn size(&self) -> u64 {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m["size"].as_u64())
+            .unwrap_or(0)
+    }
+
+    /// Content-type recorded by the source project, if any
+    pub fn mimetype(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m["mimetype"].as_str())
+            .filter(|m| !m.is_empty() && *m != "application/octet-stream")
+    }
+}
+
+/// Resolves the upload content-type for an object.
+/// Order: source metadata -> file extension -> magic bytes -> octet-stream
+pub fn resolve_content_type(object: &StorageObject, data: &[u8]) -> String {
+    if let Some(mime) = object.mimetype() {
+        return mime.to_string();
+    }
+    if let Some(mime) = content_type_from_extension(&object.name) {
+        return mime.to_string();
+    }
+    sniff_content_type(data)
+        .unwrap_or("application/octet-stream")
+        .to_string()
+}
+
+/// Content-type for a local file: extension -> magic bytes -> octet-stream
+pub fn content_type_for_file(name: &str, data: &[u8]) -> String {
+    content_type_from_extension(name)
+        .or_else(|| sniff_content_type(data))
+        .unwrap_or("application/octet-stream")
+        .to_string()
+}
+
+pub fn content_type_from_extension(name: &str) -> Option<&'static str> {
+    let ext = std::path::Path::new(name).extension()?.to_str()?.to_lowercase();
+    let mime = match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "gz" => "application/gzip",
+        "zip" => "application/zip",
+        "mp4" => "video/mp4",
+        "json" => "application/json",
+        "txt" => "text/plain",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" => "text/javascript",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+/// Last resort: detect the type from the first bytes of the payload
+pub fn sniff_content_type(data: &[u8]) -> Option<&'static str> {
+    infer::get(data).map(|kind| kind.mime_type())
+}
+
+/// Characters escaped inside a single URL path segment. Keys are split on
+/// `/` before encoding, so it only ever appears as a separator.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ').add(b'"').add(b'#').add(b'%').add(b'+').add(b'<').add(b'>')
+    .add(b'?').add(b'[').add(b']').add(b'\\').add(b'^').add(b'`').add(b'{')
+    .add(b'|').add(b'}');
+
+/// Percent-encodes a bucket id or object key for use in a URL path,
+/// keeping `/` between key segments (non-ASCII is UTF-8 encoded)
+pub fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| utf8_percent_encode(segment, PATH_SEGMENT).to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn object_url(base_url: &str, bucket_id: &str, object_name: &str) -> String {
+    format!(
+        "{}/storage/v1/object/{}/{}",
+        base_url,
+        encode_path(bucket_id),
+        encode_path(object_name)
+    )
+}
+
+/// Shared bandwidth cap across all concurrent transfers of one mirror
+struct Throttle {
+    max_bytes_per_sec: u64,
+    state: Mutex<(Instant, u64)>, // (window start, bytes since start)
+}
+
+impl Throttle {
+    fn new(max_bytes_per_sec: u64) -> Self {
+        Self {
+            max_bytes_per_sec,
+            state: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    /// Accounts for `bytes` and sleeps long enough to stay under the cap
+    async fn consume(&self, bytes: usize) {
+        if self.max_bytes_per_sec == 0 {
+            return;
+        }
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            state.1 += bytes as u64;
+            let due = Duration::from_secs_f64(state.1 as f64 / self.max_bytes_per_sec as f64);
+            due.saturating_sub(state.0.elapsed())
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Result of a sampled source/destination comparison
+#[derive(Serialize, Clone, Debug)]
+pub struct SampleCheck {
+    pub key: String,
+    pub content_length_match: bool,
+    pub sampled_match: bool,
+    pub source_length: u64,
+    pub dest_length: u64,
+    pub error: Option<String>, // set when either side could not be fetched
+}
+
+/// Whether the source key can actually download content, not just list it
+#[derive(Serialize, Clone, Debug)]
+pub struct ReadProbe {
+    pub ok: bool,
+    pub object: Option<String>,
+    pub sampled_bytes: u64,
+    pub content_type: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum SampleRange {
+    Head(u64),
+    Tail(u64),
+}
+
+/// What to do when the destination already holds an object at the same key
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CollisionStrategy {
+    #[default]
+    Overwrite,
+    Skip,
+    Rename, // differently-sized objects go to "<name>-migrated.<ext>"
+}
+
+/// Appended to the file stem of renamed objects
+pub const RENAME_SUFFIX: &str = "-migrated";
+
+/// Transfer error marking an object left alone because of a collision
+pub const COLLISION_SKIPPED: &str = "Exists on destination (collision strategy: skip)";
+
+/// "photos/cat.png" -> "photos/cat-migrated.png"
+pub fn renamed_key(key: &str) -> String {
+    let (dir, file) = match key.rsplit_once('/') {
+        Some((dir, file)) => (format!("{}/", dir), file),
+        None => (String::new(), key),
+    };
+    match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}{}{}.{}", dir, stem, RENAME_SUFFIX, ext),
+        _ => format!("{}{}{}", dir, file, RENAME_SUFFIX),
+    }
 }
 
+/// PATCH size the resumable endpoint requires for every chunk but the last
+const RESUMABLE_CHUNK_BYTES: usize = 6 * 1024 * 1024;
+
+/// How long a source listing is reused before re-enumerating
+const LISTING_CACHE_TTL: Duration = Duration::from_secs(300);
+
 pub struct StorageMirror {
     client: Client,
+    listing_cache: Mutex<HashMap<String, (Instant, Vec<StorageObject>)>>,
+    performance: PerformanceConfig,
+    throttle: Arc<Throttle>,
     source_url: String,
     source_key: String,
     dest_url: String,
     dest_key: String,
+    source_s3: Option<S3Endpoint>,
+    dest_s3: Option<S3Endpoint>,
+    collision: CollisionStrategy,
+    signed_uploads_unsupported: AtomicBool, // destination lacks the signed-upload endpoint
+    single_upload_limit: Option<u64>, // larger objects go through the resumable endpoint
+    bucket_remap: BTreeMap<String, String>, // source bucket id -> destination bucket id
+    rate_limits: http::RateLimits,
+    destination_only: bool, // both sides are the destination project
 }
 
 impl StorageMirror {
-    pub fn new(source_url: &str, source_key: &str, dest_url: &str, dest_key: &str) -> Self {
+    pub fn new(client: Client, source_url: &str, source_key: &str, dest_url: &str, dest_key: &str) -> Self {
+        let performance = PerformanceConfig::default();
         Self {
-            client: Client::new(),
+            client,
+            listing_cache: Mutex::new(HashMap::new()),
+            throttle: Arc::new(Throttle::new(performance.max_bytes_per_sec)),
+            performance,
             source_url: source_url.to_string(),
             source_key: source_key.to_string(),
             dest_url: dest_url.to_string(),
             dest_key: dest_key.to_string(),
+            source_s3: None,
+            dest_s3: None,
+            collision: CollisionStrategy::default(),
+            signed_uploads_unsupported: AtomicBool::new(false),
+            single_upload_limit: None,
+            bucket_remap: BTreeMap::new(),
+            rate_limits: http::RateLimits::default(),
+            destination_only: false,
+        }
+    }
+
+    /// Mirror for destination-only operations (wipe, probes, audits)
+    pub fn for_destination(client: Client, dest_url: &str, dest_key: &str) -> Self {
+        Self { destination_only: true, ..Self::new(client, dest_url, dest_key, dest_url, dest_key) }
+    }
+
+    pub fn with_performance(mut self, performance: PerformanceConfig) -> Self {
+        self.throttle = Arc::new(Throttle::new(performance.max_bytes_per_sec));
+        self.performance = performance;
+        self
+    }
+
+    /// Largest object sent as a single POST (see `limits::probe_upload_limit`)
+    pub fn with_upload_limit(mut self, limit: Option<u64>) -> Self {
+        self.single_upload_limit = limit;
+        self
+    }
+
+    pub fn with_collision_strategy(mut self, strategy: CollisionStrategy) -> Self {
+        self.collision = strategy;
+        self
+    }
+
+    /// Records rate-limit headers of every response into `limits` (the app's shared state)
+    pub fn with_rate_limits(mut self, limits: http::RateLimits) -> Self {
+        self.rate_limits = limits;
+        self
+    }
+
+    /// Copies source buckets into differently named destination buckets
+    pub fn with_bucket_remap(mut self, remap: BTreeMap<String, String>) -> Self {
+        self.bucket_remap = remap;
+        self
+    }
+
+    /// Destination bucket a source bucket is copied into (itself unless remapped)
+    pub fn dest_bucket<'a>(&'a self, bucket_id: &'a str) -> &'a str {
+        self.bucket_remap.get(bucket_id).map(String::as_str).unwrap_or(bucket_id)
+    }
+
+    pub fn collision_strategy(&self) -> CollisionStrategy {
+        self.collision
+    }
+
+    /// Destination key for `object` given the size of what already sits at
+    /// its key (None = nothing). None means leave the object alone.
+    pub fn place_object(&self, object: &StorageObject, existing_size: Option<u64>) -> Option<String> {
+        match (self.collision, existing_size) {
+            (CollisionStrategy::Overwrite, _) | (_, None) => Some(object.name.clone()),
+            (CollisionStrategy::Skip, Some(_)) => None,
+            // Same size is taken to be the same object already copied
+            (CollisionStrategy::Rename, Some(size)) if size == object.size() => None,
+            (CollisionStrategy::Rename, Some(_)) => Some(renamed_key(&object.name)),
+        }
+    }
+
+    /// Routes list/get/put through the S3 protocol for sides with credentials
+    pub fn with_s3(mut self, config: &S3Config) -> Self {
+        if config.enabled {
+            // A destination-only mirror signs every request with the destination credentials
+            let source = if self.destination_only { &config.dest } else { &config.source };
+            self.source_s3 = source.clone().map(|c| S3Endpoint::new(&self.source_url, &config.region, c));
+            self.dest_s3 = config.dest.clone().map(|c| S3Endpoint::new(&self.dest_url, &config.region, c));
         }
+        self
+    }
+
+    /// The S3 endpoint serving `base_url`, when S3 transfers are configured for it
+    fn s3_for(&self, base_url: &str) -> Option<&S3Endpoint> {
+        if base_url == self.source_url {
+            self.source_s3.as_ref()
+        } else if base_url == self.dest_url {
+            self.dest_s3.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// GET for an object's content via S3 when configured, else the REST API
+    fn object_request(&self, base_url: &str, key: &str, bucket_id: &str, object_name: &str) -> Result<reqwest::RequestBuilder, String> {
+        match self.s3_for(base_url) {
+            Some(s3) => s3.get_object(&self.client, bucket_id, object_name),
+            None => Ok(self
+                .client
+                .get(object_url(base_url, bucket_id, object_name))
+                .header("Authorization", format!("Bearer {}", key))),
+        }
+    }
+
+    pub fn performance(&self) -> &PerformanceConfig {
+        &self.performance
     }
 
     pub async fn list_source_buckets(&self) -> Result<Vec<Bucket>, String> {
-        let url = format!("{}/storage/v1/bucket", self.source_url);
+        self.list_buckets_at(&self.source_url, &self.source_key).await
+    }
+
+    pub async fn list_dest_buckets(&self) -> Result<Vec<Bucket>, String> {
+        self.list_buckets_at(&self.dest_url, &self.dest_key).await
+    }
+
+    async fn list_buckets_at(&self, base_url: &str, key: &str) -> Result<Vec<Bucket>, String> {
+        let url = format!("{}/storage/v1/bucket", base_url);
         let res = self
             .client
             .get(&url)
-            .header("Authorization", format!("Bearer {}", self.source_key))
+            .header("Authorization", format!("Bearer {}", key))
             .send()
             .await
+            .inspect(|r| self.rate_limits.observe(r))
             .map_err(|e| e.to_string())?;
 
         if !res.status().is_success() {
-            return Err(format!("Failed to list buckets: {}", res.status()));
+            return Err(format!("Failed to list buckets: {}", SupabaseError::from_response(res).await));
         }
 
         res.json::<Vec<Bucket>>().await.map_err(|e| e.to_string())
     }
 
-    pub async fn list_objects(&self, bucket_id: &str) -> Result<Vec<StorageObject>, String> {
-        let url = format!("{}/storage/v1/object/list/{}", self.source_url, bucket_id);
+    /// Create bucket on destination. Safe to repeat: a bucket that already
+    /// exists counts as created, and transient failures are retried.
+    pub async fn create_dest_bucket(&self, bucket_id: &str, public: bool) -> Result<(), String> {
+        let body = serde_json::json!({ "id": bucket_id, "name": bucket_id, "public": public });
+        self.post_bucket(&body).await?;
+        Ok(())
+    }
 
-        // Supabase list objects is a POST with prefix/limit/offset
-        let body = serde_json::json!({
-            "prefix": "",
-            "limit": 100,
-            "offset": 0,
-            "sortBy": {
-                "column": "name",
-                "order": "asc"
+    /// Bucket-creation POST. Ok(true) when this call created the bucket,
+    /// Ok(false) when it already existed (409). Network errors, 429 and 5xx
+    /// are retried; other rejections are final.
+    async fn post_bucket(&self, body: &serde_json::Value) -> Result<bool, String> {
+        http::with_backoff_if(http::RETRY_ATTEMPTS, || self.post_bucket_once(body), |(_, transient)| *transient)
+            .await
+            .map_err(|(e, _)| e)
+    }
+
+    /// One POST; errors carry whether they are worth retrying. When the
+    /// response is lost the destination is checked again, since the request
+    /// may still have landed.
+    async fn post_bucket_once(&self, body: &serde_json::Value) -> Result<bool, (String, bool)> {
+        let bucket_id = body["id"].as_str().unwrap_or_default();
+        let sent = self
+            .client
+            .post(format!("{}/storage/v1/bucket", self.dest_url))
+            .header("Authorization", format!("Bearer {}", self.dest_key))
+            .json(body)
+            .send()
+            .await
+            .inspect(|r| self.rate_limits.observe(r));
+        let res = match sent {
+            Ok(res) => res,
+            Err(e) => {
+                return match self.list_dest_buckets().await {
+                    Ok(buckets) if buckets.iter().any(|b| b.id == bucket_id) => Ok(true),
+                    _ => Err((format!("Failed to create bucket {}: {}", bucket_id, e), true)),
+                };
             }
+        };
+        if res.status().is_success() {
+            return Ok(true);
+        }
+        let error = SupabaseError::from_response(res).await;
+        if error.is_conflict() {
+            Ok(false)
+        } else {
+            Err((format!("Failed to create bucket {}: {}", bucket_id, error), error.is_transient()))
+        }
+    }
+
+    /// Creates or updates the destination bucket with the source bucket's
+    /// public flag, size limit and allowed MIME types. Returns true if created.
+    pub async fn mirror_bucket(&self, bucket: &Bucket, exists_on_dest: bool) -> Result<bool, String> {
+        let dest_id = self.dest_bucket(&bucket.id);
+        let settings = serde_json::json!({
+            "public": bucket.public,
+            "file_size_limit": bucket.file_size_limit,
+            "allowed_mime_types": bucket.allowed_mime_types,
         });
+        if !exists_on_dest {
+            let mut body = settings.clone();
+            body["id"] = dest_id.into();
+            body["name"] = if dest_id == bucket.id { bucket.name.as_str() } else { dest_id }.into();
+            if self.post_bucket(&body).await? {
+                return Ok(true);
+            }
+            // Created since the listing was taken: fall through and update its settings
+        }
 
+        http::with_backoff(http::RETRY_ATTEMPTS, || async {
+            let res = self
+                .client
+                .put(format!("{}/storage/v1/bucket/{}", self.dest_url, encode_path(dest_id)))
+                .header("Authorization", format!("Bearer {}", self.dest_key))
+                .json(&settings)
+                .send()
+                .await
+                .inspect(|r| self.rate_limits.observe(r))
+                .map_err(|e| e.to_string())?;
+            if !res.status().is_success() {
+                return Err(format!("Failed to mirror bucket {}: {}", dest_id, SupabaseError::from_response(res).await));
+            }
+            Ok(())
+        })
+        .await?;
+        Ok(false)
+    }
+
+    /// Delete an (empty) bucket on destination
+    pub async fn delete_dest_bucket(&self, bucket_id: &str) -> Result<(), String> {
+        let url = format!("{}/storage/v1/bucket/{}", self.dest_url, encode_path(bucket_id));
         let res = self
             .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.source_key))
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", self.dest_key))
+            .send()
+            .await
+            .inspect(|r| self.rate_limits.observe(r))
+            .map_err(|e| e.to_string())?;
+
+        if !res.status().is_success() {
+            return Err(format!("Failed to delete bucket {}: {}", bucket_id, SupabaseError::from_response(res).await));
+        }
+        Ok(())
+    }
+
+    /// Delete objects on destination (batched by the storage API)
+    pub async fn delete_dest_objects(&self, bucket_id: &str, object_names: &[String]) -> Result<(), String> {
+        let url = format!("{}/storage/v1/object/{}", self.dest_url, encode_path(bucket_id));
+        let body = serde_json::json!({ "prefixes": object_names });
+
+        let res = self
+            .client
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", self.dest_key))
             .json(&body)
             .send()
             .await
+            .inspect(|r| self.rate_limits.observe(r))
             .map_err(|e| e.to_string())?;
 
+        if !res.status().is_success() {
+            return Err(format!("Failed to delete objects in {}: {}", bucket_id, SupabaseError::from_response(res).await));
+        }
+        Ok(())
+    }
+
+    /// Lists source objects, reusing a listing younger than the cache TTL
+    /// so plan and transfer phases of one run enumerate each bucket once
+    pub async fn list_objects(&self, bucket_id: &str) -> Result<Vec<StorageObject>, String> {
+        if let Some((listed_at, objects)) = self.listing_cache.lock().unwrap().get(bucket_id) {
+            if listed_at.elapsed() < LISTING_CACHE_TTL {
+                return Ok(objects.clone());
+            }
+        }
+
+        let objects = self
+            .list_objects_at(&self.source_url, &self.source_key, bucket_id)
+            .await?;
+        self.listing_cache
+            .lock()
+            .unwrap()
+            .insert(bucket_id.to_string(), (Instant::now(), objects.clone()));
+        Ok(objects)
+    }
+
+    /// Drops cached listings (one bucket, or all when None)
+    pub fn invalidate_listing(&self, bucket_id: Option<&str>) {
+        let mut cache = self.listing_cache.lock().unwrap();
+        match bucket_id {
+            Some(id) => {
+                cache.remove(id);
+            }
+            None => cache.clear(),
+        }
+    }
+
+    /// Whether a destination bucket holds nothing, from a single one-entry list request
+    pub async fn dest_bucket_is_empty(&self, bucket_id: &str) -> Result<bool, String> {
+        if self.dest_s3.is_some() {
+            return Ok(self.list_dest_objects(bucket_id).await?.is_empty());
+        }
+        let url = format!("{}/storage/v1/object/list/{}", self.dest_url, encode_path(bucket_id));
+        let res = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.dest_key))
+            .json(&serde_json::json!({ "prefix": "", "limit": 1, "offset": 0 }))
+            .send()
+            .await
+            .inspect(|r| self.rate_limits.observe(r))
+            .map_err(|e| e.to_string())?;
         if !res.status().is_success() {
             return Err(format!(
                 "Failed to list objects in {}: {}",
                 bucket_id,
-                res.status()
+                SupabaseError::from_response(res).await
             ));
         }
+        let page = res.json::<Vec<serde_json::Value>>().await.map_err(|e| e.to_string())?;
+        Ok(page.is_empty())
+    }
+
+    pub async fn list_dest_objects(&self, bucket_id: &str) -> Result<Vec<StorageObject>, String> {
+        self.list_objects_at(&self.dest_url, &self.dest_key, bucket_id).await
+    }
+
+    /// Every object in the bucket under its full key, at any depth. The REST
+    /// API lists one folder level at a time, so its folder placeholders are
+    /// descended into and left out, matching the flat S3 listing.
+    async fn list_objects_at(&self, base_url: &str, key: &str, bucket_id: &str) -> Result<Vec<StorageObject>, String> {
+        if let Some(s3) = self.s3_for(base_url) {
+            return s3.list_objects(&self.client, bucket_id, self.performance.list_page_size).await;
+        }
+
+        let mut objects = vec![];
+        let mut folders = vec![String::new()];
+        while let Some(prefix) = folders.pop() {
+            for entry in self.list_folder(base_url, key, bucket_id, &prefix).await? {
+                let name = format!("{}{}", prefix, entry.name);
+                match entry.metadata {
+                    None => folders.push(format!("{}/", name)),
+                    Some(_) => objects.push(StorageObject { name, ..entry }),
+                }
+            }
+        }
+        objects.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(objects)
+    }
+
+    /// One folder level of a REST listing: objects and folder placeholders
+    /// with names relative to `prefix`
+    async fn list_folder(&self, base_url: &str, key: &str, bucket_id: &str, prefix: &str) -> Result<Vec<StorageObject>, String> {
+        let url = format!("{}/storage/v1/object/list/{}", base_url, encode_path(bucket_id));
+        let limit = self.performance.list_page_size;
+        let mut objects = vec![];
+
+        loop {
+            // Supabase list objects is a POST with prefix/limit/offset
+            let body = serde_json::json!({
+                "prefix": prefix,
+                "limit": limit,
+                "offset": objects.len(),
+                "sortBy": {
+                    "column": "name",
+                    "order": "asc"
+                }
+            });
+
+            let res = self
+                .client
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", key))
+                .json(&body)
+                .send()
+                .await
+                .inspect(|r| self.rate_limits.observe(r))
+                .map_err(|e| e.to_string())?;
 
-        res.json::<Vec<StorageObject>>()
+            if !res.status().is_success() {
+                return Err(format!(
+                    "Failed to list objects in {}: {}",
+                    bucket_id,
+                    SupabaseError::from_response(res).await
+                ));
+            }
+
+            let page = res
+                .json::<Vec<StorageObject>>()
+                .await
+                .map_err(|e| e.to_string())?;
+            let last_page = page.len() < limit;
+            objects.extend(page);
+            if last_page {
+                return Ok(objects);
+            }
+        }
+    }
+
+    /// Download object from source bucket
+    pub async fn download_object(&self, bucket_id: &str, object_name: &str) -> Result<Vec<u8>, String> {
+        self.fetch_object(&self.source_url, &self.source_key, bucket_id, object_name).await
+    }
+
+    /// HEAD check for an object on the source (retries of stale failures)
+    pub async fn source_object_exists(&self, bucket_id: &str, object_name: &str) -> Result<bool, String> {
+        let url = object_url(&self.source_url, bucket_id, object_name);
+        let res = self
+            .client
+            .head(&url)
+            .header("Authorization", format!("Bearer {}", self.source_key))
+            .send()
             .await
-            .map_err(|e| e.to_string())
+            .inspect(|r| self.rate_limits.observe(r))
+            .map_err(|e| e.to_string())?;
+
+        match res.status().as_u16() {
+            200..=299 => Ok(true),
+            // Storage reports missing objects as 400 or 404 depending on version
+            400 | 404 => Ok(false),
+            _ => Err(format!("Existence check failed: {}", SupabaseError::from_response(res).await)),
+        }
+    }
+
+    /// Streams a source object to `path` without buffering it in memory.
+    /// Writes to `<path>.part` and renames when complete. Returns the byte count.
+    pub async fn download_object_to_file(&self, bucket_id: &str, object_name: &str, path: &std::path::Path) -> Result<u64, String> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut response = self
+            .open_object(&self.source_url, &self.source_key, bucket_id, object_name)
+            .await?;
+        let part = path.with_extension(match path.extension() {
+            Some(ext) => format!("{}.part", ext.to_string_lossy()),
+            None => "part".to_string(),
+        });
+        let mut file = tokio::fs::File::create(&part).await.map_err(|e| e.to_string())?;
+        let mut size = 0u64;
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            self.throttle.consume(chunk.len()).await;
+            size += chunk.len() as u64;
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+        }
+        file.flush().await.map_err(|e| e.to_string())?;
+        drop(file);
+        tokio::fs::rename(&part, path).await.map_err(|e| e.to_string())?;
+        Ok(size)
+    }
+
+    /// Download object from destination bucket (read-back checks)
+    pub async fn download_dest_object(&self, bucket_id: &str, object_name: &str) -> Result<Vec<u8>, String> {
+        self.fetch_object(&self.dest_url, &self.dest_key, bucket_id, object_name).await
     }
+
+    /// Content-type a destination object is served with, for listings (S3)
+    /// that don't carry it. Only the first byte is requested.
+    pub async fn dest_content_type(&self, bucket_id: &str, object_name: &str) -> Result<Option<String>, String> {
+        let response = self
+            .object_request(&self.dest_url, &self.dest_key, bucket_id, object_name)?
+            .header("Range", "bytes=0-0")
+            .send()
+            .await
+            .inspect(|r| self.rate_limits.observe(r))
+            .map_err(|e| format!("Download failed: {}", e))?;
+        // 416: an empty object, which still has its type
+        if !response.status().is_success() && response.status().as_u16() != 416 {
+            return Err(format!("Download failed: {}", SupabaseError::from_response(response).await));
+        }
+        Ok(response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string))
+    }
+
+    /// First `bytes` of a destination object (for magic-byte sniffing)
+    pub async fn read_dest_head(&self, bucket_id: &str, object_name: &str, bytes: u64) -> Result<Vec<u8>, String> {
+        let mut response = self
+            .object_request(&self.dest_url, &self.dest_key, bucket_id, object_name)?
+            .header("Range", format!("bytes=0-{}", bytes.max(1) - 1))
+            .send()
+            .await
+            .inspect(|r| self.rate_limits.observe(r))
+            .map_err(|e| format!("Download failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Download failed: {}", SupabaseError::from_response(response).await));
+        }
+
+        // Servers that ignore Range send everything; stop once the head is in
+        let mut data = vec![];
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            data.extend_from_slice(&chunk);
+            if data.len() as u64 >= bytes {
+                data.truncate(bytes as usize);
+                break;
+            }
+        }
+        Ok(data)
+    }
+
+    /// Streams a source object through SHA-256 without buffering it.
+    /// Returns (size, lowercase hex digest).
+    pub async fn hash_object(&self, bucket_id: &str, object_name: &str) -> Result<(u64, String), String> {
+        let mut response = self
+            .open_object(&self.source_url, &self.source_key, bucket_id, object_name)
+            .await?;
+
+        let mut hasher = Sha256::new();
+        let mut size = 0u64;
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            self.throttle.consume(chunk.len()).await;
+            size += chunk.len() as u64;
+            hasher.update(&chunk);
+        }
+        Ok((size, hex::encode(hasher.finalize())))
+    }
+
+    async fn open_object(&self, base_url: &str, key: &str, bucket_id: &str, object_name: &str) -> Result<reqwest::Response, String> {
+        let response = self
+            .object_request(base_url, key, bucket_id, object_name)?
+            .send()
+            .await
+            .inspect(|r| self.rate_limits.observe(r))
+            .map_err(|e| format!("Download failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Download failed: {}", SupabaseError::from_response(response).await));
+        }
+        Ok(response)
+    }
+
+    async fn fetch_object(&self, base_url: &str, key: &str, bucket_id: &str, object_name: &str) -> Result<Vec<u8>, String> {
+        let response = self.open_object(base_url, key, bucket_id, object_name).await?;
+        self.read_body(response).await
+    }
+
+    async fn read_body(&self, mut response: reqwest::Response) -> Result<Vec<u8>, String> {
+        let mut data = vec![];
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            self.throttle.consume(chunk.len()).await;
+            data.extend_from_slice(&chunk);
+        }
+        Ok(data)
+    }
+
+    /// Lists a source bucket and downloads the first `sample_bytes` of one
+    /// object, telling "can list but can't read" apart from a working source
+    pub async fn test_source_read(&self, bucket_id: &str, sample_bytes: u64) -> ReadProbe {
+        let mut probe = ReadProbe {
+            ok: false,
+            object: None,
+            sampled_bytes: 0,
+            content_type: None,
+            error: None,
+        };
+
+        let objects = match self.list_objects(bucket_id).await {
+            Ok(objects) => objects,
+            Err(e) => {
+                probe.error = Some(format!("Cannot list bucket {}: {}", bucket_id, e));
+                return probe;
+            }
+        };
+        // Folder placeholders carry no metadata
+        let Some(object) = objects.iter().find(|o| o.metadata.is_some()) else {
+            probe.error = Some(format!("Bucket {} has no objects to read.", bucket_id));
+            return probe;
+        };
+        probe.object = Some(object.name.clone());
+
+        let response = match self
+            .object_request(&self.source_url, &self.source_key, bucket_id, &object.name)
+            .map(|r| r.header("Range", format!("bytes=0-{}", sample_bytes.max(1) - 1)))
+        {
+            Ok(request) => request.send().await.inspect(|r| self.rate_limits.observe(r)).map_err(|e| format!("Read request failed: {}", e)),
+            Err(e) => Err(e),
+        };
+        let mut response = match response {
+            Ok(r) if r.status().is_success() => r,
+            Ok(r) => {
+                let status = r.status().as_u16();
+                let error = SupabaseError::from_response(r).await;
+                probe.error = Some(if status == 401 || status == 403 || status == 400 {
+                    format!("Source key can list {} but cannot read its objects: {}", bucket_id, error)
+                } else {
+                    format!("Reading {} failed: {}", object.name, error)
+                });
+                return probe;
+            }
+            Err(e) => {
+                probe.error = Some(e);
+                return probe;
+            }
+        };
+
+        probe.content_type = response
+            .headers()
+            .get("Content-Type")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        // Servers that ignore Range send everything; stop once the sample is in
+        loop {
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    probe.sampled_bytes += chunk.len() as u64;
+                    if probe.sampled_bytes >= sample_bytes {
+                        probe.sampled_bytes = sample_bytes;
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    probe.error = Some(format!("Read of {} interrupted: {}", object.name, e));
+                    return probe;
+                }
+            }
+        }
+        probe.ok = true;
+        probe
+    }
+
+    /// Compares content length plus the first and last `sample_bytes` of an
+    /// object on both sides. Cheap probabilistic check for very large objects.
+    pub async fn sample_verify(&self, bucket_id: &str, object_name: &str, sample_bytes: u64) -> Result<SampleCheck, String> {
+        let (src_head, src_len) = self
+            .fetch_range(&self.source_url, &self.source_key, bucket_id, object_name, SampleRange::Head(sample_bytes))
+            .await?;
+        let dest_bucket = self.dest_bucket(bucket_id);
+        let (dst_head, dst_len) = self
+            .fetch_range(&self.dest_url, &self.dest_key, dest_bucket, object_name, SampleRange::Head(sample_bytes))
+            .await?;
+
+        // Small objects are covered entirely by the head sample
+        let mut sampled_match = src_head == dst_head;
+        if sampled_match && src_len == dst_len && src_len > sample_bytes {
+            let (src_tail, _) = self
+                .fetch_range(&self.source_url, &self.source_key, bucket_id, object_name, SampleRange::Tail(sample_bytes))
+                .await?;
+            let (dst_tail, _) = self
+                .fetch_range(&self.dest_url, &self.dest_key, dest_bucket, object_name, SampleRange::Tail(sample_bytes))
+                .await?;
+            sampled_match = src_tail == dst_tail;
+        }
+
+        Ok(SampleCheck {
+            key: object_name.to_string(),
+            content_length_match: src_len == dst_len,
+            sampled_match,
+            source_length: src_len,
+            dest_length: dst_len,
+            error: None,
+        })
+    }
+
+    /// Fetches part of an object and returns it with the object's total size.
+    /// Servers that ignore `Range` send the whole body; it's sliced locally.
+    async fn fetch_range(
+        &self,
+        base_url: &str,
+        key: &str,
+        bucket_id: &str,
+        object_name: &str,
+        range: SampleRange,
+    ) -> Result<(Vec<u8>, u64), String> {
+        let header = match range {
+            SampleRange::Head(n) => format!("bytes=0-{}", n.max(1) - 1),
+            SampleRange::Tail(n) => format!("bytes=-{}", n.max(1)),
+        };
+        let mut response = self
+            .object_request(base_url, key, bucket_id, object_name)?
+            .header("Range", header)
+            .send()
+            .await
+            .inspect(|r| self.rate_limits.observe(r))
+            .map_err(|e| format!("Range request failed: {}", e))?;
+
+        let status = response.status().as_u16();
+        if status == 416 {
+            // Unsatisfiable range: the object is empty
+            return Ok((vec![], 0));
+        }
+        if !response.status().is_success() {
+            return Err(format!("Range request failed: {}", SupabaseError::from_response(response).await));
+        }
+
+        // "bytes 0-1023/123456" -> 123456
+        let total = response
+            .headers()
+            .get("Content-Range")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.rsplit('/').next())
+            .and_then(|v| v.parse::<u64>().ok());
+
+        let mut data = vec![];
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            self.throttle.consume(chunk.len()).await;
+            data.extend_from_slice(&chunk);
+        }
+
+        match (status, total) {
+            (206, Some(total)) => Ok((data, total)),
+            _ => {
+                let total = data.len() as u64;
+                let slice = match range {
+                    SampleRange::Head(n) => data[..data.len().min(n as usize)].to_vec(),
+                    SampleRange::Tail(n) => data[data.len().saturating_sub(n as usize)..].to_vec(),
+                };
+                Ok((slice, total))
+            }
+        }
+    }
+
     /// Upload object to destination bucket
     pub async fn upload_object(
         &self,
         bucket_id: &str,
         object_name: &str,
         data: Vec<u8>,
+        content_type: &str,
+    ) -> Result<(), String> {
+        self.put_object(bucket_id, object_name, data, content_type, false).await
+    }
+
+    /// Upload that replaces an existing object instead of failing
+    pub async fn upsert_object(
+        &self,
+        bucket_id: &str,
+        object_name: &str,
+        data: Vec<u8>,
+        content_type: &str,
     ) -> Result<(), String> {
+        self.put_object(bucket_id, object_name, data, content_type, true).await
+    }
+
+    /// Signed upload URL for one destination object
+    /// (`POST /storage/v1/object/upload/sign/...`); the PUT to it needs no key
+    pub async fn create_signed_upload_url(&self, bucket_id: &str, object_name: &str, upsert: bool) -> Result<String, SupabaseError> {
         let url = format!(
-            "{}/storage/v1/object/{}/{}", 
-            self.dest_url, bucket_id, object_name
+            "{}/storage/v1/object/upload/sign/{}/{}",
+            self.dest_url,
+            encode_path(bucket_id),
+            encode_path(object_name)
         );
-        
-        let response = self.client
+        let response = self
+            .client
             .post(&url)
             .header("Authorization", format!("Bearer {}", self.dest_key))
+            .header("apikey", &self.dest_key)
+            .header("x-upsert", upsert.to_string())
+            .send()
+            .await
+            .inspect(|r| self.rate_limits.observe(r))
+            .map_err(|e| SupabaseError { status: 0, code: None, message: e.to_string() })?;
+        if !response.status().is_success() {
+            return Err(SupabaseError::from_response(response).await);
+        }
+        let body: serde_json::Value = response.json().await.map_err(|e| SupabaseError { status: 0, code: None, message: e.to_string() })?;
+        match body["url"].as_str() {
+            Some(path) => Ok(format!("{}/storage/v1{}", self.dest_url, path)),
+            None => Err(SupabaseError { status: 0, code: None, message: "No url in signed upload response".to_string() }),
+        }
+    }
+
+    /// Where to PUT an upload when `performance.signed_uploads` is on. None
+    /// falls back to the standard POST: for this object when signing fails,
+    /// for the rest of the run when the destination has no signing endpoint.
+    async fn signed_upload_target(&self, bucket_id: &str, object_name: &str, upsert: bool) -> Option<String> {
+        if !self.performance.signed_uploads || self.signed_uploads_unsupported.load(Ordering::Relaxed) {
+            return None;
+        }
+        match self.create_signed_upload_url(bucket_id, object_name, upsert).await {
+            Ok(url) => Some(url),
+            Err(e) => {
+                if matches!(e.status, 404 | 405 | 501) {
+                    self.signed_uploads_unsupported.store(true, Ordering::Relaxed);
+                }
+                None
+            }
+        }
+    }
+
+    async fn put_object(
+        &self,
+        bucket_id: &str,
+        object_name: &str,
+        data: Vec<u8>,
+        content_type: &str,
+        upsert: bool,
+    ) -> Result<(), String> {
+        // Stream in chunks so the bandwidth cap also applies to uploads
+        let total = data.len();
+        let chunk_size = self.performance.upload_chunk_bytes as usize;
+        let chunks: Vec<Vec<u8>> = data.chunks(chunk_size.max(1)).map(|c| c.to_vec()).collect();
+        let throttle = self.throttle.clone();
+        let body = futures::stream::iter(chunks).then(move |chunk| {
+            let throttle = throttle.clone();
+            async move {
+                throttle.consume(chunk.len()).await;
+                Ok::<_, std::io::Error>(chunk)
+            }
+        });
+
+        if self.needs_resumable(total as u64) {
+            return self.send_resumable(bucket_id, object_name, body, total as u64, content_type, upsert).await;
+        }
+        self.send_upload(bucket_id, object_name, reqwest::Body::wrap_stream(body), total as u64, content_type, upsert)
+            .await
+    }
+
+    /// Uploads `bytes` zero bytes as a single POST to find the size limit.
+    /// Ok(false) when the destination rejects the size; the probe object is
+    /// removed after a successful upload.
+    pub async fn probe_upload(&self, bucket_id: &str, object_name: &str, bytes: u64) -> Result<bool, String> {
+        let chunk_size = self.performance.upload_chunk_bytes.max(1);
+        let chunks = (0..bytes.div_ceil(chunk_size))
+            .map(move |i| Ok::<_, std::io::Error>(vec![0u8; chunk_size.min(bytes - i * chunk_size) as usize]));
+        let response = self
+            .client
+            .post(object_url(&self.dest_url, bucket_id, object_name))
+            .header("Authorization", format!("Bearer {}", self.dest_key))
+            .header("x-upsert", "true")
             .header("Content-Type", "application/octet-stream")
-            .body(data)
+            .header("Content-Length", bytes)
+            .body(reqwest::Body::wrap_stream(futures::stream::iter(chunks)))
             .send()
             .await
+            .inspect(|r| self.rate_limits.observe(r))
+            .map_err(|e| format!("Upload probe failed: {}", e))?;
+        if response.status().is_success() {
+            let _ = self.delete_dest_objects(bucket_id, &[object_name.to_string()]).await;
+            return Ok(true);
+        }
+        let error = SupabaseError::from_response(response).await;
+        if error.is_payload_too_large() {
+            Ok(false)
+        } else {
+            Err(format!("Upload probe failed: {}", error))
+        }
+    }
+
+    /// Whether an object of `total` bytes is over the single-POST limit.
+    /// S3 destinations have their own multipart handling and never qualify.
+    fn needs_resumable(&self, total: u64) -> bool {
+        self.dest_s3.is_none() && self.single_upload_limit.is_some_and(|limit| total > limit)
+    }
+
+    /// TUS upload (`/storage/v1/upload/resumable`): creates the upload, then
+    /// PATCHes the body in the fixed-size chunks the endpoint requires
+    async fn send_resumable<S, T, E>(
+        &self,
+        bucket_id: &str,
+        object_name: &str,
+        body: S,
+        total: u64,
+        content_type: &str,
+        upsert: bool,
+    ) -> Result<(), String>
+    where
+        S: futures::Stream<Item = Result<T, E>>,
+        T: AsRef<[u8]>,
+        E: std::fmt::Display,
+    {
+        use base64::Engine;
+        let encode = |v: &str| base64::engine::general_purpose::STANDARD.encode(v);
+        let metadata = format!(
+            "bucketName {},objectName {},contentType {},cacheControl {}",
+            encode(bucket_id),
+            encode(object_name),
+            encode(content_type),
+            encode("3600")
+        );
+        let created = self
+            .client
+            .post(format!("{}/storage/v1/upload/resumable", self.dest_url))
+            .header("Authorization", format!("Bearer {}", self.dest_key))
+            .header("x-upsert", upsert.to_string())
+            .header("Tus-Resumable", "1.0.0")
+            .header("Upload-Length", total)
+            .header("Upload-Metadata", metadata)
+            .send()
+            .await
+            .inspect(|r| self.rate_limits.observe(r))
+            .map_err(|e| format!("Resumable upload failed: {}", e))?;
+        if !created.status().is_success() {
+            return Err(format!("Resumable upload failed: {}", SupabaseError::from_response(created).await));
+        }
+        let location = created
+            .headers()
+            .get("Location")
+            .and_then(|v| v.to_str().ok())
+            .ok_or("Resumable upload failed: no Location in response")?
+            .to_string();
+
+        let mut offset = 0u64;
+        let mut buffer: Vec<u8> = Vec::with_capacity(RESUMABLE_CHUNK_BYTES);
+        let mut body = std::pin::pin!(body);
+        loop {
+            let next = body.next().await.transpose().map_err(|e| format!("Download failed: {}", e))?;
+            if let Some(chunk) = &next {
+                buffer.extend_from_slice(chunk.as_ref());
+            }
+            while buffer.len() >= RESUMABLE_CHUNK_BYTES || (next.is_none() && !buffer.is_empty()) {
+                let rest = buffer.split_off(buffer.len().min(RESUMABLE_CHUNK_BYTES));
+                let part = std::mem::replace(&mut buffer, rest);
+                let len = part.len() as u64;
+                let response = self
+                    .client
+                    .patch(&location)
+                    .header("Authorization", format!("Bearer {}", self.dest_key))
+                    .header("Tus-Resumable", "1.0.0")
+                    .header("Upload-Offset", offset)
+                    .header("Content-Type", "application/offset+octet-stream")
+                    .body(part)
+                    .send()
+                    .await
+                    .inspect(|r| self.rate_limits.observe(r))
+                    .map_err(|e| format!("Resumable upload failed at byte {}: {}", offset, e))?;
+                if !response.status().is_success() {
+                    return Err(format!(
+                        "Resumable upload failed at byte {}: {}",
+                        offset,
+                        SupabaseError::from_response(response).await
+                    ));
+                }
+                offset += len;
+            }
+            if next.is_none() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends an upload body of `total` bytes to the destination
+    async fn send_upload(
+        &self,
+        bucket_id: &str,
+        object_name: &str,
+        body: reqwest::Body,
+        total: u64,
+        content_type: &str,
+        upsert: bool,
+    ) -> Result<(), String> {
+        let url = object_url(&self.dest_url, bucket_id, object_name);
+        let request = match &self.dest_s3 {
+            // S3 PUT always replaces; the precondition makes it fail on an existing key instead
+            Some(s3) if !upsert => s3.put_object(&self.client, bucket_id, object_name)?.header("If-None-Match", "*"),
+            Some(s3) => s3.put_object(&self.client, bucket_id, object_name)?,
+            None => match self.signed_upload_target(bucket_id, object_name, upsert).await {
+                Some(signed) => self.client.put(signed).header("x-upsert", upsert.to_string()),
+                None => self.client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", self.dest_key))
+                    .header("x-upsert", upsert.to_string()),
+            },
+        };
+        let response = request
+            .header("Content-Type", content_type)
+            .header("Content-Length", total)
+            .body(body)
+            .send()
+            .await
+            .inspect(|r| self.rate_limits.observe(r))
             .map_err(|e| format!("Upload failed: {}", e))?;
         
         if !response.status().is_success() {
-            return Err(format!("Upload failed with status: {}", response.status()));
+            return Err(format!("Upload failed: {}", SupabaseError::from_response(response).await));
         }
         
         Ok(())
     }
+
+    /// Copies one object source -> destination.
+    /// Returns the byte count and the content-type it was uploaded with.
+    pub async fn transfer_object(&self, bucket_id: &str, object: &StorageObject) -> Result<(u64, String), String> {
+        self.transfer_object_to(bucket_id, object, &object.name).await
+    }
+
+    /// Copies one object to `dest_name` on the destination. Existing objects
+    /// are replaced unless the collision strategy is Skip.
+    pub async fn transfer_object_to(&self, bucket_id: &str, object: &StorageObject, dest_name: &str) -> Result<(u64, String), String> {
+        let upsert = self.collision != CollisionStrategy::Skip;
+        let response = self.open_object(&self.source_url, &self.source_key, bucket_id, &object.name).await?;
+        let dest_bucket = self.dest_bucket(bucket_id);
+        match response.content_length() {
+            Some(total) if self.performance.streaming_transfers => {
+                self.pipe_object(dest_bucket, object, dest_name, response, total, upsert).await
+            }
+            // Unknown length (or streaming off): the upload needs a Content-Length, so buffer
+            _ => {
+                let data = self.read_body(response).await?;
+                let bytes = data.len() as u64;
+                let content_type = resolve_content_type(object, &data);
+                self.put_object(dest_bucket, dest_name, data, &content_type, upsert).await?;
+                Ok((bytes, content_type))
+            }
+        }
+    }
+
+    /// Pipes a source download straight into the destination upload. The
+    /// upload pulls chunks as it sends them, so each transfer holds only a
+    /// chunk or two in memory whatever the object size; with at most
+    /// `transfer_concurrency` transfers in flight, memory stays bounded.
+    async fn pipe_object(
+        &self,
+        dest_bucket: &str,
+        object: &StorageObject,
+        dest_name: &str,
+        response: reqwest::Response,
+        total: u64,
+        upsert: bool,
+    ) -> Result<(u64, String), String> {
+        let mut source = response.bytes_stream();
+        // The first chunk is enough to sniff a type when metadata and extension don't say
+        let first = source.next().await.transpose().map_err(|e| format!("Download failed: {}", e))?.unwrap_or_default();
+        let content_type = resolve_content_type(object, &first);
+
+        let throttle = self.throttle.clone();
+        let body = futures::stream::once(async move { Ok(first) }).chain(source).then(move |chunk| {
+            let throttle = throttle.clone();
+            async move {
+                if let Ok(chunk) = &chunk {
+                    throttle.consume(chunk.len()).await;
+                }
+                chunk
+            }
+        });
+        if self.needs_resumable(total) {
+            self.send_resumable(dest_bucket, dest_name, body, total, &content_type, upsert).await?;
+        } else {
+            self.send_upload(dest_bucket, dest_name, reqwest::Body::wrap_stream(body), total, &content_type, upsert)
+                .await?;
+        }
+        Ok((total, content_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockServer, Reply, Request};
+
+    fn object(name: &str, size: u64) -> serde_json::Value {
+        serde_json::json!({ "name": name, "id": format!("id-{}", name), "metadata": { "size": size } })
+    }
+
+    #[test]
+    fn magic_bytes_identify_common_types() {
+        let cases: [(&[u8], &str); 5] = [
+            (b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", "image/png"),
+            (b"\xff\xd8\xff\xe0\0\x10JFIF\0", "image/jpeg"),
+            (b"%PDF-1.7\n%\xe2\xe3", "application/pdf"),
+            (b"\x1f\x8b\x08\0\0\0\0\0", "application/gzip"),
+            (b"\0\0\0\x18ftypisom\0\0\x02\0isomiso2", "video/mp4"),
+        ];
+        for (prefix, mime) in cases {
+            assert_eq!(sniff_content_type(prefix), Some(mime));
+        }
+        assert_eq!(sniff_content_type(b"plain words"), None);
+    }
+
+    #[test]
+    fn metadata_then_extension_win_over_sniffing() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        let with = |name: &str, mimetype: Option<&str>| -> StorageObject {
+            serde_json::from_value(serde_json::json!({ "name": name, "id": "1", "metadata": { "size": 1, "mimetype": mimetype } })).unwrap()
+        };
+        assert_eq!(resolve_content_type(&with("a.pdf", Some("image/webp")), png), "image/webp");
+        assert_eq!(resolve_content_type(&with("a.pdf", None), png), "application/pdf");
+        // octet-stream metadata says nothing, so it doesn't block the fallbacks
+        assert_eq!(resolve_content_type(&with("a", Some("application/octet-stream")), png), "image/png");
+        assert_eq!(resolve_content_type(&with("a.unknown", None), b"plain words"), "application/octet-stream");
+    }
+
+    const AWKWARD_KEYS: [&str; 4] = ["reports/Q1 2024.pdf", "a+b=c?.txt", "deep/50%/#1 [draft].md", "фото/ünï 😀.png"];
+
+    #[test]
+    fn encoded_keys_keep_slashes_and_round_trip() {
+        assert_eq!(encode_path("reports/Q1 2024.pdf"), "reports/Q1%202024.pdf");
+        assert_eq!(encode_path("a+b=c?.txt"), "a%2Bb=c%3F.txt");
+        assert_eq!(encode_path("фото/x"), "%D1%84%D0%BE%D1%82%D0%BE/x");
+        for key in AWKWARD_KEYS {
+            let encoded = encode_path(key);
+            assert_eq!(encoded.matches('/').count(), key.matches('/').count(), "{}", encoded);
+            assert!(encoded.is_ascii() && !encoded.contains([' ', '+', '?', '#']), "{}", encoded);
+            assert_eq!(percent_encoding::percent_decode_str(&encoded).decode_utf8().unwrap(), key);
+        }
+    }
+
+    #[tokio::test]
+    async fn awkward_keys_transfer_under_their_encoded_paths() {
+        let source = MockServer::start(|_| Reply::bytes(200, b"hello".to_vec())).await;
+        let dest = MockServer::start(|_| Reply::json(200, serde_json::json!({}))).await;
+        let mirror = StorageMirror::new(Client::new(), &source.url, "key", &dest.url, "key");
+
+        for key in AWKWARD_KEYS {
+            let object: StorageObject = serde_json::from_value(object(key, 5)).unwrap();
+            assert_eq!(mirror.transfer_object("docs", &object).await.unwrap().0, 5);
+        }
+        let paths = |server: &MockServer| server.requests().into_iter().map(|r| r.path).collect::<Vec<_>>();
+        let expected: Vec<_> = AWKWARD_KEYS.iter().map(|k| format!("/storage/v1/object/docs/{}", encode_path(k))).collect();
+        assert_eq!(paths(&source), expected);
+        assert_eq!(paths(&dest), expected);
+    }
+
+    /// Serves `body` for every object GET; honours Range only when asked to
+    fn ranged(body: &'static [u8], honor_range: bool) -> impl Fn(&Request) -> Reply + Send + Sync {
+        move |request| {
+            let total = body.len();
+            let range = request.header("Range").filter(|_| honor_range).and_then(|r| r.strip_prefix("bytes="));
+            let (start, end) = match range {
+                None => return Reply::bytes(200, body.to_vec()),
+                Some(r) if r.starts_with('-') => (total.saturating_sub(r[1..].parse().unwrap()), total - 1),
+                Some(r) => {
+                    let (a, b) = r.split_once('-').unwrap();
+                    (a.parse().unwrap(), b.parse::<usize>().unwrap().min(total - 1))
+                }
+            };
+            Reply::bytes(206, body[start..=end].to_vec())
+                .with_header("Content-Range", &format!("bytes {}-{}/{}", start, end, total))
+        }
+    }
+
+    #[tokio::test]
+    async fn sample_verify_handles_partial_and_ignored_ranges() {
+        let source = MockServer::start(ranged(b"0123456789abcdef", true)).await;
+        let dest = MockServer::start(ranged(b"0123456789abcdef", false)).await;
+        let mirror = StorageMirror::new(Client::new(), &source.url, "key", &dest.url, "key");
+
+        let check = mirror.sample_verify("docs", "a.bin", 4).await.unwrap();
+        assert!(check.content_length_match && check.sampled_match, "{:?}", check);
+        assert_eq!((check.source_length, check.dest_length), (16, 16));
+
+        let ranges: Vec<_> = source.requests().iter().filter_map(|r| r.header("Range").map(str::to_string)).collect();
+        assert_eq!(ranges, vec!["bytes=0-3", "bytes=-4"]);
+    }
+
+    #[tokio::test]
+    async fn sample_verify_catches_tail_difference_through_206() {
+        let source = MockServer::start(ranged(b"0123456789abcdef", true)).await;
+        let dest = MockServer::start(ranged(b"0123456789abcdeX", true)).await;
+        let mirror = StorageMirror::new(Client::new(), &source.url, "key", &dest.url, "key");
+
+        let check = mirror.sample_verify("docs", "a.bin", 4).await.unwrap();
+        assert!(check.content_length_match);
+        assert!(!check.sampled_match);
+    }
+
+    #[tokio::test]
+    async fn unsatisfiable_range_reads_as_empty_object() {
+        let server = MockServer::start(|_| Reply::status(416)).await;
+        let mirror = StorageMirror::new(Client::new(), &server.url, "key", &server.url, "key");
+
+        let (data, total) = mirror
+            .fetch_range(&server.url, "key", "docs", "empty.txt", SampleRange::Head(4))
+            .await
+            .unwrap();
+        assert!(data.is_empty());
+        assert_eq!(total, 0);
+    }
+
+    #[tokio::test]
+    async fn listing_within_ttl_hits_cache_until_invalidated() {
+        let server = MockServer::start(|_| Reply::json(200, serde_json::json!([object("a.txt", 1), object("b.txt", 2)]))).await;
+        let mirror = StorageMirror::new(Client::new(), &server.url, "key", &server.url, "key");
+
+        assert_eq!(mirror.list_objects("docs").await.unwrap().len(), 2);
+        assert_eq!(mirror.list_objects("docs").await.unwrap().len(), 2);
+        assert_eq!(server.count("POST", "/storage/v1/object/list/docs"), 1);
+        let request = &server.requests()[0];
+        assert_eq!(request.header("Authorization"), Some("Bearer key"));
+        assert_eq!(request.json()["prefix"], "");
+
+        mirror.invalidate_listing(Some("docs"));
+        mirror.list_objects("docs").await.unwrap();
+        assert_eq!(server.count("POST", "/storage/v1/object/list/docs"), 2);
+    }
+
+    #[tokio::test]
+    async fn remapped_bucket_is_created_and_filled_under_its_new_id() {
+        let source = MockServer::start(|_| Reply::bytes(200, b"hello".to_vec())).await;
+        let dest = MockServer::start(|_| Reply::json(200, serde_json::json!({}))).await;
+        let remap = BTreeMap::from([("docs".to_string(), "documents".to_string())]);
+        let mirror = StorageMirror::new(Client::new(), &source.url, "key", &dest.url, "key").with_bucket_remap(remap);
+        assert_eq!(mirror.dest_bucket("docs"), "documents");
+        assert_eq!(mirror.dest_bucket("avatars"), "avatars");
+
+        let bucket = Bucket {
+            id: "docs".to_string(),
+            name: "docs".to_string(),
+            public: false,
+            file_size_limit: None,
+            allowed_mime_types: None,
+        };
+        assert!(mirror.mirror_bucket(&bucket, false).await.unwrap());
+        let created = dest.requests()[0].json();
+        assert_eq!((created["id"].as_str(), created["name"].as_str()), (Some("documents"), Some("documents")));
+
+        let object: StorageObject = serde_json::from_value(object("a.txt", 5)).unwrap();
+        mirror.transfer_object_to("docs", &object, "a.txt").await.unwrap();
+        assert_eq!(source.count("GET", "/storage/v1/object/docs/a.txt"), 1);
+        let uploads: Vec<_> = dest.requests().into_iter().skip(1).map(|r| r.path).collect();
+        assert!(!uploads.is_empty() && uploads.iter().all(|p| p.contains("/documents/")), "{:?}", uploads);
+    }
+
+    #[tokio::test]
+    async fn rest_listing_descends_into_folders_like_s3() {
+        let folder = |name: &str| serde_json::json!({ "name": name, "id": null, "metadata": null });
+        let rest = MockServer::start(move |request| {
+            let level = match request.json()["prefix"].as_str().unwrap_or_default() {
+                "" => vec![folder("photos"), object("a.txt", 1)],
+                "photos/" => vec![folder("2024"), object("b.jpg", 2)],
+                "photos/2024/" => vec![object("c.jpg", 3)],
+                _ => vec![],
+            };
+            Reply::json(200, serde_json::Value::Array(level))
+        })
+        .await;
+        let s3 = MockServer::start(|_| {
+            let keys = ["a.txt", "photos/2024/c.jpg", "photos/b.jpg"];
+            let contents: String = keys.iter().map(|k| format!("<Contents><Key>{}</Key><Size>1</Size></Contents>", k)).collect();
+            Reply::bytes(200, format!("<ListBucketResult><IsTruncated>false</IsTruncated>{}</ListBucketResult>", contents).into_bytes())
+        })
+        .await;
+        let credentials = crate::s3::S3Credentials { access_key_id: "id".to_string(), secret_access_key: "secret".to_string() };
+        let config = S3Config { enabled: true, dest: Some(credentials), ..Default::default() };
+        let mirror = StorageMirror::new(Client::new(), &rest.url, "key", &s3.url, "key").with_s3(&config);
+
+        let names = |objects: Vec<StorageObject>| objects.into_iter().map(|o| o.name).collect::<Vec<_>>();
+        let source = names(mirror.list_objects("docs").await.unwrap());
+        assert_eq!(source, ["a.txt", "photos/2024/c.jpg", "photos/b.jpg"]);
+        assert_eq!(source, names(mirror.list_dest_objects("docs").await.unwrap()));
+        assert_eq!(rest.count("POST", "/storage/v1/object/list/docs"), 3);
+    }
+
+    /// A destination that already holds every key: REST refuses without
+    /// x-upsert, S3 refuses under If-None-Match
+    async fn occupied_destination() -> MockServer {
+        MockServer::start(|request| {
+            if request.path.starts_with("/storage/v1/object/upload/sign/") {
+                return Reply::status(404);
+            }
+            let refused = if request.path.starts_with("/storage/v1/s3/") {
+                request.header("If-None-Match") == Some("*")
+            } else {
+                request.header("x-upsert") != Some("true")
+            };
+            match refused {
+                true if request.path.starts_with("/storage/v1/s3/") => Reply::status(412),
+                true => Reply::json(409, serde_json::json!({ "statusCode": "409", "error": "Duplicate", "message": "The resource already exists" })),
+                false => Reply::json(200, serde_json::json!({})),
+            }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn existing_destination_object_is_only_replaced_when_the_strategy_allows() {
+        let source = MockServer::start(|_| Reply::bytes(200, b"hello".to_vec())).await;
+        let object: StorageObject = serde_json::from_value(object("a.txt", 5)).unwrap();
+        for s3 in [false, true] {
+            for (strategy, replaced) in [
+                (CollisionStrategy::Overwrite, true),
+                (CollisionStrategy::Rename, true),
+                (CollisionStrategy::Skip, false),
+            ] {
+                let dest = occupied_destination().await;
+                let credentials = crate::s3::S3Credentials { access_key_id: "id".to_string(), secret_access_key: "secret".to_string() };
+                let config = S3Config { enabled: s3, dest: Some(credentials), ..Default::default() };
+                let mirror = StorageMirror::new(Client::new(), &source.url, "key", &dest.url, "key")
+                    .with_s3(&config)
+                    .with_collision_strategy(strategy);
+
+                let outcome = mirror.transfer_object_to("docs", &object, "a.txt").await;
+                assert_eq!(outcome.is_ok(), replaced, "{:?} over S3={}: {:?}", strategy, s3, outcome);
+                let upload = dest.requests().into_iter().find(|r| !r.path.contains("/upload/sign/")).unwrap();
+                assert_eq!(upload.path.starts_with("/storage/v1/s3/"), s3);
+            }
+        }
+    }
+
+    /// Bucket endpoint whose POSTs get the given replies in turn; the
+    /// listing never shows the bucket
+    async fn bucket_endpoint(mut replies: Vec<Reply>) -> MockServer {
+        replies.reverse();
+        let replies = std::sync::Mutex::new(replies);
+        MockServer::start(move |request| match request.method.as_str() {
+            "POST" => replies.lock().unwrap().pop().unwrap_or_else(|| Reply::status(500)),
+            _ => Reply::json(200, serde_json::json!([])),
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn bucket_created_behind_a_dropped_response_counts_as_created() {
+        let conflict = serde_json::json!({ "statusCode": "409", "error": "Duplicate", "message": "The resource already exists" });
+        let dest = bucket_endpoint(vec![Reply::Drop, Reply::json(400, conflict)]).await;
+        let mirror = StorageMirror::new(Client::new(), &dest.url, "key", &dest.url, "key");
+
+        mirror.create_dest_bucket("docs", false).await.unwrap();
+        assert_eq!(dest.count("POST", "/storage/v1/bucket"), 2);
+        assert_eq!(dest.count("GET", "/storage/v1/bucket"), 1);
+    }
+
+    #[tokio::test]
+    async fn bucket_creation_retries_only_transient_failures() {
+        let dest = bucket_endpoint(vec![Reply::status(503), Reply::status(429), Reply::json(200, serde_json::json!({}))]).await;
+        let mirror = StorageMirror::new(Client::new(), &dest.url, "key", &dest.url, "key");
+        mirror.create_dest_bucket("docs", false).await.unwrap();
+        assert_eq!(dest.count("POST", "/storage/v1/bucket"), 3);
+
+        let invalid = serde_json::json!({ "statusCode": "400", "error": "Invalid", "message": "Bucket name invalid" });
+        let dest = bucket_endpoint(vec![Reply::json(400, invalid), Reply::json(200, serde_json::json!({}))]).await;
+        let mirror = StorageMirror::new(Client::new(), &dest.url, "key", &dest.url, "key");
+        let error = mirror.create_dest_bucket("docs", false).await.unwrap_err();
+        assert!(error.contains("Bucket name invalid"), "{}", error);
+        assert_eq!(dest.count("POST", "/storage/v1/bucket"), 1);
+    }
 }
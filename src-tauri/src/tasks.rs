@@ -0,0 +1,11 @@
+/// Runs blocking filesystem or CPU work (zipping, extraction, sleeps) on the
+/// blocking pool, so the async runtime keeps delivering `log`/`progress` events
+pub async fn run_blocking<T, F>(work: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| format!("Background task failed: {}", e))?
+}